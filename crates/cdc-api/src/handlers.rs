@@ -1,15 +1,19 @@
 use axum::{extract::State, response::IntoResponse};
-use cdc_config_store::UnifiedConfigStore;
-use cdc_core::{FlowOrchestrator, Registry};
+use cdc_config_store::{FlowLeases, UnifiedConfigStore};
+use cdc_core::{FlowOrchestrator, IngestHub, Registry};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::ApiResponse;
 
+pub mod bundle;
 pub mod connectors;
 pub mod destinations;
 pub mod flows;
+pub mod history;
+pub mod ingest;
+pub mod types;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -17,6 +21,10 @@ pub struct AppState {
     pub config_store: Arc<RwLock<UnifiedConfigStore>>,
     pub orchestrator: Arc<FlowOrchestrator>,
     pub registry: Arc<Registry>,
+    /// Set when flows are leased across instances sharing the config database
+    pub leases: Option<FlowLeases>,
+    /// Queues of running `http` connectors, fed by `POST /api/ingest/{flow}`
+    pub ingest: IngestHub,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
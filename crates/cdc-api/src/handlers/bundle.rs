@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use cdc_config_store::{BundleFormat, ConfigBundle, ConflictPolicy, ImportReport};
+use cdc_core::{contains_masked, restore_masked};
+use serde::Deserialize;
+
+use crate::{handlers::AppState, ApiResponse};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportQuery {
+    /// `yaml` (default) or `json`
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportQuery {
+    /// Validate and report without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// What to do with entries that already exist
+    #[serde(default)]
+    pub on_conflict: ConflictPolicy,
+
+    /// Bundle format; taken from the Content-Type header if omitted
+    pub format: Option<String>,
+}
+
+pub async fn export_config(
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    let format = match query.format.as_deref().map(str::parse::<BundleFormat>) {
+        Some(Ok(format)) => format,
+        Some(Err(e)) => return ApiResponse::<()>::bad_request(e.to_string()).into_response(),
+        None => BundleFormat::default(),
+    };
+
+    let mut bundle = match state.config_store.read().await.export().await {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to export config: {:#}", e))
+                .into_response()
+        }
+    };
+
+    // Same masking as the regular getters; secret references stay visible
+    for entry in &mut bundle.connectors {
+        entry.config = state
+            .registry
+            .redact_connector_config(&entry.connector_type, &entry.config);
+    }
+    for entry in &mut bundle.destinations {
+        entry.config = state
+            .registry
+            .redact_destination_config(&entry.destination_type, &entry.config);
+    }
+
+    match bundle.render(format) {
+        Ok(body) => ([(header::CONTENT_TYPE, format.content_type())], body).into_response(),
+        Err(e) => ApiResponse::<()>::internal_error(format!("Failed to export config: {}", e))
+            .into_response(),
+    }
+}
+
+pub async fn import_config(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> ApiResponse<ImportReport> {
+    let format = match query.format.as_deref() {
+        Some(format) => match format.parse::<BundleFormat>() {
+            Ok(format) => format,
+            Err(e) => return ApiResponse::bad_request(e.to_string()),
+        },
+        None => {
+            let is_json = headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.contains("json"));
+            if is_json {
+                BundleFormat::Json
+            } else {
+                BundleFormat::Yaml
+            }
+        }
+    };
+
+    let mut bundle = match ConfigBundle::parse(&body, format) {
+        Ok(bundle) => bundle,
+        Err(e) => return ApiResponse::bad_request(format!("{:#}", e)),
+    };
+
+    let mut store = state.config_store.write().await;
+
+    // A bundle exported through the API carries masked secrets; keep the stored ones
+    let mut masked = Vec::new();
+    for entry in &mut bundle.connectors {
+        let existing = match store.get_connector(&entry.name).await {
+            Ok(existing) => existing,
+            Err(e) => {
+                return ApiResponse::internal_error(format!("Failed to load connector: {:#}", e))
+            }
+        };
+        if let Some(existing) = existing {
+            let secret_fields = state
+                .registry
+                .connector_secret_fields(&entry.connector_type);
+            restore_masked(&mut entry.config, &existing.config, &secret_fields);
+        }
+        if contains_masked(&entry.config) {
+            masked.push(format!("connector '{}'", entry.name));
+        }
+    }
+    for entry in &mut bundle.destinations {
+        let existing = match store.get_destination(&entry.name).await {
+            Ok(existing) => existing,
+            Err(e) => {
+                return ApiResponse::internal_error(format!("Failed to load destination: {:#}", e))
+            }
+        };
+        if let Some(existing) = existing {
+            let secret_fields = state
+                .registry
+                .destination_secret_fields(&entry.destination_type);
+            restore_masked(&mut entry.config, &existing.config, &secret_fields);
+        }
+        if contains_masked(&entry.config) {
+            masked.push(format!("destination '{}'", entry.name));
+        }
+    }
+    if !masked.is_empty() {
+        return ApiResponse::error(
+            "BAD_REQUEST",
+            "Bundle contains masked secrets with no stored value; supply the real values or secret references",
+            masked,
+        );
+    }
+
+    let report = match store.import(bundle, query.on_conflict, query.dry_run).await {
+        Ok(report) => report,
+        Err(e) => return ApiResponse::internal_error(format!("Failed to import config: {:#}", e)),
+    };
+
+    if !report.errors.is_empty() {
+        let code = if report.has_conflicts() {
+            "CONFLICT"
+        } else {
+            "BAD_REQUEST"
+        };
+        let errors = report.errors.clone();
+        return ApiResponse {
+            data: Some(report),
+            message: "Import rejected".to_string(),
+            code: code.to_string(),
+            errors,
+        };
+    }
+
+    let message = if report.dry_run {
+        "Import validated (dry run)"
+    } else {
+        "Config imported successfully"
+    };
+    ApiResponse::success(report, message)
+}
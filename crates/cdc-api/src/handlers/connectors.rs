@@ -4,15 +4,29 @@ use axum::{
     Json,
 };
 use cdc_config_store::ConnectorConfigEntry;
+use cdc_core::{contains_masked, is_masked_copy, restore_masked};
 
 use crate::{handlers::AppState, ApiResponse};
 
+const MASKED_SECRET_ERROR: &str =
+    "Config contains masked secrets with no stored value; supply the real values or secret references";
+
 pub async fn list_connectors(State(state): State<AppState>) -> impl IntoResponse {
     let store = state.config_store.read().await;
-    let connectors = store
-        .list_connectors()
-        .await
+    let connectors = match store.list_connectors().await {
+        Ok(connectors) => connectors,
+        Err(e) => {
+            return ApiResponse::internal_error(format!("Failed to list connectors: {:#}", e))
+        }
+    };
+    let connectors = connectors
         .into_iter()
+        .map(|mut entry| {
+            entry.config = state
+                .registry
+                .redact_connector_config(&entry.connector_type, &entry.config);
+            entry
+        })
         .collect::<Vec<_>>();
     ApiResponse::success(connectors, "Connectors retrieved successfully")
 }
@@ -23,10 +37,14 @@ pub async fn get_connector(
 ) -> impl IntoResponse {
     let store = state.config_store.read().await;
 
-    let connector = match store.get_connector(&name).await {
-        Some(c) => c,
-        None => return ApiResponse::not_found("Connector"),
+    let mut connector = match store.get_connector(&name).await {
+        Ok(Some(c)) => c,
+        Ok(None) => return ApiResponse::not_found("Connector"),
+        Err(e) => return ApiResponse::internal_error(format!("Failed to load connector: {:#}", e)),
     };
+    connector.config = state
+        .registry
+        .redact_connector_config(&connector.connector_type, &connector.config);
 
     ApiResponse::success(connector, "Connector retrieved successfully")
 }
@@ -35,6 +53,10 @@ pub async fn create_connector(
     State(state): State<AppState>,
     Json(entry): Json<ConnectorConfigEntry>,
 ) -> ApiResponse<()> {
+    if contains_masked(&entry.config) {
+        return ApiResponse::<()>::bad_request(MASKED_SECRET_ERROR);
+    }
+
     let mut store = state.config_store.write().await;
 
     match store.add_connector(entry).await {
@@ -46,10 +68,26 @@ pub async fn create_connector(
 pub async fn update_connector(
     State(state): State<AppState>,
     Path(name): Path<String>,
-    Json(entry): Json<ConnectorConfigEntry>,
+    Json(mut entry): Json<ConnectorConfigEntry>,
 ) -> ApiResponse<()> {
     let mut store = state.config_store.write().await;
 
+    // Keep stored secrets the client sent back masked
+    let existing = match store.get_connector(&name).await {
+        Ok(existing) => existing,
+        Err(e) => return ApiResponse::internal_error(format!("Failed to load connector: {:#}", e)),
+    };
+    if let Some(existing) = existing {
+        let secret_fields = state
+            .registry
+            .connector_secret_fields(&entry.connector_type);
+        restore_masked(&mut entry.config, &existing.config, &secret_fields);
+    }
+    // A masked URL password whose host changed has nothing to restore from
+    if contains_masked(&entry.config) {
+        return ApiResponse::<()>::bad_request(MASKED_SECRET_ERROR);
+    }
+
     match store.update_connector(&name, entry).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Connector updated successfully"),
         Err(_) => ApiResponse::<()>::not_found("Connector"),
@@ -62,10 +100,16 @@ pub async fn delete_connector(
 ) -> ApiResponse<()> {
     // Check if connector is used in any flow
     let store = state.config_store.read().await;
-    if store.is_connector_in_use(&name).await {
-        return ApiResponse::<()>::conflict(
-            "Cannot delete connector: it is being used in one or more flows".to_string(),
-        );
+    match store.is_connector_in_use(&name).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return ApiResponse::<()>::conflict(
+                "Cannot delete connector: it is being used in one or more flows".to_string(),
+            )
+        }
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to list flows: {:#}", e))
+        }
     }
     drop(store);
 
@@ -84,18 +128,23 @@ pub async fn test_connector(
     let store = state.config_store.read().await;
 
     let connector_entry = match store.get_connector(&name).await {
-        Some(entry) => entry,
-        None => return ApiResponse::not_found("Connector"),
+        Ok(Some(entry)) => entry,
+        Ok(None) => return ApiResponse::not_found("Connector"),
+        Err(e) => return ApiResponse::internal_error(format!("Failed to load connector: {:#}", e)),
     };
 
     // Get registry to create connector
     let registry = &state.registry;
-    let connector_factory = match registry.get_connector_factory(&connector_entry.connector_type) {
-        Ok(factory) => factory,
-        Err(_) => return ApiResponse::bad_request("Invalid connector type"),
-    };
+    if registry
+        .get_connector_factory(&connector_entry.connector_type)
+        .is_err()
+    {
+        return ApiResponse::bad_request("Invalid connector type");
+    }
 
-    let mut connector = match connector_factory.create(connector_entry.config.clone()) {
+    let mut connector = match registry
+        .create_connector(&connector_entry.connector_type, &connector_entry.config)
+    {
         Ok(conn) => conn,
         Err(e) => return ApiResponse::bad_request(format!("Failed to create connector: {}", e)),
     };
@@ -112,16 +161,49 @@ pub async fn test_connector(
 
 pub async fn test_connector_config(
     State(state): State<AppState>,
-    Json(entry): Json<ConnectorConfigEntry>,
+    Json(mut entry): Json<ConnectorConfigEntry>,
 ) -> impl IntoResponse {
+    // A form opened from a saved connector carries masked secrets. Test with the stored
+    // ones only if nothing else changed, so they can't be sent to another server.
+    if contains_masked(&entry.config) {
+        let existing = state
+            .config_store
+            .read()
+            .await
+            .get_connector(&entry.name)
+            .await;
+        let secret_fields = state
+            .registry
+            .connector_secret_fields(&entry.connector_type);
+        match existing {
+            Ok(Some(existing))
+                if existing.connector_type == entry.connector_type
+                    && is_masked_copy(&entry.config, &existing.config, &secret_fields) =>
+            {
+                entry.config = existing.config;
+            }
+            Err(e) => {
+                return ApiResponse::internal_error(format!("Failed to load connector: {:#}", e))
+            }
+            _ => {
+                return ApiResponse::bad_request(
+                    "Masked secrets can only be tested with the saved configuration; \
+                     enter them again to test changes",
+                )
+            }
+        }
+    }
+
     // Get registry to create connector
     let registry = &state.registry;
-    let connector_factory = match registry.get_connector_factory(&entry.connector_type) {
-        Ok(factory) => factory,
-        Err(_) => return ApiResponse::bad_request("Invalid connector type"),
-    };
+    if registry
+        .get_connector_factory(&entry.connector_type)
+        .is_err()
+    {
+        return ApiResponse::bad_request("Invalid connector type");
+    }
 
-    let mut connector = match connector_factory.create(entry.config.clone()) {
+    let mut connector = match registry.create_connector(&entry.connector_type, &entry.config) {
         Ok(conn) => conn,
         Err(e) => return ApiResponse::bad_request(format!("Failed to create connector: {}", e)),
     };
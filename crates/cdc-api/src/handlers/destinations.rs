@@ -4,15 +4,29 @@ use axum::{
     Json,
 };
 use cdc_config_store::DestinationConfigEntry;
+use cdc_core::{contains_masked, is_masked_copy, restore_masked};
 
 use crate::{handlers::AppState, ApiResponse};
 
+const MASKED_SECRET_ERROR: &str =
+    "Config contains masked secrets with no stored value; supply the real values or secret references";
+
 pub async fn list_destinations(State(state): State<AppState>) -> impl IntoResponse {
     let store = state.config_store.read().await;
-    let destinations = store
-        .list_destinations()
-        .await
+    let destinations = match store.list_destinations().await {
+        Ok(destinations) => destinations,
+        Err(e) => {
+            return ApiResponse::internal_error(format!("Failed to list destinations: {:#}", e))
+        }
+    };
+    let destinations = destinations
         .into_iter()
+        .map(|mut entry| {
+            entry.config = state
+                .registry
+                .redact_destination_config(&entry.destination_type, &entry.config);
+            entry
+        })
         .collect::<Vec<_>>();
     ApiResponse::success(destinations, "Destinations retrieved successfully")
 }
@@ -23,10 +37,16 @@ pub async fn get_destination(
 ) -> impl IntoResponse {
     let store = state.config_store.read().await;
 
-    let destination = match store.get_destination(&name).await {
-        Some(d) => d,
-        None => return ApiResponse::not_found("Destination"),
+    let mut destination = match store.get_destination(&name).await {
+        Ok(Some(d)) => d,
+        Ok(None) => return ApiResponse::not_found("Destination"),
+        Err(e) => {
+            return ApiResponse::internal_error(format!("Failed to load destination: {:#}", e))
+        }
     };
+    destination.config = state
+        .registry
+        .redact_destination_config(&destination.destination_type, &destination.config);
 
     ApiResponse::success(destination, "Destination retrieved successfully")
 }
@@ -35,6 +55,10 @@ pub async fn create_destination(
     State(state): State<AppState>,
     Json(entry): Json<DestinationConfigEntry>,
 ) -> ApiResponse<()> {
+    if contains_masked(&entry.config) {
+        return ApiResponse::<()>::bad_request(MASKED_SECRET_ERROR);
+    }
+
     let mut store = state.config_store.write().await;
 
     match store.add_destination(entry).await {
@@ -46,10 +70,28 @@ pub async fn create_destination(
 pub async fn update_destination(
     State(state): State<AppState>,
     Path(name): Path<String>,
-    Json(entry): Json<DestinationConfigEntry>,
+    Json(mut entry): Json<DestinationConfigEntry>,
 ) -> ApiResponse<()> {
     let mut store = state.config_store.write().await;
 
+    // Keep stored secrets the client sent back masked
+    let existing = match store.get_destination(&name).await {
+        Ok(existing) => existing,
+        Err(e) => {
+            return ApiResponse::internal_error(format!("Failed to load destination: {:#}", e))
+        }
+    };
+    if let Some(existing) = existing {
+        let secret_fields = state
+            .registry
+            .destination_secret_fields(&entry.destination_type);
+        restore_masked(&mut entry.config, &existing.config, &secret_fields);
+    }
+    // A masked URL password whose host changed has nothing to restore from
+    if contains_masked(&entry.config) {
+        return ApiResponse::<()>::bad_request(MASKED_SECRET_ERROR);
+    }
+
     match store.update_destination(&name, entry).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Destination updated successfully"),
         Err(_) => ApiResponse::<()>::not_found("Destination"),
@@ -62,10 +104,16 @@ pub async fn delete_destination(
 ) -> ApiResponse<()> {
     // Check if destination is used in any flow
     let store = state.config_store.read().await;
-    if store.is_destination_in_use(&name).await {
-        return ApiResponse::<()>::conflict(
-            "Cannot delete destination: it is being used in one or more flows".to_string(),
-        );
+    match store.is_destination_in_use(&name).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return ApiResponse::<()>::conflict(
+                "Cannot delete destination: it is being used in one or more flows".to_string(),
+            )
+        }
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to list flows: {:#}", e))
+        }
     }
     drop(store);
 
@@ -84,18 +132,25 @@ pub async fn test_destination(
     let store = state.config_store.read().await;
 
     let dest_entry = match store.get_destination(&name).await {
-        Some(entry) => entry,
-        None => return ApiResponse::not_found("Destination"),
+        Ok(Some(entry)) => entry,
+        Ok(None) => return ApiResponse::not_found("Destination"),
+        Err(e) => {
+            return ApiResponse::internal_error(format!("Failed to load destination: {:#}", e))
+        }
     };
 
     // Get registry to create destination
     let registry = &state.registry;
-    let dest_factory = match registry.get_destination_factory(&dest_entry.destination_type) {
-        Ok(factory) => factory,
-        Err(_) => return ApiResponse::bad_request("Invalid destination type"),
-    };
+    if registry
+        .get_destination_factory(&dest_entry.destination_type)
+        .is_err()
+    {
+        return ApiResponse::bad_request("Invalid destination type");
+    }
 
-    let mut destination = match dest_factory.create(dest_entry.config.clone()) {
+    let mut destination = match registry
+        .create_destination(&dest_entry.destination_type, &dest_entry.config)
+    {
         Ok(dest) => dest,
         Err(e) => return ApiResponse::bad_request(format!("Failed to create destination: {}", e)),
     };
@@ -112,16 +167,50 @@ pub async fn test_destination(
 
 pub async fn test_destination_config(
     State(state): State<AppState>,
-    Json(entry): Json<DestinationConfigEntry>,
+    Json(mut entry): Json<DestinationConfigEntry>,
 ) -> impl IntoResponse {
+    // A form opened from a saved destination carries masked secrets. Test with the stored
+    // ones only if nothing else changed, so they can't be sent to another server.
+    if contains_masked(&entry.config) {
+        let existing = state
+            .config_store
+            .read()
+            .await
+            .get_destination(&entry.name)
+            .await;
+        let secret_fields = state
+            .registry
+            .destination_secret_fields(&entry.destination_type);
+        match existing {
+            Ok(Some(existing))
+                if existing.destination_type == entry.destination_type
+                    && is_masked_copy(&entry.config, &existing.config, &secret_fields) =>
+            {
+                entry.config = existing.config;
+            }
+            Err(e) => {
+                return ApiResponse::internal_error(format!("Failed to load destination: {:#}", e))
+            }
+            _ => {
+                return ApiResponse::bad_request(
+                    "Masked secrets can only be tested with the saved configuration; \
+                     enter them again to test changes",
+                )
+            }
+        }
+    }
+
     // Get registry to create destination
     let registry = &state.registry;
-    let dest_factory = match registry.get_destination_factory(&entry.destination_type) {
-        Ok(factory) => factory,
-        Err(_) => return ApiResponse::bad_request("Invalid destination type"),
-    };
+    if registry
+        .get_destination_factory(&entry.destination_type)
+        .is_err()
+    {
+        return ApiResponse::bad_request("Invalid destination type");
+    }
 
-    let mut destination = match dest_factory.create(entry.config.clone()) {
+    let mut destination = match registry.create_destination(&entry.destination_type, &entry.config)
+    {
         Ok(dest) => dest,
         Err(e) => return ApiResponse::bad_request(format!("Failed to create destination: {}", e)),
     };
@@ -3,9 +3,10 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use cdc_config_store::FlowConfigEntry;
+use cdc_config_store::{FlowConfigEntry, FlowLease};
 use cdc_core::{FlowBuilder, FlowStatus};
 use serde::Serialize;
+use tracing::warn;
 
 use crate::{handlers::AppState, ApiResponse};
 
@@ -25,6 +26,69 @@ pub struct FlowInfo {
     pub records_processed: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub messages_received: Option<u64>,
+    /// Instance running the flow, when flows are leased across instances
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+/// Claim a flow for this instance before running it
+async fn acquire_lease(state: &AppState, name: &str) -> Result<(), ApiResponse<()>> {
+    let Some(leases) = &state.leases else {
+        return Ok(());
+    };
+
+    match leases.try_acquire(name).await {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            let owner = leases
+                .get(name)
+                .await
+                .ok()
+                .flatten()
+                .map(|lease| lease.owner)
+                .unwrap_or_default();
+            Err(ApiResponse::<()>::conflict(format!(
+                "Flow '{}' is owned by instance '{}'",
+                name, owner
+            )))
+        }
+        Err(e) => Err(ApiResponse::<()>::internal_error(format!(
+            "Failed to acquire lease for flow '{}': {}",
+            name, e
+        ))),
+    }
+}
+
+/// Hand back the lease of a flow that failed to start, recording why
+async fn release_failed_lease(state: &AppState, name: &str, error: &str) {
+    let Some(leases) = &state.leases else {
+        return;
+    };
+
+    if let Err(e) = leases
+        .release(name, &FlowStatus::Failed(error.to_string()))
+        .await
+    {
+        warn!("Failed to release lease for flow '{}': {:#}", name, e);
+    }
+}
+
+/// Live lease on a flow held by another instance
+pub(crate) async fn remote_lease(state: &AppState, name: &str) -> Option<FlowLease> {
+    let leases = state.leases.as_ref()?;
+    leases
+        .get(name)
+        .await
+        .ok()
+        .flatten()
+        .filter(|lease| !lease.expired && lease.owner != leases.instance_id())
+}
+
+fn local_owner(state: &AppState) -> Option<String> {
+    state
+        .leases
+        .as_ref()
+        .map(|leases| leases.instance_id().to_string())
 }
 
 pub async fn list_flows(State(state): State<AppState>) -> impl IntoResponse {
@@ -35,8 +99,14 @@ pub async fn list_flows(State(state): State<AppState>) -> impl IntoResponse {
     let store = state.config_store.read().await;
 
     let mut flows = Vec::new();
+    let mut local_names = Vec::new();
     for (name, status) in flow_statuses {
-        if let Some(flow_config) = store.get_flow(&name).await {
+        local_names.push(name.clone());
+        let flow_config = match store.get_flow(&name).await {
+            Ok(flow_config) => flow_config,
+            Err(e) => return ApiResponse::internal_error(format!("Failed to load flow: {:#}", e)),
+        };
+        if let Some(flow_config) = flow_config {
             let is_active = matches!(status, FlowStatus::Running);
 
             // Get metrics from orchestrator
@@ -55,10 +125,51 @@ pub async fn list_flows(State(state): State<AppState>) -> impl IntoResponse {
                 uptime_seconds,
                 records_processed,
                 messages_received,
+                owner: local_owner(&state),
             });
         }
     }
 
+    // Flows running on other instances
+    if let Some(leases) = &state.leases {
+        let remote = match leases.list().await {
+            Ok(remote) => remote,
+            Err(e) => {
+                return ApiResponse::internal_error(format!("Failed to list flow leases: {:#}", e))
+            }
+        };
+        for lease in remote {
+            if lease.expired
+                || lease.owner == leases.instance_id()
+                || local_names.contains(&lease.flow_name)
+            {
+                continue;
+            }
+            let flow_config = match store.get_flow(&lease.flow_name).await {
+                Ok(flow_config) => flow_config,
+                Err(e) => {
+                    return ApiResponse::internal_error(format!("Failed to load flow: {:#}", e))
+                }
+            };
+            if let Some(flow_config) = flow_config {
+                let status = lease.status.unwrap_or(FlowStatus::Running);
+                flows.push(FlowInfo {
+                    name: lease.flow_name,
+                    connector_name: flow_config.connector_name,
+                    destination_names: flow_config.destination_names,
+                    batch_size: flow_config.batch_size,
+                    auto_start: flow_config.auto_start,
+                    is_active: matches!(status, FlowStatus::Running),
+                    status,
+                    uptime_seconds: None,
+                    records_processed: None,
+                    messages_received: None,
+                    owner: Some(lease.owner),
+                });
+            }
+        }
+    }
+
     ApiResponse::success(flows, "Flows retrieved successfully")
 }
 
@@ -68,15 +179,21 @@ pub async fn get_flow(
 ) -> impl IntoResponse {
     let store = state.config_store.read().await;
     let flow_config = match store.get_flow(&name).await {
-        Some(config) => config,
-        None => return ApiResponse::not_found("Flow"),
+        Ok(Some(config)) => config,
+        Ok(None) => return ApiResponse::not_found("Flow"),
+        Err(e) => return ApiResponse::internal_error(format!("Failed to load flow: {:#}", e)),
     };
 
-    let status = state
-        .orchestrator
-        .get_flow_status(&name)
-        .await
-        .unwrap_or(FlowStatus::Stopped);
+    let (status, owner) = match state.orchestrator.get_flow_status(&name).await {
+        Some(status) => (status, local_owner(&state)),
+        None => match remote_lease(&state, &name).await {
+            Some(lease) => (
+                lease.status.unwrap_or(FlowStatus::Running),
+                Some(lease.owner),
+            ),
+            None => (FlowStatus::Stopped, None),
+        },
+    };
 
     let is_active = matches!(status, FlowStatus::Running);
 
@@ -95,6 +212,7 @@ pub async fn get_flow(
         uptime_seconds,
         records_processed,
         messages_received,
+        owner,
     };
 
     ApiResponse::success(info, "Flow retrieved successfully")
@@ -118,21 +236,33 @@ pub async fn create_flow(
 
         // Get connector config
         let connector_entry = match store_read.get_connector(&entry.connector_name).await {
-            Some(c) => c,
-            None => return ApiResponse::<()>::bad_request("Connector not found"),
+            Ok(Some(c)) => c,
+            Ok(None) => return ApiResponse::<()>::bad_request("Connector not found"),
+            Err(e) => {
+                return ApiResponse::<()>::internal_error(format!(
+                    "Failed to load connector: {:#}",
+                    e
+                ))
+            }
         };
 
         // Get destination configs
         let mut dest_entries = Vec::new();
         for dest_name in &entry.destination_names {
             let dest_entry = match store_read.get_destination(dest_name).await {
-                Some(d) => d,
-                None => {
+                Ok(Some(d)) => d,
+                Ok(None) => {
                     return ApiResponse::<()>::bad_request(format!(
                         "Destination {} not found",
                         dest_name
                     ))
                 }
+                Err(e) => {
+                    return ApiResponse::<()>::internal_error(format!(
+                        "Failed to load destination: {:#}",
+                        e
+                    ))
+                }
             };
             dest_entries.push(dest_entry);
         }
@@ -158,7 +288,11 @@ pub async fn create_flow(
         };
 
         // Start flow
+        if let Err(response) = acquire_lease(&state, &entry.name).await {
+            return response;
+        }
         if let Err(e) = state.orchestrator.add_flow(flow).await {
+            release_failed_lease(&state, &entry.name, &e.to_string()).await;
             return ApiResponse::<()>::internal_error(format!("Failed to start flow: {}", e));
         }
     }
@@ -178,6 +312,9 @@ pub async fn delete_flow(
 
     // Remove from orchestrator
     state.orchestrator.remove_flow(&name).await.ok();
+    if let Some(leases) = &state.leases {
+        leases.remove(&name).await.ok();
+    }
 
     // Remove from config store
     let mut store = state.config_store.write().await;
@@ -193,32 +330,44 @@ pub async fn start_flow(
 ) -> ApiResponse<()> {
     let store = state.config_store.read().await;
     let entry = match store.get_flow(&name).await {
-        Some(e) => e,
-        None => return ApiResponse::<()>::not_found("Flow"),
+        Ok(Some(e)) => e,
+        Ok(None) => return ApiResponse::<()>::not_found("Flow"),
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load flow: {:#}", e))
+        }
     };
 
     // Get connector config
     let connector_entry = match store.get_connector(&entry.connector_name).await {
-        Some(c) => c,
-        None => {
+        Ok(Some(c)) => c,
+        Ok(None) => {
             return ApiResponse::<()>::bad_request(format!(
                 "Connector '{}' not found for flow '{}'",
                 entry.connector_name, name
             ))
         }
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load connector: {:#}", e))
+        }
     };
 
     // Get destination configs
     let mut dest_entries = Vec::new();
     for dest_name in &entry.destination_names {
         let dest_entry = match store.get_destination(dest_name).await {
-            Some(d) => d,
-            None => {
+            Ok(Some(d)) => d,
+            Ok(None) => {
                 return ApiResponse::<()>::bad_request(format!(
                     "Destination '{}' not found for flow '{}'",
                     dest_name, name
                 ))
             }
+            Err(e) => {
+                return ApiResponse::<()>::internal_error(format!(
+                    "Failed to load destination: {:#}",
+                    e
+                ))
+            }
         };
         dest_entries.push(dest_entry);
     }
@@ -252,16 +401,28 @@ pub async fn start_flow(
     };
 
     // Start flow
+    if let Err(response) = acquire_lease(&state, &name).await {
+        return response;
+    }
     match state.orchestrator.add_flow(flow).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Flow started successfully"),
-        Err(e) => ApiResponse::<()>::conflict(format!("Failed to start flow '{}': {}", name, e)),
+        Err(e) => {
+            release_failed_lease(&state, &name, &e.to_string()).await;
+            ApiResponse::<()>::conflict(format!("Failed to start flow '{}': {}", name, e))
+        }
     }
 }
 
 pub async fn stop_flow(State(state): State<AppState>, Path(name): Path<String>) -> ApiResponse<()> {
     match state.orchestrator.stop_flow(&name).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Flow stopped successfully"),
-        Err(_) => ApiResponse::<()>::not_found("Flow"),
+        Err(_) => match remote_lease(&state, &name).await {
+            Some(lease) => ApiResponse::<()>::conflict(format!(
+                "Flow '{}' is owned by instance '{}'",
+                name, lease.owner
+            )),
+            None => ApiResponse::<()>::not_found("Flow"),
+        },
     }
 }
 
@@ -281,32 +442,44 @@ pub async fn restart_flow(
     // Now start it again using the same logic as start_flow
     let store = state.config_store.read().await;
     let entry = match store.get_flow(&name).await {
-        Some(e) => e,
-        None => return ApiResponse::<()>::not_found("Flow"),
+        Ok(Some(e)) => e,
+        Ok(None) => return ApiResponse::<()>::not_found("Flow"),
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load flow: {:#}", e))
+        }
     };
 
     // Get connector config
     let connector_entry = match store.get_connector(&entry.connector_name).await {
-        Some(c) => c,
-        None => {
+        Ok(Some(c)) => c,
+        Ok(None) => {
             return ApiResponse::<()>::bad_request(format!(
                 "Connector '{}' not found for flow '{}'",
                 entry.connector_name, name
             ))
         }
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load connector: {:#}", e))
+        }
     };
 
     // Get destination configs
     let mut dest_entries = Vec::new();
     for dest_name in &entry.destination_names {
         let dest_entry = match store.get_destination(dest_name).await {
-            Some(d) => d,
-            None => {
+            Ok(Some(d)) => d,
+            Ok(None) => {
                 return ApiResponse::<()>::bad_request(format!(
                     "Destination '{}' not found for flow '{}'",
                     dest_name, name
                 ))
             }
+            Err(e) => {
+                return ApiResponse::<()>::internal_error(format!(
+                    "Failed to load destination: {:#}",
+                    e
+                ))
+            }
         };
         dest_entries.push(dest_entry);
     }
@@ -337,9 +510,15 @@ pub async fn restart_flow(
     };
 
     // Start flow
+    if let Err(response) = acquire_lease(&state, &name).await {
+        return response;
+    }
     match state.orchestrator.add_flow(flow).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Flow restarted successfully"),
-        Err(e) => ApiResponse::<()>::conflict(format!("Failed to restart flow '{}': {}", name, e)),
+        Err(e) => {
+            release_failed_lease(&state, &name, &e.to_string()).await;
+            ApiResponse::<()>::conflict(format!("Failed to restart flow '{}': {}", name, e))
+        }
     }
 }
 
@@ -351,7 +530,13 @@ pub async fn pause_flow(
     // For now, we can use stop as pause (they're functionally similar)
     match state.orchestrator.stop_flow(&name).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Flow paused successfully"),
-        Err(_) => ApiResponse::<()>::not_found("Flow"),
+        Err(_) => match remote_lease(&state, &name).await {
+            Some(lease) => ApiResponse::<()>::conflict(format!(
+                "Flow '{}' is owned by instance '{}'",
+                name, lease.owner
+            )),
+            None => ApiResponse::<()>::not_found("Flow"),
+        },
     }
 }
 
@@ -363,32 +548,44 @@ pub async fn resume_flow(
     // We'll use the same logic as start_flow
     let store = state.config_store.read().await;
     let entry = match store.get_flow(&name).await {
-        Some(e) => e,
-        None => return ApiResponse::<()>::not_found("Flow"),
+        Ok(Some(e)) => e,
+        Ok(None) => return ApiResponse::<()>::not_found("Flow"),
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load flow: {:#}", e))
+        }
     };
 
     // Get connector config
     let connector_entry = match store.get_connector(&entry.connector_name).await {
-        Some(c) => c,
-        None => {
+        Ok(Some(c)) => c,
+        Ok(None) => {
             return ApiResponse::<()>::bad_request(format!(
                 "Connector '{}' not found for flow '{}'",
                 entry.connector_name, name
             ))
         }
+        Err(e) => {
+            return ApiResponse::<()>::internal_error(format!("Failed to load connector: {:#}", e))
+        }
     };
 
     // Get destination configs
     let mut dest_entries = Vec::new();
     for dest_name in &entry.destination_names {
         let dest_entry = match store.get_destination(dest_name).await {
-            Some(d) => d,
-            None => {
+            Ok(Some(d)) => d,
+            Ok(None) => {
                 return ApiResponse::<()>::bad_request(format!(
                     "Destination '{}' not found for flow '{}'",
                     dest_name, name
                 ))
             }
+            Err(e) => {
+                return ApiResponse::<()>::internal_error(format!(
+                    "Failed to load destination: {:#}",
+                    e
+                ))
+            }
         };
         dest_entries.push(dest_entry);
     }
@@ -422,8 +619,14 @@ pub async fn resume_flow(
     };
 
     // Start flow
+    if let Err(response) = acquire_lease(&state, &name).await {
+        return response;
+    }
     match state.orchestrator.add_flow(flow).await {
         Ok(_) => ApiResponse::<()>::success_no_data("Flow resumed successfully"),
-        Err(e) => ApiResponse::<()>::conflict(format!("Failed to resume flow '{}': {}", name, e)),
+        Err(e) => {
+            release_failed_lease(&state, &name, &e.to_string()).await;
+            ApiResponse::<()>::conflict(format!("Failed to resume flow '{}': {}", name, e))
+        }
     }
 }
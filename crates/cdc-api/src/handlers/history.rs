@@ -0,0 +1,191 @@
+use axum::extract::{Path, Query, State};
+use cdc_config_store::{ConfigKind, ConfigRevision, RollbackError};
+use cdc_core::FlowStatus;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    handlers::{flows, AppState},
+    ApiResponse,
+};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RollbackQuery {
+    /// Restart running flows that use the restored entry
+    #[serde(default)]
+    pub restart_flows: bool,
+}
+
+#[derive(Serialize)]
+pub struct RollbackResult {
+    pub revision: i64,
+    pub restarted_flows: Vec<String>,
+}
+
+pub async fn connector_history(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResponse<Vec<ConfigRevision>> {
+    history(&state, ConfigKind::Connector, &name).await
+}
+
+pub async fn destination_history(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResponse<Vec<ConfigRevision>> {
+    history(&state, ConfigKind::Destination, &name).await
+}
+
+pub async fn flow_history(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResponse<Vec<ConfigRevision>> {
+    history(&state, ConfigKind::Flow, &name).await
+}
+
+pub async fn rollback_connector(
+    State(state): State<AppState>,
+    Path((name, revision)): Path<(String, i64)>,
+    Query(query): Query<RollbackQuery>,
+) -> ApiResponse<RollbackResult> {
+    rollback(&state, ConfigKind::Connector, &name, revision, query).await
+}
+
+pub async fn rollback_destination(
+    State(state): State<AppState>,
+    Path((name, revision)): Path<(String, i64)>,
+    Query(query): Query<RollbackQuery>,
+) -> ApiResponse<RollbackResult> {
+    rollback(&state, ConfigKind::Destination, &name, revision, query).await
+}
+
+pub async fn rollback_flow(
+    State(state): State<AppState>,
+    Path((name, revision)): Path<(String, i64)>,
+    Query(query): Query<RollbackQuery>,
+) -> ApiResponse<RollbackResult> {
+    rollback(&state, ConfigKind::Flow, &name, revision, query).await
+}
+
+async fn history(
+    state: &AppState,
+    kind: ConfigKind,
+    name: &str,
+) -> ApiResponse<Vec<ConfigRevision>> {
+    let store = state.config_store.read().await;
+    let mut revisions = match store.history(kind, name).await {
+        Ok(revisions) => revisions,
+        Err(e) => return ApiResponse::internal_error(format!("Failed to get history: {}", e)),
+    };
+    if revisions.is_empty() {
+        return ApiResponse::not_found("History");
+    }
+
+    // Snapshots carry full configs; mask secrets like the regular getters do
+    for revision in &mut revisions {
+        if let Some(snapshot) = revision.snapshot.as_mut() {
+            redact_snapshot(state, kind, snapshot);
+        }
+    }
+
+    ApiResponse::success(revisions, "History retrieved successfully")
+}
+
+fn redact_snapshot(state: &AppState, kind: ConfigKind, snapshot: &mut serde_json::Value) {
+    let config = &snapshot["config"];
+    let redacted = match kind {
+        ConfigKind::Connector => {
+            let connector_type = snapshot["connector_type"].as_str().unwrap_or_default();
+            state
+                .registry
+                .redact_connector_config(connector_type, config)
+        }
+        ConfigKind::Destination => {
+            let destination_type = snapshot["destination_type"].as_str().unwrap_or_default();
+            state
+                .registry
+                .redact_destination_config(destination_type, config)
+        }
+        ConfigKind::Flow => return,
+    };
+    snapshot["config"] = redacted;
+}
+
+async fn rollback(
+    state: &AppState,
+    kind: ConfigKind,
+    name: &str,
+    revision: i64,
+    query: RollbackQuery,
+) -> ApiResponse<RollbackResult> {
+    let mut store = state.config_store.write().await;
+    if let Err(e) = store.rollback(kind, name, revision).await {
+        return match e.downcast_ref::<RollbackError>() {
+            Some(RollbackError::NotFound { .. }) => ApiResponse::not_found(format!(
+                "Revision {} of {} '{}'",
+                revision,
+                kind.as_str(),
+                name
+            )),
+            Some(_) => ApiResponse::bad_request(format!("Failed to roll back: {}", e)),
+            None => ApiResponse::internal_error(format!("Failed to roll back: {:#}", e)),
+        };
+    }
+
+    // Flows that use the restored entry
+    let flows = match kind {
+        ConfigKind::Flow => Vec::new(),
+        _ => match store.list_flows().await {
+            Ok(flows) => flows,
+            Err(e) => {
+                return ApiResponse::internal_error(format!(
+                    "Rolled back, but failed to list affected flows: {:#}",
+                    e
+                ))
+            }
+        },
+    };
+    let affected: Vec<String> = match kind {
+        ConfigKind::Connector => flows
+            .into_iter()
+            .filter(|flow| flow.connector_name == name)
+            .map(|flow| flow.name)
+            .collect(),
+        ConfigKind::Destination => flows
+            .into_iter()
+            .filter(|flow| flow.destination_names.iter().any(|d| d == name))
+            .map(|flow| flow.name)
+            .collect(),
+        ConfigKind::Flow => vec![name.to_string()],
+    };
+    drop(store);
+
+    let mut restarted_flows = Vec::new();
+    if query.restart_flows {
+        for flow_name in affected {
+            let running = matches!(
+                state.orchestrator.get_flow_status(&flow_name).await,
+                Some(FlowStatus::Running)
+            );
+            if !running {
+                continue;
+            }
+
+            let response = flows::restart_flow(State(state.clone()), Path(flow_name.clone())).await;
+            if response.code != "SUCCESS" {
+                return ApiResponse::internal_error(format!(
+                    "Rolled back to revision {}, but failed to restart flow '{}': {}",
+                    revision, flow_name, response.message
+                ));
+            }
+            restarted_flows.push(flow_name);
+        }
+    }
+
+    ApiResponse::success(
+        RollbackResult {
+            revision,
+            restarted_flows,
+        },
+        format!("Rolled back to revision {}", revision),
+    )
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
+use cdc_core::{DataRecord, IngestError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    handlers::{flows::remote_lease, AppState},
+    ApiResponse,
+};
+
+/// Body of an ingest request: one record or an array of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum IngestBody {
+    Many(Vec<DataRecord>),
+    One(DataRecord),
+}
+
+#[derive(Serialize)]
+pub struct IngestResponse {
+    pub accepted: usize,
+}
+
+/// Token from an `Authorization: Bearer <token>` header
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+/// Queue records for a flow whose connector is of type `http`, answering once they are
+/// written or queued depending on the connector's `acknowledge` setting. Records still
+/// unwritten after the connector's `write_timeout_ms` are answered 202 as only queued
+pub async fn ingest(
+    State(state): State<AppState>,
+    Path(flow): Path<String>,
+    headers: HeaderMap,
+    body: Result<Json<IngestBody>, JsonRejection>,
+) -> Response {
+    // Check the flow before the body so producers see 404/409/401 for a wrong URL or token
+    let records = match body {
+        Ok(Json(IngestBody::Many(records))) => records,
+        Ok(Json(IngestBody::One(record))) => vec![record],
+        Err(rejection) => {
+            if let Err(e @ (IngestError::NotFound | IngestError::Unauthorized)) = state
+                .ingest
+                .submit(&flow, bearer_token(&headers), Vec::new())
+            {
+                return ingest_error(&state, &flow, e).await;
+            }
+            return ApiResponse::<()>::bad_request(format!(
+                "Invalid records: {}",
+                rejection.body_text()
+            ))
+            .into_response();
+        }
+    };
+
+    let submission = match state.ingest.submit(&flow, bearer_token(&headers), records) {
+        Ok(submission) => submission,
+        Err(e) => return ingest_error(&state, &flow, e).await,
+    };
+    let accepted = submission.accepted;
+    state.stats.write().await.records_received += accepted as u64;
+
+    let Some(written) = submission.written else {
+        return ApiResponse::accepted(
+            IngestResponse { accepted },
+            format!("{} record(s) queued for flow '{}'", accepted, flow),
+        )
+        .into_response();
+    };
+    match tokio::time::timeout(submission.write_timeout, written).await {
+        Ok(Ok(())) => ApiResponse::success(
+            IngestResponse { accepted },
+            format!("{} record(s) written by flow '{}'", accepted, flow),
+        )
+        .into_response(),
+        Err(_) => ApiResponse::accepted(
+            IngestResponse { accepted },
+            format!(
+                "{} record(s) queued for flow '{}', not yet written",
+                accepted, flow
+            ),
+        )
+        .into_response(),
+        Ok(Err(_)) => (
+            [(header::RETRY_AFTER, "1")],
+            ApiResponse::<()>::service_unavailable(format!(
+                "Flow '{}' stopped before writing the records, retry them",
+                flow
+            )),
+        )
+            .into_response(),
+    }
+}
+
+async fn ingest_error(state: &AppState, flow: &str, error: IngestError) -> Response {
+    match error {
+        IngestError::NotFound => match remote_lease(state, flow).await {
+            // Producers can retry against the instance running the flow
+            Some(lease) => ApiResponse::<()>::conflict(format!(
+                "Flow '{}' is owned by instance '{}'",
+                flow, lease.owner
+            ))
+            .into_response(),
+            None => ApiResponse::<()>::not_found(format!("Ingest endpoint for flow '{}'", flow))
+                .into_response(),
+        },
+        IngestError::Unauthorized => {
+            ApiResponse::<()>::unauthorized(format!("Invalid ingest token for flow '{}'", flow))
+                .into_response()
+        }
+        IngestError::Full => (
+            [(header::RETRY_AFTER, "1")],
+            ApiResponse::<()>::too_many_requests(format!(
+                "Ingest queue of flow '{}' is full, retry later",
+                flow
+            )),
+        )
+            .into_response(),
+        IngestError::TooLarge { capacity } => ApiResponse::<()>::payload_too_large(format!(
+            "Flow '{}' accepts at most {} records per request",
+            flow, capacity
+        ))
+        .into_response(),
+    }
+}
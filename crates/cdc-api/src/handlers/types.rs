@@ -0,0 +1,50 @@
+use axum::{extract::State, response::IntoResponse};
+use cdc_core::PluginSource;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{handlers::AppState, ApiResponse};
+
+/// A registered connector or destination type with its config schema
+#[derive(Serialize)]
+pub struct TypeInfo {
+    pub name: String,
+    pub source: PluginSource,
+    pub config_schema: Value,
+}
+
+pub async fn list_connector_types(State(state): State<AppState>) -> impl IntoResponse {
+    let types = state
+        .registry
+        .list_connectors()
+        .into_iter()
+        .filter_map(|info| {
+            let factory = state.registry.get_connector_factory(&info.name).ok()?;
+            Some(TypeInfo {
+                name: info.name,
+                source: info.source,
+                config_schema: factory.config_schema(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    ApiResponse::success(types, "Connector types retrieved successfully")
+}
+
+pub async fn list_destination_types(State(state): State<AppState>) -> impl IntoResponse {
+    let types = state
+        .registry
+        .list_destinations()
+        .into_iter()
+        .filter_map(|info| {
+            let factory = state.registry.get_destination_factory(&info.name).ok()?;
+            Some(TypeInfo {
+                name: info.name,
+                source: info.source,
+                config_schema: factory.config_schema(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    ApiResponse::success(types, "Destination types retrieved successfully")
+}
@@ -0,0 +1,107 @@
+use axum::extract::{Path, State};
+use cdc_config_store::FlowLeases;
+use cdc_core::FlowStatus;
+use std::collections::{HashMap, HashSet};
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::handlers::{flows, AppState};
+
+/// Heartbeat loop that keeps this instance's flow leases alive.
+///
+/// Every `ttl / 3` it renews the leases of local flows, stops flows whose lease
+/// was lost or whose config was deleted, and starts flows nobody is running:
+/// auto-start flows without a lease, and flows whose owner stopped renewing or
+/// shut down while they were running. Returns immediately when leasing is disabled.
+pub async fn run_flow_leasing(state: AppState) {
+    let Some(leases) = state.leases.clone() else {
+        return;
+    };
+
+    info!(
+        "Flow leasing enabled for instance '{}' (lease ttl {:?})",
+        leases.instance_id(),
+        leases.ttl()
+    );
+
+    let mut ticker = tokio::time::interval(leases.ttl() / 3);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = heartbeat(&state, &leases).await {
+            warn!("Flow lease heartbeat failed: {}", e);
+        }
+    }
+}
+
+async fn heartbeat(state: &AppState, leases: &FlowLeases) -> anyhow::Result<()> {
+    // A failed read skips the tick instead of treating every flow as deleted
+    let configured: HashMap<_, _> = state
+        .config_store
+        .read()
+        .await
+        .list_flows()
+        .await?
+        .into_iter()
+        .map(|flow| (flow.name.clone(), flow))
+        .collect();
+
+    // Renew what runs here
+    let mut local = HashSet::new();
+    for (name, status) in state.orchestrator.list_flows().await {
+        if !configured.contains_key(&name) {
+            info!("Flow '{}' was deleted, stopping it", name);
+            stop_local(state, &name).await;
+            leases.remove(&name).await?;
+            continue;
+        }
+
+        if leases.renew(&name, &status).await? {
+            local.insert(name);
+        } else {
+            warn!("Lost lease on flow '{}', stopping it", name);
+            stop_local(state, &name).await;
+        }
+    }
+
+    // Take over flows nobody runs
+    let current: HashMap<_, _> = leases
+        .list()
+        .await?
+        .into_iter()
+        .map(|lease| (lease.flow_name.clone(), lease))
+        .collect();
+
+    for (name, flow) in &configured {
+        if local.contains(name) {
+            continue;
+        }
+
+        let unowned = match current.get(name) {
+            Some(lease) => {
+                lease.expired && matches!(lease.status, None | Some(FlowStatus::Running))
+            }
+            None => flow.auto_start,
+        };
+        if !unowned || !leases.try_acquire(name).await? {
+            continue;
+        }
+
+        info!("Acquired lease on flow '{}', starting it", name);
+        let response = flows::start_flow(State(state.clone()), Path(name.clone())).await;
+        if response.code != "SUCCESS" {
+            error!("Failed to start flow '{}': {}", name, response.message);
+            // A failed status keeps other instances from retrying it every tick
+            leases
+                .release(name, &FlowStatus::Failed(response.message))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn stop_local(state: &AppState, name: &str) {
+    state.orchestrator.stop_flow(name).await.ok();
+    state.orchestrator.remove_flow(name).await.ok();
+}
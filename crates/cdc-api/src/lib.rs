@@ -1,6 +1,8 @@
 pub mod handlers;
+pub mod leasing;
 pub mod response;
 pub mod server;
 
+pub use leasing::run_flow_leasing;
 pub use response::ApiResponse;
 pub use server::ApiServer;
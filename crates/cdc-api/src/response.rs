@@ -52,6 +52,16 @@ impl<T: Serialize> ApiResponse<T> {
         }
     }
 
+    /// Create an accepted response for work queued for later processing
+    pub fn accepted(data: T, message: impl Into<String>) -> Self {
+        Self {
+            data: Some(data),
+            message: message.into(),
+            code: "ACCEPTED".to_string(),
+            errors: vec![],
+        }
+    }
+
     /// Create a bad request error response
     pub fn bad_request(message: impl Into<String>) -> Self {
         Self {
@@ -72,6 +82,46 @@ impl<T: Serialize> ApiResponse<T> {
         }
     }
 
+    /// Create an unauthorized error response
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            data: None,
+            message: message.into(),
+            code: "UNAUTHORIZED".to_string(),
+            errors: vec![],
+        }
+    }
+
+    /// Create a too many requests error response
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self {
+            data: None,
+            message: message.into(),
+            code: "TOO_MANY_REQUESTS".to_string(),
+            errors: vec![],
+        }
+    }
+
+    /// Create a payload too large error response
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self {
+            data: None,
+            message: message.into(),
+            code: "PAYLOAD_TOO_LARGE".to_string(),
+            errors: vec![],
+        }
+    }
+
+    /// Create a service unavailable error response
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self {
+            data: None,
+            message: message.into(),
+            code: "SERVICE_UNAVAILABLE".to_string(),
+            errors: vec![],
+        }
+    }
+
     /// Create an internal error response
     pub fn internal_error(message: impl Into<String>) -> Self {
         Self {
@@ -87,9 +137,14 @@ impl<T: Serialize> IntoResponse for ApiResponse<T> {
     fn into_response(self) -> axum::response::Response {
         let status = match self.code.as_str() {
             "SUCCESS" => StatusCode::OK,
+            "ACCEPTED" => StatusCode::ACCEPTED,
             "NOT_FOUND" => StatusCode::NOT_FOUND,
             "BAD_REQUEST" => StatusCode::BAD_REQUEST,
             "CONFLICT" => StatusCode::CONFLICT,
+            "UNAUTHORIZED" => StatusCode::UNAUTHORIZED,
+            "TOO_MANY_REQUESTS" => StatusCode::TOO_MANY_REQUESTS,
+            "PAYLOAD_TOO_LARGE" => StatusCode::PAYLOAD_TOO_LARGE,
+            "SERVICE_UNAVAILABLE" => StatusCode::SERVICE_UNAVAILABLE,
             "INTERNAL_ERROR" => StatusCode::INTERNAL_SERVER_ERROR,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
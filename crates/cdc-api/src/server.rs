@@ -1,5 +1,6 @@
 use crate::handlers::{
-    connectors, destinations, flows, get_stats, health_check, reset_stats, AppState,
+    bundle, connectors, destinations, flows, get_stats, health_check, history, ingest, reset_stats,
+    types, AppState,
 };
 use axum::{
     routing::{get, post, put},
@@ -31,6 +32,15 @@ impl ApiServer {
             .route("/health", get(health_check))
             .route("/api/stats", get(get_stats))
             .route("/api/stats/reset", post(reset_stats))
+            // Connector and destination type introspection
+            .route("/api/types/connectors", get(types::list_connector_types))
+            .route(
+                "/api/types/destinations",
+                get(types::list_destination_types),
+            )
+            // Bulk config export/import
+            .route("/api/export", get(bundle::export_config))
+            .route("/api/import", post(bundle::import_config))
             // Connector management
             .route(
                 "/api/connectors",
@@ -50,6 +60,14 @@ impl ApiServer {
                 "/api/connectors/{name}/test",
                 post(connectors::test_connector),
             )
+            .route(
+                "/api/connectors/{name}/history",
+                get(history::connector_history),
+            )
+            .route(
+                "/api/connectors/{name}/rollback/{revision}",
+                post(history::rollback_connector),
+            )
             // Destination management
             .route(
                 "/api/destinations",
@@ -69,6 +87,14 @@ impl ApiServer {
                 "/api/destinations/{name}/test",
                 post(destinations::test_destination),
             )
+            .route(
+                "/api/destinations/{name}/history",
+                get(history::destination_history),
+            )
+            .route(
+                "/api/destinations/{name}/rollback/{revision}",
+                post(history::rollback_destination),
+            )
             // Flow management
             .route(
                 "/api/flows",
@@ -83,6 +109,12 @@ impl ApiServer {
             .route("/api/flows/{name}/restart", put(flows::restart_flow))
             .route("/api/flows/{name}/pause", put(flows::pause_flow))
             .route("/api/flows/{name}/resume", put(flows::resume_flow))
+            .route("/api/flows/{name}/history", get(history::flow_history))
+            .route(
+                "/api/flows/{name}/rollback/{revision}",
+                post(history::rollback_flow),
+            )
+            .route("/api/ingest/{flow}", post(ingest::ingest))
             .with_state(self.state);
 
         if self.cors_enabled {
@@ -0,0 +1,349 @@
+use crate::models::{ConfigKind, ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Current bundle format version
+pub const BUNDLE_VERSION: u32 = 1;
+
+fn default_version() -> u32 {
+    BUNDLE_VERSION
+}
+
+/// All connectors, destinations and flows of a store in one document.
+///
+/// Used to copy configs between storage backends (YAML files and PostgreSQL)
+/// or between environments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    #[serde(default)]
+    pub connectors: Vec<ConnectorConfigEntry>,
+
+    #[serde(default)]
+    pub destinations: Vec<DestinationConfigEntry>,
+
+    #[serde(default)]
+    pub flows: Vec<FlowConfigEntry>,
+}
+
+impl Default for ConfigBundle {
+    fn default() -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            connectors: Vec::new(),
+            destinations: Vec::new(),
+            flows: Vec::new(),
+        }
+    }
+}
+
+/// Serialization format of a bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BundleFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl BundleFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Yaml => "application/yaml",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+impl std::str::FromStr for BundleFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "json" => Ok(Self::Json),
+            other => Err(anyhow!("Unknown bundle format '{}'", other)),
+        }
+    }
+}
+
+/// What to do with bundle entries whose name already exists in the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Keep the stored entry
+    Skip,
+    /// Replace the stored entry with the bundle entry
+    Overwrite,
+    /// Reject the whole import
+    #[default]
+    Fail,
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "fail" => Ok(Self::Fail),
+            other => Err(anyhow!(
+                "Unknown conflict policy '{}' (expected skip, overwrite or fail)",
+                other
+            )),
+        }
+    }
+}
+
+/// Planned (or applied) outcome for a single bundle entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Update,
+    Skip,
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportItem {
+    pub kind: ConfigKind,
+    pub name: String,
+    pub action: ImportAction,
+}
+
+/// Result of importing a bundle.
+///
+/// Nothing is written when `errors` is non-empty or the import is a dry run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub items: Vec<ImportItem>,
+    pub errors: Vec<String>,
+}
+
+impl ImportReport {
+    pub fn count(&self, action: ImportAction) -> usize {
+        self.items.iter().filter(|i| i.action == action).count()
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        self.count(ImportAction::Conflict) > 0
+    }
+}
+
+impl ConfigBundle {
+    pub fn parse(content: &str, format: BundleFormat) -> Result<Self> {
+        let bundle: Self = match format {
+            BundleFormat::Yaml => {
+                serde_yaml::from_str(content).context("Failed to parse YAML bundle")?
+            }
+            BundleFormat::Json => {
+                serde_json::from_str(content).context("Failed to parse JSON bundle")?
+            }
+        };
+        if bundle.version > BUNDLE_VERSION {
+            bail!(
+                "Bundle version {} is newer than the supported version {}",
+                bundle.version,
+                BUNDLE_VERSION
+            );
+        }
+        Ok(bundle)
+    }
+
+    pub fn render(&self, format: BundleFormat) -> Result<String> {
+        match format {
+            BundleFormat::Yaml => serde_yaml::to_string(self).context("Failed to serialize bundle"),
+            BundleFormat::Json => {
+                serde_json::to_string_pretty(self).context("Failed to serialize bundle")
+            }
+        }
+    }
+
+    /// Work out what importing this bundle into a store holding `existing` would do.
+    ///
+    /// Reports duplicate names within the bundle, conflicts under
+    /// [`ConflictPolicy::Fail`] and flows referencing connectors or destinations
+    /// that are neither in the bundle nor in the store.
+    pub fn plan_import(&self, existing: &ConfigBundle, policy: ConflictPolicy) -> ImportReport {
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+
+        let existing_connectors: HashSet<&str> = existing
+            .connectors
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        let existing_destinations: HashSet<&str> = existing
+            .destinations
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        let existing_flows: HashSet<&str> =
+            existing.flows.iter().map(|e| e.name.as_str()).collect();
+
+        let entries = self
+            .connectors
+            .iter()
+            .map(|e| (ConfigKind::Connector, e.name.as_str(), &existing_connectors))
+            .chain(self.destinations.iter().map(|e| {
+                (
+                    ConfigKind::Destination,
+                    e.name.as_str(),
+                    &existing_destinations,
+                )
+            }))
+            .chain(
+                self.flows
+                    .iter()
+                    .map(|e| (ConfigKind::Flow, e.name.as_str(), &existing_flows)),
+            );
+
+        let mut seen = HashSet::new();
+        for (kind, name, existing_names) in entries {
+            if !seen.insert((kind, name)) {
+                errors.push(format!(
+                    "Cannot import {} '{}': it appears more than once in the bundle",
+                    kind.as_str(),
+                    name
+                ));
+                continue;
+            }
+
+            let action = if !existing_names.contains(name) {
+                ImportAction::Create
+            } else {
+                match policy {
+                    ConflictPolicy::Skip => ImportAction::Skip,
+                    ConflictPolicy::Overwrite => ImportAction::Update,
+                    ConflictPolicy::Fail => {
+                        errors.push(format!(
+                            "Cannot import {} '{}': it already exists",
+                            kind.as_str(),
+                            name
+                        ));
+                        ImportAction::Conflict
+                    }
+                }
+            };
+            items.push(ImportItem {
+                kind,
+                name: name.to_string(),
+                action,
+            });
+        }
+
+        // Flows may reference entries from the bundle or ones already stored
+        for flow in &self.flows {
+            let connector_known = existing_connectors.contains(flow.connector_name.as_str())
+                || self
+                    .connectors
+                    .iter()
+                    .any(|c| c.name == flow.connector_name);
+            if !connector_known {
+                errors.push(format!(
+                    "Flow '{}' references unknown connector '{}'",
+                    flow.name, flow.connector_name
+                ));
+            }
+
+            if flow.destination_names.is_empty() {
+                errors.push(format!(
+                    "Flow '{}' must have at least one destination",
+                    flow.name
+                ));
+            }
+            for dest_name in &flow.destination_names {
+                let destination_known = existing_destinations.contains(dest_name.as_str())
+                    || self.destinations.iter().any(|d| &d.name == dest_name);
+                if !destination_known {
+                    errors.push(format!(
+                        "Flow '{}' references unknown destination '{}'",
+                        flow.name, dest_name
+                    ));
+                }
+            }
+        }
+
+        ImportReport {
+            dry_run: false,
+            applied: false,
+            items,
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bundle() -> ConfigBundle {
+        ConfigBundle {
+            connectors: vec![ConnectorConfigEntry::new(
+                "nats".to_string(),
+                "nats".to_string(),
+                json!({"servers": ["nats://localhost:4222"]}),
+            )],
+            destinations: vec![DestinationConfigEntry::new(
+                "pg".to_string(),
+                "postgres".to_string(),
+                json!({"url": "postgresql://localhost/db"}),
+            )],
+            flows: vec![FlowConfigEntry::new(
+                "flow".to_string(),
+                "nats".to_string(),
+                vec!["pg".to_string(), "missing".to_string()],
+                100,
+            )],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = bundle();
+        for format in [BundleFormat::Yaml, BundleFormat::Json] {
+            let parsed = ConfigBundle::parse(&bundle.render(format).unwrap(), format).unwrap();
+            assert_eq!(parsed.version, BUNDLE_VERSION);
+            assert_eq!(parsed.connectors[0].name, "nats");
+            assert_eq!(parsed.flows[0].destination_names.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_plan_import_conflicts_and_references() {
+        let bundle = bundle();
+        let existing = ConfigBundle {
+            destinations: bundle.destinations.clone(),
+            ..Default::default()
+        };
+
+        let report = bundle.plan_import(&existing, ConflictPolicy::Skip);
+        assert_eq!(report.count(ImportAction::Create), 2);
+        assert_eq!(report.count(ImportAction::Skip), 1);
+        assert_eq!(
+            report.errors,
+            vec!["Flow 'flow' references unknown destination 'missing'"]
+        );
+
+        let report = bundle.plan_import(&existing, ConflictPolicy::Fail);
+        assert!(report.has_conflicts());
+        assert_eq!(report.errors.len(), 2);
+    }
+}
@@ -0,0 +1,374 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cdc_core::{is_secret_field, is_secret_ref, redact_url};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Environment variable holding the base64-encoded master key
+pub const MASTER_KEY_ENV: &str = "CDC_MASTER_KEY";
+
+/// Environment variable pointing at a file containing the base64-encoded master key
+pub const MASTER_KEY_FILE_ENV: &str = "CDC_MASTER_KEY_FILE";
+
+/// Key under which an encrypted value's envelope is stored
+const ENVELOPE_KEY: &str = "$enc";
+
+const ENVELOPE_VERSION: u64 = 1;
+const NONCE_LEN: usize = 12;
+
+/// 256-bit key-encryption key used to wrap per-value data keys
+#[derive(Clone)]
+pub struct MasterKey {
+    id: String,
+    key: Key<Aes256Gcm>,
+}
+
+impl MasterKey {
+    /// Generate a new random master key
+    pub fn generate() -> Self {
+        Self::from_key(Aes256Gcm::generate_key(OsRng))
+    }
+
+    /// Parse a base64-encoded 32-byte key
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .context("Master key is not valid base64")?;
+        if bytes.len() != 32 {
+            bail!("Master key must be 32 bytes, got {}", bytes.len());
+        }
+        Ok(Self::from_key(*Key::<Aes256Gcm>::from_slice(&bytes)))
+    }
+
+    /// Read a base64-encoded key from a file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let encoded = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read master key file {}", path.display()))?;
+        Self::from_base64(&encoded)
+    }
+
+    /// Load the key from `CDC_MASTER_KEY` or `CDC_MASTER_KEY_FILE`, if either is set
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(encoded) = std::env::var(MASTER_KEY_ENV) {
+            return Self::from_base64(&encoded).map(Some);
+        }
+        if let Ok(path) = std::env::var(MASTER_KEY_FILE_ENV) {
+            return Self::from_file(path).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn from_key(key: Key<Aes256Gcm>) -> Self {
+        let digest = Sha256::digest(key.as_slice());
+        let id = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        Self { id, key }
+    }
+
+    /// Short fingerprint of the key, stored with every value it encrypts
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Base64 encoding of the key, as accepted by `from_base64`
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.key.as_slice())
+    }
+}
+
+/// Envelope encryption for the secret fields of connector and destination configs.
+///
+/// Every secret value gets its own random data key; the value is encrypted
+/// with the data key and the data key is wrapped with the master key. A value
+/// is secret when its field is marked secret for the component type (or looks
+/// like one, see `cdc_core::is_secret_field`) or when it's a URL with a
+/// password. Secret references (`${env:..}`) are left as they are.
+///
+/// Values are always encrypted with the current key; previous keys are only
+/// used to decrypt, so rows can be read while a rotation is in progress.
+pub struct ConfigCipher {
+    key: MasterKey,
+    previous_keys: Vec<MasterKey>,
+    connector_secret_fields: HashMap<String, HashSet<String>>,
+    destination_secret_fields: HashMap<String, HashSet<String>>,
+}
+
+impl ConfigCipher {
+    pub fn new(key: MasterKey) -> Self {
+        Self {
+            key,
+            previous_keys: Vec::new(),
+            connector_secret_fields: HashMap::new(),
+            destination_secret_fields: HashMap::new(),
+        }
+    }
+
+    /// Also accept values encrypted with an older master key
+    pub fn with_previous_key(mut self, key: MasterKey) -> Self {
+        self.previous_keys.push(key);
+        self
+    }
+
+    /// Declare the secret fields of a connector type
+    pub fn with_connector_secret_fields(
+        mut self,
+        connector_type: impl Into<String>,
+        fields: HashSet<String>,
+    ) -> Self {
+        self.connector_secret_fields
+            .insert(connector_type.into(), fields);
+        self
+    }
+
+    /// Declare the secret fields of a destination type
+    pub fn with_destination_secret_fields(
+        mut self,
+        destination_type: impl Into<String>,
+        fields: HashSet<String>,
+    ) -> Self {
+        self.destination_secret_fields
+            .insert(destination_type.into(), fields);
+        self
+    }
+
+    /// Fingerprint of the master key in use
+    pub fn key_id(&self) -> &str {
+        self.key.id()
+    }
+
+    /// Encrypt the secret values of a connector config
+    pub fn encrypt_connector_config(&self, connector_type: &str, config: &Value) -> Result<Value> {
+        let fields = self.connector_secret_fields.get(connector_type);
+        self.encrypt_secrets(config, fields.unwrap_or(&HashSet::new()))
+    }
+
+    /// Encrypt the secret values of a destination config
+    pub fn encrypt_destination_config(
+        &self,
+        destination_type: &str,
+        config: &Value,
+    ) -> Result<Value> {
+        let fields = self.destination_secret_fields.get(destination_type);
+        self.encrypt_secrets(config, fields.unwrap_or(&HashSet::new()))
+    }
+
+    /// Decrypt every encrypted value in a config
+    pub fn decrypt_config(&self, config: &Value) -> Result<Value> {
+        match config {
+            Value::Object(map) => {
+                if let Some(envelope) = envelope(config) {
+                    return self.decrypt_value(envelope).map(Value::String);
+                }
+                let mut decrypted = serde_json::Map::with_capacity(map.len());
+                for (key, value) in map {
+                    decrypted.insert(key.clone(), self.decrypt_config(value)?);
+                }
+                Ok(Value::Object(decrypted))
+            }
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.decrypt_config(item))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array),
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn encrypt_secrets(&self, config: &Value, fields: &HashSet<String>) -> Result<Value> {
+        match config {
+            Value::Object(map) if envelope(config).is_none() => {
+                let mut encrypted = serde_json::Map::with_capacity(map.len());
+                for (key, value) in map {
+                    let value = match value {
+                        Value::String(s) if is_secret_field(key, fields) && !is_secret_ref(s) => {
+                            self.encrypt_value(s)?
+                        }
+                        _ => self.encrypt_secrets(value, fields)?,
+                    };
+                    encrypted.insert(key.clone(), value);
+                }
+                Ok(Value::Object(encrypted))
+            }
+            Value::Array(items) => items
+                .iter()
+                .map(|item| self.encrypt_secrets(item, fields))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array),
+            Value::String(s) if redact_url(s) != *s => self.encrypt_value(s),
+            other => Ok(other.clone()),
+        }
+    }
+
+    fn encrypt_value(&self, plaintext: &str) -> Result<Value> {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt config value"))?;
+
+        let wrap_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut wrapped_key = wrap_nonce.to_vec();
+        wrapped_key.extend(
+            Aes256Gcm::new(&self.key.key)
+                .encrypt(&wrap_nonce, data_key.as_slice())
+                .map_err(|_| anyhow!("Failed to wrap data key"))?,
+        );
+
+        Ok(json!({
+            ENVELOPE_KEY: {
+                "v": ENVELOPE_VERSION,
+                "kid": self.key.id(),
+                "dek": STANDARD.encode(wrapped_key),
+                "nonce": STANDARD.encode(nonce),
+                "ct": STANDARD.encode(ciphertext),
+            }
+        }))
+    }
+
+    fn decrypt_value(&self, envelope: &Value) -> Result<String> {
+        let field = |name: &str| {
+            envelope
+                .get(name)
+                .ok_or_else(|| anyhow!("Encrypted value is missing '{}'", name))
+        };
+
+        if field("v")?.as_u64() != Some(ENVELOPE_VERSION) {
+            bail!("Unsupported encrypted value version: {}", field("v")?);
+        }
+        let kid = field("kid")?.as_str().unwrap_or_default();
+        let master_key = std::iter::once(&self.key)
+            .chain(&self.previous_keys)
+            .find(|key| key.id() == kid)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Value was encrypted with master key {}, but the configured key is {}",
+                    kid,
+                    self.key.id()
+                )
+            })?;
+
+        let decode = |name: &str| -> Result<Vec<u8>> {
+            let encoded = field(name)?
+                .as_str()
+                .ok_or_else(|| anyhow!("Encrypted value field '{}' is not a string", name))?;
+            STANDARD
+                .decode(encoded)
+                .with_context(|| format!("Encrypted value field '{}' is not valid base64", name))
+        };
+        let wrapped_key = decode("dek")?;
+        let nonce = decode("nonce")?;
+        let ciphertext = decode("ct")?;
+        if wrapped_key.len() <= NONCE_LEN || nonce.len() != NONCE_LEN {
+            bail!("Encrypted value is malformed");
+        }
+
+        let (wrap_nonce, wrapped_key) = wrapped_key.split_at(NONCE_LEN);
+        let data_key = Aes256Gcm::new(&master_key.key)
+            .decrypt(Nonce::from_slice(wrap_nonce), wrapped_key)
+            .map_err(|_| anyhow!("Failed to unwrap data key"))?;
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key))
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Failed to decrypt config value"))?;
+
+        String::from_utf8(plaintext).context("Decrypted config value is not UTF-8")
+    }
+}
+
+fn envelope(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.get(ENVELOPE_KEY),
+        _ => None,
+    }
+}
+
+/// Check whether a config contains any encrypted values
+pub fn is_encrypted(config: &Value) -> bool {
+    match config {
+        Value::Object(map) => envelope(config).is_some() || map.values().any(is_encrypted),
+        Value::Array(items) => items.iter().any(is_encrypted),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher(key: MasterKey) -> ConfigCipher {
+        ConfigCipher::new(key)
+            .with_destination_secret_fields("postgres", HashSet::from(["credential".to_string()]))
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher = cipher(MasterKey::generate());
+        let config = json!({
+            "url": "postgresql://app:hunter2@db:5432/cdc",
+            "credential": "abc",
+            "password": "${env:PG_PASSWORD}",
+            "schema": "public",
+            "max_connections": 10
+        });
+
+        let encrypted = cipher
+            .encrypt_destination_config("postgres", &config)
+            .unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(encrypted["url"].get(ENVELOPE_KEY).is_some());
+        assert!(encrypted["credential"].get(ENVELOPE_KEY).is_some());
+        assert_eq!(encrypted["password"], "${env:PG_PASSWORD}");
+        assert_eq!(encrypted["schema"], "public");
+        assert!(!encrypted.to_string().contains("hunter2"));
+
+        // Encrypting twice leaves existing envelopes alone
+        let again = cipher
+            .encrypt_destination_config("postgres", &encrypted)
+            .unwrap();
+        assert_eq!(again, encrypted);
+
+        assert_eq!(cipher.decrypt_config(&encrypted).unwrap(), config);
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_key_fails() {
+        let encrypted = cipher(MasterKey::generate())
+            .encrypt_destination_config("postgres", &json!({"credential": "abc"}))
+            .unwrap();
+
+        assert!(cipher(MasterKey::generate())
+            .decrypt_config(&encrypted)
+            .is_err());
+    }
+
+    #[test]
+    fn test_previous_key_decrypts_during_rotation() {
+        let old_key = MasterKey::generate();
+        let config = json!({"credential": "abc"});
+        let old = cipher(old_key.clone())
+            .encrypt_destination_config("postgres", &config)
+            .unwrap();
+
+        let new_cipher = cipher(MasterKey::generate()).with_previous_key(old_key);
+        let rotated = new_cipher
+            .encrypt_destination_config("postgres", &new_cipher.decrypt_config(&old).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            rotated["credential"][ENVELOPE_KEY]["kid"],
+            new_cipher.key_id()
+        );
+        assert_eq!(new_cipher.decrypt_config(&rotated).unwrap(), config);
+    }
+
+    #[test]
+    fn test_master_key_base64_roundtrip() {
+        let key = MasterKey::generate();
+        let parsed = MasterKey::from_base64(&key.to_base64()).unwrap();
+        assert_eq!(parsed.id(), key.id());
+        assert!(MasterKey::from_base64("c2hvcnQ=").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use cdc_core::FlowStatus;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::time::Duration;
+
+/// Ownership of a flow by one instance, kept alive by heartbeats
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowLease {
+    pub flow_name: String,
+    pub owner: String,
+    /// Flow status as last reported by the owner
+    pub status: Option<FlowStatus>,
+    pub acquired_at: DateTime<Utc>,
+    pub renewed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// The owner stopped renewing; any instance may take the flow over
+    pub expired: bool,
+}
+
+/// Flow leases in the `flow_leases` table, held on behalf of one instance.
+///
+/// A flow runs only on the instance holding its lease. The owner renews its
+/// leases every heartbeat; once a lease expires another instance can acquire it.
+/// Releasing a lease expires it but keeps the row, so the last reported status
+/// still tells other instances whether the flow should be taken over.
+#[derive(Clone)]
+pub struct FlowLeases {
+    pool: PgPool,
+    instance_id: String,
+    ttl: Duration,
+}
+
+impl FlowLeases {
+    pub(crate) fn new(pool: PgPool, instance_id: String, ttl: Duration) -> Self {
+        Self {
+            pool,
+            instance_id,
+            ttl,
+        }
+    }
+
+    /// Create the lease table if it doesn't exist yet
+    pub(crate) async fn ensure_table(tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS flow_leases (
+                flow_name VARCHAR(255) PRIMARY KEY,
+                owner VARCHAR(255) NOT NULL,
+                status JSONB,
+                acquired_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                renewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                expires_at TIMESTAMPTZ NOT NULL
+            )",
+        )
+        .execute(&mut **tx)
+        .await
+        .context("Failed to create flow_leases table")?;
+
+        Ok(())
+    }
+
+    /// Identifier of the instance these leases are held for
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// How long a lease stays valid without a heartbeat
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Take the lease for a flow if it is free, expired or already ours
+    pub async fn try_acquire(&self, flow_name: &str) -> Result<bool> {
+        let row = sqlx::query(
+            "INSERT INTO flow_leases (flow_name, owner, acquired_at, renewed_at, expires_at)
+             VALUES ($1, $2, NOW(), NOW(), NOW() + make_interval(secs => $3))
+             ON CONFLICT (flow_name) DO UPDATE SET
+                owner = EXCLUDED.owner,
+                acquired_at = CASE WHEN flow_leases.owner = EXCLUDED.owner
+                    THEN flow_leases.acquired_at ELSE NOW() END,
+                renewed_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+             WHERE flow_leases.owner = EXCLUDED.owner OR flow_leases.expires_at < NOW()
+             RETURNING owner",
+        )
+        .bind(flow_name)
+        .bind(&self.instance_id)
+        .bind(self.ttl.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await
+        .with_context(|| format!("Failed to acquire lease for flow '{}'", flow_name))?;
+
+        Ok(row.is_some())
+    }
+
+    /// Extend our lease and publish the flow status; `false` if the lease was lost
+    pub async fn renew(&self, flow_name: &str, status: &FlowStatus) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE flow_leases
+             SET status = $3, renewed_at = NOW(), expires_at = NOW() + make_interval(secs => $4)
+             WHERE flow_name = $1 AND owner = $2",
+        )
+        .bind(flow_name)
+        .bind(&self.instance_id)
+        .bind(serde_json::to_value(status)?)
+        .bind(self.ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to renew lease for flow '{}'", flow_name))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Give up our lease on a flow, recording the status it was left in
+    pub async fn release(&self, flow_name: &str, status: &FlowStatus) -> Result<()> {
+        sqlx::query(
+            "UPDATE flow_leases SET status = $3, expires_at = NOW()
+             WHERE flow_name = $1 AND owner = $2",
+        )
+        .bind(flow_name)
+        .bind(&self.instance_id)
+        .bind(serde_json::to_value(status)?)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to release lease for flow '{}'", flow_name))?;
+
+        Ok(())
+    }
+
+    /// Give up every lease held by this instance, e.g. on shutdown.
+    ///
+    /// Flows last reported as running are taken over by another instance.
+    pub async fn release_all(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE flow_leases SET expires_at = NOW() WHERE owner = $1 AND expires_at > NOW()",
+        )
+        .bind(&self.instance_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to release flow leases")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Drop the lease of a deleted flow, whoever holds it
+    pub async fn remove(&self, flow_name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM flow_leases WHERE flow_name = $1")
+            .bind(flow_name)
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("Failed to remove lease for flow '{}'", flow_name))?;
+
+        Ok(())
+    }
+
+    pub async fn get(&self, flow_name: &str) -> Result<Option<FlowLease>> {
+        let row = sqlx::query(
+            "SELECT flow_name, owner, status, acquired_at, renewed_at, expires_at,
+                    expires_at < NOW() AS expired
+             FROM flow_leases WHERE flow_name = $1",
+        )
+        .bind(flow_name)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to get flow lease")?;
+
+        Ok(row.map(|r| lease_from_row(&r)))
+    }
+
+    pub async fn list(&self) -> Result<Vec<FlowLease>> {
+        let rows = sqlx::query(
+            "SELECT flow_name, owner, status, acquired_at, renewed_at, expires_at,
+                    expires_at < NOW() AS expired
+             FROM flow_leases ORDER BY flow_name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list flow leases")?;
+
+        Ok(rows.iter().map(lease_from_row).collect())
+    }
+}
+
+fn lease_from_row(row: &sqlx::postgres::PgRow) -> FlowLease {
+    FlowLease {
+        flow_name: row.get("flow_name"),
+        owner: row.get("owner"),
+        status: row
+            .get::<Option<serde_json::Value>, _>("status")
+            .and_then(|status| serde_json::from_value(status).ok()),
+        acquired_at: row.get("acquired_at"),
+        renewed_at: row.get("renewed_at"),
+        expires_at: row.get("expires_at"),
+        expired: row.get("expired"),
+    }
+}
@@ -1,11 +1,23 @@
 mod backend;
+mod bundle;
+mod crypto;
+mod lease;
 mod models;
 mod pg_store;
 mod store;
 mod unified_store;
 
 pub use backend::ConfigStoreBackend;
-pub use models::{ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry};
+pub use bundle::{
+    BundleFormat, ConfigBundle, ConflictPolicy, ImportAction, ImportItem, ImportReport,
+    BUNDLE_VERSION,
+};
+pub use crypto::{is_encrypted, ConfigCipher, MasterKey, MASTER_KEY_ENV, MASTER_KEY_FILE_ENV};
+pub use lease::{FlowLease, FlowLeases};
+pub use models::{
+    ConfigKind, ConfigRevision, ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry,
+    RevisionOperation, RollbackError,
+};
 pub use pg_store::PgConfigStore;
 pub use store::ConfigStore;
 pub use unified_store::UnifiedConfigStore;
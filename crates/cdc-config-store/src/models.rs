@@ -144,6 +144,118 @@ impl FlowConfigEntry {
     }
 }
 
+/// Kind of config entry tracked in revision history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigKind {
+    Connector,
+    Destination,
+    Flow,
+}
+
+impl ConfigKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Connector => "connector",
+            Self::Destination => "destination",
+            Self::Flow => "flow",
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "connector" => Ok(Self::Connector),
+            "destination" => Ok(Self::Destination),
+            "flow" => Ok(Self::Flow),
+            other => Err(anyhow::anyhow!("Unknown config kind '{}'", other)),
+        }
+    }
+}
+
+/// Change that produced a revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevisionOperation {
+    Create,
+    Update,
+    Delete,
+    Rollback,
+}
+
+impl RevisionOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Rollback => "rollback",
+        }
+    }
+}
+
+impl std::str::FromStr for RevisionOperation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            "rollback" => Ok(Self::Rollback),
+            other => Err(anyhow::anyhow!("Unknown revision operation '{}'", other)),
+        }
+    }
+}
+
+/// One entry in the append-only revision history of a config entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigRevision {
+    /// Kind of the config entry
+    pub kind: ConfigKind,
+
+    /// Name of the config entry
+    pub name: String,
+
+    /// Revision number, starting at 1 for each entry
+    pub revision: i64,
+
+    /// Change that produced this revision
+    pub operation: RevisionOperation,
+
+    /// The full entry as of this revision (absent for deletions)
+    pub snapshot: Option<Value>,
+
+    /// When this revision was recorded
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Why a rollback was refused; any other rollback error is a storage failure
+#[derive(Debug, thiserror::Error)]
+pub enum RollbackError {
+    #[error("Revision {revision} of {} '{name}' not found", kind.as_str())]
+    NotFound {
+        kind: ConfigKind,
+        name: String,
+        revision: i64,
+    },
+
+    #[error("Revision {revision} of {} '{name}' is a deletion and cannot be restored", kind.as_str())]
+    Deletion {
+        kind: ConfigKind,
+        name: String,
+        revision: i64,
+    },
+
+    /// The restored entry is no longer valid, e.g. a flow whose connector was deleted
+    #[error("{0}")]
+    Invalid(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
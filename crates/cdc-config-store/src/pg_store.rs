@@ -1,9 +1,20 @@
-use crate::models::{ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry};
-use anyhow::{anyhow, Context, Result};
-use sqlx::{PgPool, Row};
+use crate::bundle::{ConfigBundle, ImportAction};
+use crate::crypto::{is_encrypted, ConfigCipher};
+use crate::lease::FlowLeases;
+use crate::models::{
+    ConfigKind, ConfigRevision, ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry,
+    RevisionOperation, RollbackError,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::time::Duration;
 
 pub struct PgConfigStore {
     pool: PgPool,
+    cipher: Option<ConfigCipher>,
 }
 
 impl PgConfigStore {
@@ -13,29 +24,244 @@ impl PgConfigStore {
             .await
             .context("Failed to connect to PostgreSQL")?;
 
-        Ok(Self { pool })
+        let store = Self { pool, cipher: None };
+        store.ensure_tables().await?;
+        Ok(store)
+    }
+
+    /// Flow leases held on behalf of `instance_id`, valid for `ttl` without renewal
+    pub fn flow_leases(&self, instance_id: impl Into<String>, ttl: Duration) -> FlowLeases {
+        FlowLeases::new(self.pool.clone(), instance_id.into(), ttl)
+    }
+
+    /// Create the history and lease tables if they don't exist yet
+    async fn ensure_tables(&self) -> Result<()> {
+        // Instances starting together would otherwise race on CREATE TABLE IF NOT EXISTS
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('cdc_config_store_schema'))")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to lock config store schema")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS config_history (
+                id BIGSERIAL PRIMARY KEY,
+                kind VARCHAR(32) NOT NULL,
+                name VARCHAR(255) NOT NULL,
+                revision BIGINT NOT NULL,
+                operation VARCHAR(32) NOT NULL,
+                snapshot JSONB,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE (kind, name, revision)
+            )",
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to create config_history table")?;
+
+        FlowLeases::ensure_table(&mut tx).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Encrypt secret config fields at rest with the given cipher
+    pub fn with_cipher(mut self, cipher: ConfigCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    // ========== Encryption ==========
+
+    fn encrypt_connector_config(&self, entry: &ConnectorConfigEntry) -> Result<Value> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt_connector_config(&entry.connector_type, &entry.config),
+            None => Ok(entry.config.clone()),
+        }
+    }
+
+    fn encrypt_destination_config(&self, entry: &DestinationConfigEntry) -> Result<Value> {
+        match &self.cipher {
+            Some(cipher) => {
+                cipher.encrypt_destination_config(&entry.destination_type, &entry.config)
+            }
+            None => Ok(entry.config.clone()),
+        }
+    }
+
+    fn decrypt_config(&self, name: &str, config: Value) -> Result<Value> {
+        match &self.cipher {
+            Some(cipher) => cipher
+                .decrypt_config(&config)
+                .with_context(|| format!("Failed to decrypt config of '{}'", name)),
+            None if is_encrypted(&config) => bail!(
+                "Config of '{}' is encrypted but no master key is configured",
+                name
+            ),
+            None => Ok(config),
+        }
+    }
+
+    /// Re-encrypt every connector and destination config with a new cipher.
+    ///
+    /// Existing values are decrypted with `new_cipher`, so it must carry the
+    /// old master key via `with_previous_key`; plaintext rows are simply
+    /// encrypted. Runs in one transaction and can be safely re-run. Returns the
+    /// number of rows rewritten.
+    pub async fn rotate_key(&self, new_cipher: &ConfigCipher) -> Result<usize> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        let mut rotated = 0;
+
+        let rows = sqlx::query("SELECT name, connector_type, config FROM connectors FOR UPDATE")
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to read connectors")?;
+        for row in rows {
+            let name: String = row.get("name");
+            let config = new_cipher
+                .decrypt_config(&row.get::<Value, _>("config"))
+                .with_context(|| format!("Failed to decrypt config of '{}'", name))?;
+            let config = new_cipher.encrypt_connector_config(row.get("connector_type"), &config)?;
+            sqlx::query("UPDATE connectors SET config = $2 WHERE name = $1")
+                .bind(&name)
+                .bind(&config)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update connector")?;
+            rotated += 1;
+        }
+
+        let rows =
+            sqlx::query("SELECT name, destination_type, config FROM destinations FOR UPDATE")
+                .fetch_all(&mut *tx)
+                .await
+                .context("Failed to read destinations")?;
+        for row in rows {
+            let name: String = row.get("name");
+            let config = new_cipher
+                .decrypt_config(&row.get::<Value, _>("config"))
+                .with_context(|| format!("Failed to decrypt config of '{}'", name))?;
+            let config =
+                new_cipher.encrypt_destination_config(row.get("destination_type"), &config)?;
+            sqlx::query("UPDATE destinations SET config = $2 WHERE name = $1")
+                .bind(&name)
+                .bind(&config)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update destination")?;
+            rotated += 1;
+        }
+
+        // Snapshots in the revision history hold configs too
+        let rows = sqlx::query(
+            "SELECT id, kind, name, snapshot FROM config_history
+             WHERE snapshot IS NOT NULL AND kind IN ('connector', 'destination')
+             FOR UPDATE",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to read config history")?;
+        for row in rows {
+            let name: String = row.get("name");
+            let mut snapshot = new_cipher
+                .decrypt_config(&row.get::<Value, _>("snapshot"))
+                .with_context(|| format!("Failed to decrypt history of '{}'", name))?;
+            let kind: ConfigKind = row.get::<String, _>("kind").parse()?;
+            let config = match kind {
+                ConfigKind::Connector => new_cipher.encrypt_connector_config(
+                    snapshot["connector_type"].as_str().unwrap_or_default(),
+                    &snapshot["config"],
+                )?,
+                _ => new_cipher.encrypt_destination_config(
+                    snapshot["destination_type"].as_str().unwrap_or_default(),
+                    &snapshot["config"],
+                )?,
+            };
+            snapshot["config"] = config;
+            sqlx::query("UPDATE config_history SET snapshot = $2 WHERE id = $1")
+                .bind(row.get::<i64, _>("id"))
+                .bind(&snapshot)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to update config history")?;
+        }
+
+        tx.commit().await.context("Failed to commit key rotation")?;
+        Ok(rotated)
     }
 
     // ========== Connector Management ==========
 
     pub async fn add_connector(&self, entry: &ConnectorConfigEntry) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.insert_connector(&mut tx, entry).await?;
+        tx.commit().await.context("Failed to commit connector")?;
+
+        Ok(())
+    }
+
+    async fn insert_connector(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        entry: &ConnectorConfigEntry,
+    ) -> Result<()> {
+        let config = self.encrypt_connector_config(entry)?;
+        let snapshot = snapshot_with_config(entry, &config)?;
+
         sqlx::query(
             "INSERT INTO connectors (name, connector_type, config, description, tags)
              VALUES ($1, $2, $3, $4, $5)",
         )
         .bind(&entry.name)
         .bind(&entry.connector_type)
-        .bind(&entry.config)
+        .bind(&config)
         .bind(&entry.description)
         .bind(&entry.tags)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to insert connector")?;
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Connector,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
     pub async fn update_connector(&self, name: &str, entry: &ConnectorConfigEntry) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.update_connector_in(&mut tx, name, entry).await?;
+        tx.commit().await.context("Failed to commit connector")?;
+
+        Ok(())
+    }
+
+    async fn update_connector_in(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+        entry: &ConnectorConfigEntry,
+    ) -> Result<()> {
+        let config = self.encrypt_connector_config(entry)?;
+        let snapshot = snapshot_with_config(entry, &config)?;
+
         let result = sqlx::query(
             "UPDATE connectors 
              SET connector_type = $2, config = $3, description = $4, tags = $5
@@ -43,10 +269,10 @@ impl PgConfigStore {
         )
         .bind(name)
         .bind(&entry.connector_type)
-        .bind(&entry.config)
+        .bind(&config)
         .bind(&entry.description)
         .bind(&entry.tags)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to update connector")?;
 
@@ -54,6 +280,15 @@ impl PgConfigStore {
             return Err(anyhow!("Connector '{}' not found", name));
         }
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Connector,
+            name,
+            RevisionOperation::Update,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -78,9 +313,14 @@ impl PgConfigStore {
             ));
         }
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
         let result = sqlx::query("DELETE FROM connectors WHERE name = $1")
             .bind(name)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .context("Failed to delete connector")?;
 
@@ -88,6 +328,18 @@ impl PgConfigStore {
             return Err(anyhow!("Connector '{}' not found", name));
         }
 
+        Self::record_revision(
+            &mut tx,
+            ConfigKind::Connector,
+            name,
+            RevisionOperation::Delete,
+            None,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .context("Failed to commit connector deletion")?;
+
         Ok(())
     }
 
@@ -102,15 +354,18 @@ impl PgConfigStore {
         .await
         .context("Failed to get connector")?;
 
-        Ok(row.map(|r| ConnectorConfigEntry {
-            name: r.get("name"),
-            connector_type: r.get("connector_type"),
-            config: r.get("config"),
-            description: r.get("description"),
-            tags: r.get("tags"),
-            created_at: r.get("created_at"),
-            updated_at: r.get("updated_at"),
-        }))
+        row.map(|r| {
+            Ok(ConnectorConfigEntry {
+                config: self.decrypt_config(name, r.get("config"))?,
+                name: r.get("name"),
+                connector_type: r.get("connector_type"),
+                description: r.get("description"),
+                tags: r.get("tags"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+            })
+        })
+        .transpose()
     }
 
     pub async fn list_connectors(&self) -> Result<Vec<ConnectorConfigEntry>> {
@@ -123,36 +378,66 @@ impl PgConfigStore {
         .await
         .context("Failed to list connectors")?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| ConnectorConfigEntry {
-                name: r.get("name"),
-                connector_type: r.get("connector_type"),
-                config: r.get("config"),
-                description: r.get("description"),
-                tags: r.get("tags"),
-                created_at: r.get("created_at"),
-                updated_at: r.get("updated_at"),
+        rows.into_iter()
+            .map(|r| {
+                let name: String = r.get("name");
+                Ok(ConnectorConfigEntry {
+                    config: self.decrypt_config(&name, r.get("config"))?,
+                    name,
+                    connector_type: r.get("connector_type"),
+                    description: r.get("description"),
+                    tags: r.get("tags"),
+                    created_at: r.get("created_at"),
+                    updated_at: r.get("updated_at"),
+                })
             })
-            .collect())
+            .collect()
     }
 
     // ========== Destination Management ==========
 
     pub async fn add_destination(&self, entry: &DestinationConfigEntry) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.insert_destination(&mut tx, entry).await?;
+        tx.commit().await.context("Failed to commit destination")?;
+
+        Ok(())
+    }
+
+    async fn insert_destination(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        entry: &DestinationConfigEntry,
+    ) -> Result<()> {
+        let config = self.encrypt_destination_config(entry)?;
+        let snapshot = snapshot_with_config(entry, &config)?;
+
         sqlx::query(
             "INSERT INTO destinations (name, destination_type, config, description, tags)
              VALUES ($1, $2, $3, $4, $5)",
         )
         .bind(&entry.name)
         .bind(&entry.destination_type)
-        .bind(&entry.config)
+        .bind(&config)
         .bind(&entry.description)
         .bind(&entry.tags)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to insert destination")?;
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Destination,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -161,6 +446,26 @@ impl PgConfigStore {
         name: &str,
         entry: &DestinationConfigEntry,
     ) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.update_destination_in(&mut tx, name, entry).await?;
+        tx.commit().await.context("Failed to commit destination")?;
+
+        Ok(())
+    }
+
+    async fn update_destination_in(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+        entry: &DestinationConfigEntry,
+    ) -> Result<()> {
+        let config = self.encrypt_destination_config(entry)?;
+        let snapshot = snapshot_with_config(entry, &config)?;
+
         let result = sqlx::query(
             "UPDATE destinations 
              SET destination_type = $2, config = $3, description = $4, tags = $5
@@ -168,10 +473,10 @@ impl PgConfigStore {
         )
         .bind(name)
         .bind(&entry.destination_type)
-        .bind(&entry.config)
+        .bind(&config)
         .bind(&entry.description)
         .bind(&entry.tags)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to update destination")?;
 
@@ -179,6 +484,15 @@ impl PgConfigStore {
             return Err(anyhow!("Destination '{}' not found", name));
         }
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Destination,
+            name,
+            RevisionOperation::Update,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
@@ -203,9 +517,14 @@ impl PgConfigStore {
             ));
         }
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
         let result = sqlx::query("DELETE FROM destinations WHERE name = $1")
             .bind(name)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .context("Failed to delete destination")?;
 
@@ -213,6 +532,18 @@ impl PgConfigStore {
             return Err(anyhow!("Destination '{}' not found", name));
         }
 
+        Self::record_revision(
+            &mut tx,
+            ConfigKind::Destination,
+            name,
+            RevisionOperation::Delete,
+            None,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .context("Failed to commit destination deletion")?;
+
         Ok(())
     }
 
@@ -227,15 +558,18 @@ impl PgConfigStore {
         .await
         .context("Failed to get destination")?;
 
-        Ok(row.map(|r| DestinationConfigEntry {
-            name: r.get("name"),
-            destination_type: r.get("destination_type"),
-            config: r.get("config"),
-            description: r.get("description"),
-            tags: r.get("tags"),
-            created_at: r.get("created_at"),
-            updated_at: r.get("updated_at"),
-        }))
+        row.map(|r| {
+            Ok(DestinationConfigEntry {
+                config: self.decrypt_config(name, r.get("config"))?,
+                name: r.get("name"),
+                destination_type: r.get("destination_type"),
+                description: r.get("description"),
+                tags: r.get("tags"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+            })
+        })
+        .transpose()
     }
 
     pub async fn list_destinations(&self) -> Result<Vec<DestinationConfigEntry>> {
@@ -248,23 +582,43 @@ impl PgConfigStore {
         .await
         .context("Failed to list destinations")?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| DestinationConfigEntry {
-                name: r.get("name"),
-                destination_type: r.get("destination_type"),
-                config: r.get("config"),
-                description: r.get("description"),
-                tags: r.get("tags"),
-                created_at: r.get("created_at"),
-                updated_at: r.get("updated_at"),
+        rows.into_iter()
+            .map(|r| {
+                let name: String = r.get("name");
+                Ok(DestinationConfigEntry {
+                    config: self.decrypt_config(&name, r.get("config"))?,
+                    name,
+                    destination_type: r.get("destination_type"),
+                    description: r.get("description"),
+                    tags: r.get("tags"),
+                    created_at: r.get("created_at"),
+                    updated_at: r.get("updated_at"),
+                })
             })
-            .collect())
+            .collect()
     }
 
     // ========== Flow Management ==========
 
     pub async fn add_flow(&self, entry: &FlowConfigEntry) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.insert_flow(&mut tx, entry).await?;
+        tx.commit().await.context("Failed to commit flow")?;
+
+        Ok(())
+    }
+
+    async fn insert_flow(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        entry: &FlowConfigEntry,
+    ) -> Result<()> {
+        let snapshot = serde_json::to_value(entry).context("Failed to serialize flow")?;
+
         sqlx::query(
             "INSERT INTO flows (name, connector_name, destination_names, batch_size, auto_start, description)
              VALUES ($1, $2, $3, $4, $5, $6)"
@@ -275,14 +629,42 @@ impl PgConfigStore {
         .bind(entry.batch_size as i32)
         .bind(entry.auto_start)
         .bind(&entry.description)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to insert flow")?;
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Flow,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
     pub async fn update_flow(&self, name: &str, entry: &FlowConfigEntry) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+        self.update_flow_in(&mut tx, name, entry).await?;
+        tx.commit().await.context("Failed to commit flow")?;
+
+        Ok(())
+    }
+
+    async fn update_flow_in(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+        entry: &FlowConfigEntry,
+    ) -> Result<()> {
+        let snapshot = serde_json::to_value(entry).context("Failed to serialize flow")?;
+
         let result = sqlx::query(
             "UPDATE flows 
              SET connector_name = $2, destination_names = $3, batch_size = $4, 
@@ -295,7 +677,7 @@ impl PgConfigStore {
         .bind(entry.batch_size as i32)
         .bind(entry.auto_start)
         .bind(&entry.description)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .context("Failed to update flow")?;
 
@@ -303,13 +685,27 @@ impl PgConfigStore {
             return Err(anyhow!("Flow '{}' not found", name));
         }
 
+        Self::record_revision(
+            tx,
+            ConfigKind::Flow,
+            name,
+            RevisionOperation::Update,
+            Some(snapshot),
+        )
+        .await?;
+
         Ok(())
     }
 
     pub async fn delete_flow(&self, name: &str) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
         let result = sqlx::query("DELETE FROM flows WHERE name = $1")
             .bind(name)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .context("Failed to delete flow")?;
 
@@ -317,6 +713,18 @@ impl PgConfigStore {
             return Err(anyhow!("Flow '{}' not found", name));
         }
 
+        Self::record_revision(
+            &mut tx,
+            ConfigKind::Flow,
+            name,
+            RevisionOperation::Delete,
+            None,
+        )
+        .await?;
+        tx.commit()
+            .await
+            .context("Failed to commit flow deletion")?;
+
         Ok(())
     }
 
@@ -370,6 +778,251 @@ impl PgConfigStore {
             .collect())
     }
 
+    // ========== Bulk Import ==========
+
+    /// Apply a planned import in one transaction, so a failing entry leaves the store unchanged.
+    ///
+    /// `actions` holds one action per bundle entry: connectors, then destinations, then flows.
+    pub async fn import(&self, bundle: &ConfigBundle, actions: &[ImportAction]) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        let mut actions = actions.iter();
+        for entry in &bundle.connectors {
+            match actions.next() {
+                Some(ImportAction::Create) => self.insert_connector(&mut tx, entry).await,
+                Some(ImportAction::Update) => {
+                    self.update_connector_in(&mut tx, &entry.name, entry).await
+                }
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import connector '{}'", entry.name))?;
+        }
+        for entry in &bundle.destinations {
+            match actions.next() {
+                Some(ImportAction::Create) => self.insert_destination(&mut tx, entry).await,
+                Some(ImportAction::Update) => {
+                    self.update_destination_in(&mut tx, &entry.name, entry).await
+                }
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import destination '{}'", entry.name))?;
+        }
+        for entry in &bundle.flows {
+            match actions.next() {
+                Some(ImportAction::Create) => self.insert_flow(&mut tx, entry).await,
+                Some(ImportAction::Update) => self.update_flow_in(&mut tx, &entry.name, entry).await,
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import flow '{}'", entry.name))?;
+        }
+
+        tx.commit().await.context("Failed to commit import")?;
+        Ok(())
+    }
+
+    // ========== Revision History ==========
+
+    async fn record_revision(
+        tx: &mut Transaction<'_, Postgres>,
+        kind: ConfigKind,
+        name: &str,
+        operation: RevisionOperation,
+        snapshot: Option<Value>,
+    ) -> Result<()> {
+        // Concurrent saves of one entry would otherwise read the same MAX(revision);
+        // the lock lasts until commit, so the next writer sees this revision
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('cdc_config_history'), hashtext($1 || '/' || $2))")
+            .bind(kind.as_str())
+            .bind(name)
+            .execute(&mut **tx)
+            .await
+            .context("Failed to lock config history")?;
+
+        sqlx::query(
+            "INSERT INTO config_history (kind, name, revision, operation, snapshot)
+             SELECT $1, $2, COALESCE(MAX(revision), 0) + 1, $3, $4
+             FROM config_history
+             WHERE kind = $1 AND name = $2",
+        )
+        .bind(kind.as_str())
+        .bind(name)
+        .bind(operation.as_str())
+        .bind(snapshot)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to record config revision")?;
+
+        Ok(())
+    }
+
+    fn revision_from_row(&self, r: PgRow) -> Result<ConfigRevision> {
+        let name: String = r.get("name");
+        let snapshot = r
+            .get::<Option<Value>, _>("snapshot")
+            .map(|snapshot| self.decrypt_config(&name, snapshot))
+            .transpose()?;
+
+        Ok(ConfigRevision {
+            kind: r.get::<String, _>("kind").parse()?,
+            name,
+            revision: r.get("revision"),
+            operation: r.get::<String, _>("operation").parse()?,
+            snapshot,
+            created_at: r.get("created_at"),
+        })
+    }
+
+    /// All revisions of an entry, oldest first
+    pub async fn history(&self, kind: ConfigKind, name: &str) -> Result<Vec<ConfigRevision>> {
+        let rows = sqlx::query(
+            "SELECT kind, name, revision, operation, snapshot, created_at
+             FROM config_history
+             WHERE kind = $1 AND name = $2
+             ORDER BY revision",
+        )
+        .bind(kind.as_str())
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to get config history")?;
+
+        rows.into_iter()
+            .map(|r| self.revision_from_row(r))
+            .collect()
+    }
+
+    pub async fn get_revision(
+        &self,
+        kind: ConfigKind,
+        name: &str,
+        revision: i64,
+    ) -> Result<Option<ConfigRevision>> {
+        let row = sqlx::query(
+            "SELECT kind, name, revision, operation, snapshot, created_at
+             FROM config_history
+             WHERE kind = $1 AND name = $2 AND revision = $3",
+        )
+        .bind(kind.as_str())
+        .bind(name)
+        .bind(revision)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to get config revision")?;
+
+        row.map(|r| self.revision_from_row(r)).transpose()
+    }
+
+    /// Restore an entry to an earlier revision, recreating it if it was deleted
+    pub async fn rollback(&self, kind: ConfigKind, name: &str, revision: i64) -> Result<()> {
+        let snapshot = self
+            .get_revision(kind, name, revision)
+            .await?
+            .ok_or_else(|| RollbackError::NotFound {
+                kind,
+                name: name.to_string(),
+                revision,
+            })?
+            .snapshot
+            .ok_or_else(|| RollbackError::Deletion {
+                kind,
+                name: name.to_string(),
+                revision,
+            })?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        let snapshot = match kind {
+            ConfigKind::Connector => {
+                let entry: ConnectorConfigEntry =
+                    serde_json::from_value(snapshot).context("Invalid connector snapshot")?;
+                let config = self.encrypt_connector_config(&entry)?;
+                sqlx::query(
+                    "INSERT INTO connectors (name, connector_type, config, description, tags)
+                     VALUES ($1, $2, $3, $4, $5)
+                     ON CONFLICT (name) DO UPDATE
+                     SET connector_type = EXCLUDED.connector_type, config = EXCLUDED.config,
+                         description = EXCLUDED.description, tags = EXCLUDED.tags",
+                )
+                .bind(name)
+                .bind(&entry.connector_type)
+                .bind(&config)
+                .bind(&entry.description)
+                .bind(&entry.tags)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to restore connector")?;
+                snapshot_with_config(&entry, &config)?
+            }
+            ConfigKind::Destination => {
+                let entry: DestinationConfigEntry =
+                    serde_json::from_value(snapshot).context("Invalid destination snapshot")?;
+                let config = self.encrypt_destination_config(&entry)?;
+                sqlx::query(
+                    "INSERT INTO destinations (name, destination_type, config, description, tags)
+                     VALUES ($1, $2, $3, $4, $5)
+                     ON CONFLICT (name) DO UPDATE
+                     SET destination_type = EXCLUDED.destination_type, config = EXCLUDED.config,
+                         description = EXCLUDED.description, tags = EXCLUDED.tags",
+                )
+                .bind(name)
+                .bind(&entry.destination_type)
+                .bind(&config)
+                .bind(&entry.description)
+                .bind(&entry.tags)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to restore destination")?;
+                snapshot_with_config(&entry, &config)?
+            }
+            ConfigKind::Flow => {
+                let entry: FlowConfigEntry =
+                    serde_json::from_value(snapshot.clone()).context("Invalid flow snapshot")?;
+                self.validate_flow(&entry)
+                    .await
+                    .map_err(|e| RollbackError::Invalid(format!("{:#}", e)))?;
+                sqlx::query(
+                    "INSERT INTO flows (name, connector_name, destination_names, batch_size, auto_start, description)
+                     VALUES ($1, $2, $3, $4, $5, $6)
+                     ON CONFLICT (name) DO UPDATE
+                     SET connector_name = EXCLUDED.connector_name,
+                         destination_names = EXCLUDED.destination_names,
+                         batch_size = EXCLUDED.batch_size, auto_start = EXCLUDED.auto_start,
+                         description = EXCLUDED.description",
+                )
+                .bind(name)
+                .bind(&entry.connector_name)
+                .bind(&entry.destination_names)
+                .bind(entry.batch_size as i32)
+                .bind(entry.auto_start)
+                .bind(&entry.description)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to restore flow")?;
+                snapshot
+            }
+        };
+
+        Self::record_revision(
+            &mut tx,
+            kind,
+            name,
+            RevisionOperation::Rollback,
+            Some(snapshot),
+        )
+        .await?;
+        tx.commit().await.context("Failed to commit rollback")?;
+
+        Ok(())
+    }
+
     // ========== Validation ==========
 
     pub async fn validate_flow(&self, flow: &FlowConfigEntry) -> Result<()> {
@@ -405,3 +1058,10 @@ impl PgConfigStore {
         Ok(())
     }
 }
+
+/// Serialize an entry for the revision history, with its config as stored
+fn snapshot_with_config<T: Serialize>(entry: &T, config: &Value) -> Result<Value> {
+    let mut snapshot = serde_json::to_value(entry).context("Failed to serialize entry")?;
+    snapshot["config"] = config.clone();
+    Ok(snapshot)
+}
@@ -1,6 +1,11 @@
-use crate::models::{ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry};
+use crate::bundle::{ConfigBundle, ImportAction};
+use crate::models::{
+    ConfigKind, ConfigRevision, ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry,
+    RevisionOperation, RollbackError,
+};
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -10,6 +15,7 @@ pub struct ConfigStore {
     connectors: HashMap<String, ConnectorConfigEntry>,
     destinations: HashMap<String, DestinationConfigEntry>,
     flows: HashMap<String, FlowConfigEntry>,
+    history: Vec<ConfigRevision>,
     storage_dir: PathBuf,
 }
 
@@ -20,6 +26,7 @@ impl ConfigStore {
             connectors: HashMap::new(),
             destinations: HashMap::new(),
             flows: HashMap::new(),
+            history: Vec::new(),
             storage_dir: storage_dir.into(),
         }
     }
@@ -64,10 +71,21 @@ impl ConfigStore {
             HashMap::new()
         };
 
+        // Load revision history
+        let history_path = storage_dir.join("history.yaml");
+        let history = if history_path.exists() {
+            let content =
+                std::fs::read_to_string(&history_path).context("Failed to read history.yaml")?;
+            serde_yaml::from_str(&content).context("Failed to parse history.yaml")?
+        } else {
+            Vec::new()
+        };
+
         Ok(Self {
             connectors,
             destinations,
             flows,
+            history,
             storage_dir: storage_dir.to_path_buf(),
         })
     }
@@ -100,6 +118,12 @@ impl ConfigStore {
         std::fs::write(self.storage_dir.join("flows.yaml"), flows_yaml)
             .context("Failed to write flows.yaml")?;
 
+        // Save revision history
+        let history_yaml =
+            serde_yaml::to_string(&self.history).context("Failed to serialize history")?;
+        std::fs::write(self.storage_dir.join("history.yaml"), history_yaml)
+            .context("Failed to write history.yaml")?;
+
         Ok(())
     }
 
@@ -112,6 +136,12 @@ impl ConfigStore {
 
         entry.created_at = Utc::now();
         entry.updated_at = Utc::now();
+        self.record_revision(
+            ConfigKind::Connector,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(&entry),
+        )?;
         self.connectors.insert(entry.name.clone(), entry);
         Ok(())
     }
@@ -128,6 +158,12 @@ impl ConfigStore {
         entry.updated_at = Utc::now();
         entry.name = name.to_string();
 
+        self.record_revision(
+            ConfigKind::Connector,
+            name,
+            RevisionOperation::Update,
+            Some(&entry),
+        )?;
         self.connectors.insert(name.to_string(), entry);
         Ok(())
     }
@@ -165,6 +201,7 @@ impl ConfigStore {
 
         // Remove from store
         self.connectors.remove(name);
+        self.record_revision::<()>(ConfigKind::Connector, name, RevisionOperation::Delete, None)?;
         Ok(())
     }
 
@@ -185,6 +222,12 @@ impl ConfigStore {
 
         entry.created_at = Utc::now();
         entry.updated_at = Utc::now();
+        self.record_revision(
+            ConfigKind::Destination,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(&entry),
+        )?;
         self.destinations.insert(entry.name.clone(), entry);
         Ok(())
     }
@@ -205,6 +248,12 @@ impl ConfigStore {
         entry.updated_at = Utc::now();
         entry.name = name.to_string();
 
+        self.record_revision(
+            ConfigKind::Destination,
+            name,
+            RevisionOperation::Update,
+            Some(&entry),
+        )?;
         self.destinations.insert(name.to_string(), entry);
         Ok(())
     }
@@ -229,6 +278,12 @@ impl ConfigStore {
         self.destinations
             .remove(name)
             .ok_or_else(|| anyhow!("Destination '{}' not found", name))?;
+        self.record_revision::<()>(
+            ConfigKind::Destination,
+            name,
+            RevisionOperation::Delete,
+            None,
+        )?;
         Ok(())
     }
 
@@ -252,6 +307,12 @@ impl ConfigStore {
 
         entry.created_at = Utc::now();
         entry.updated_at = Utc::now();
+        self.record_revision(
+            ConfigKind::Flow,
+            &entry.name,
+            RevisionOperation::Create,
+            Some(&entry),
+        )?;
         self.flows.insert(entry.name.clone(), entry);
         Ok(())
     }
@@ -271,6 +332,12 @@ impl ConfigStore {
         entry.updated_at = Utc::now();
         entry.name = name.to_string();
 
+        self.record_revision(
+            ConfigKind::Flow,
+            name,
+            RevisionOperation::Update,
+            Some(&entry),
+        )?;
         self.flows.insert(name.to_string(), entry);
         Ok(())
     }
@@ -279,6 +346,7 @@ impl ConfigStore {
         self.flows
             .remove(name)
             .ok_or_else(|| anyhow!("Flow '{}' not found", name))?;
+        self.record_revision::<()>(ConfigKind::Flow, name, RevisionOperation::Delete, None)?;
         Ok(())
     }
 
@@ -290,6 +358,140 @@ impl ConfigStore {
         self.flows.values().collect()
     }
 
+    // ========== Bulk Import ==========
+
+    /// Apply a planned import in memory; callers save once it fully succeeds.
+    ///
+    /// `actions` holds one action per bundle entry: connectors, then destinations, then flows.
+    pub fn import(&mut self, bundle: &ConfigBundle, actions: &[ImportAction]) -> Result<()> {
+        let mut actions = actions.iter();
+        for entry in &bundle.connectors {
+            match actions.next() {
+                Some(ImportAction::Create) => self.add_connector(entry.clone()),
+                Some(ImportAction::Update) => self.update_connector(&entry.name, entry.clone()),
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import connector '{}'", entry.name))?;
+        }
+        for entry in &bundle.destinations {
+            match actions.next() {
+                Some(ImportAction::Create) => self.add_destination(entry.clone()),
+                Some(ImportAction::Update) => self.update_destination(&entry.name, entry.clone()),
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import destination '{}'", entry.name))?;
+        }
+        for entry in &bundle.flows {
+            match actions.next() {
+                Some(ImportAction::Create) => self.add_flow(entry.clone()),
+                Some(ImportAction::Update) => self.update_flow(&entry.name, entry.clone()),
+                _ => Ok(()),
+            }
+            .with_context(|| format!("Failed to import flow '{}'", entry.name))?;
+        }
+        Ok(())
+    }
+
+    // ========== Revision History ==========
+
+    fn record_revision<T: Serialize>(
+        &mut self,
+        kind: ConfigKind,
+        name: &str,
+        operation: RevisionOperation,
+        entry: Option<&T>,
+    ) -> Result<()> {
+        let snapshot = entry
+            .map(serde_json::to_value)
+            .transpose()
+            .context("Failed to serialize revision snapshot")?;
+        let revision = self
+            .history(kind, name)
+            .last()
+            .map_or(1, |latest| latest.revision + 1);
+
+        self.history.push(ConfigRevision {
+            kind,
+            name: name.to_string(),
+            revision,
+            operation,
+            snapshot,
+            created_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// All revisions of an entry, oldest first
+    pub fn history(&self, kind: ConfigKind, name: &str) -> Vec<&ConfigRevision> {
+        self.history
+            .iter()
+            .filter(|r| r.kind == kind && r.name == name)
+            .collect()
+    }
+
+    pub fn get_revision(
+        &self,
+        kind: ConfigKind,
+        name: &str,
+        revision: i64,
+    ) -> Option<&ConfigRevision> {
+        self.history
+            .iter()
+            .find(|r| r.kind == kind && r.name == name && r.revision == revision)
+    }
+
+    /// Restore an entry to an earlier revision, recreating it if it was deleted
+    pub fn rollback(&mut self, kind: ConfigKind, name: &str, revision: i64) -> Result<()> {
+        let snapshot = self
+            .get_revision(kind, name, revision)
+            .ok_or_else(|| RollbackError::NotFound {
+                kind,
+                name: name.to_string(),
+                revision,
+            })?
+            .snapshot
+            .clone()
+            .ok_or_else(|| RollbackError::Deletion {
+                kind,
+                name: name.to_string(),
+                revision,
+            })?;
+
+        let now = Utc::now();
+        match kind {
+            ConfigKind::Connector => {
+                let mut entry: ConnectorConfigEntry =
+                    serde_json::from_value(snapshot).context("Invalid connector snapshot")?;
+                entry.name = name.to_string();
+                entry.created_at = self.connectors.get(name).map_or(now, |e| e.created_at);
+                entry.updated_at = now;
+                self.record_revision(kind, name, RevisionOperation::Rollback, Some(&entry))?;
+                self.connectors.insert(name.to_string(), entry);
+            }
+            ConfigKind::Destination => {
+                let mut entry: DestinationConfigEntry =
+                    serde_json::from_value(snapshot).context("Invalid destination snapshot")?;
+                entry.name = name.to_string();
+                entry.created_at = self.destinations.get(name).map_or(now, |e| e.created_at);
+                entry.updated_at = now;
+                self.record_revision(kind, name, RevisionOperation::Rollback, Some(&entry))?;
+                self.destinations.insert(name.to_string(), entry);
+            }
+            ConfigKind::Flow => {
+                let mut entry: FlowConfigEntry =
+                    serde_json::from_value(snapshot).context("Invalid flow snapshot")?;
+                self.validate_flow(&entry)
+                    .map_err(|e| RollbackError::Invalid(format!("{:#}", e)))?;
+                entry.name = name.to_string();
+                entry.created_at = self.flows.get(name).map_or(now, |e| e.created_at);
+                entry.updated_at = now;
+                self.record_revision(kind, name, RevisionOperation::Rollback, Some(&entry))?;
+                self.flows.insert(name.to_string(), entry);
+            }
+        }
+        Ok(())
+    }
+
     // ========== Validation ==========
 
     /// Clean up external resources for a connector before deletion
@@ -298,14 +500,19 @@ impl ConfigStore {
         connector_type: &str,
         config: &serde_json::Value,
     ) -> Result<()> {
+        use cdc_core::ConnectorFactory;
         use tracing::{info, warn};
 
         match connector_type {
             "nats" => {
                 // Parse NATS config
+                let secrets = cdc_core::secret_fields(
+                    &cdc_nats_connector::NatsConnectorFactory.config_schema(),
+                );
+                let config = cdc_core::resolve_secret_refs(config, &secrets)
+                    .context("Failed to resolve NATS config secrets")?;
                 let nats_config: cdc_nats_connector::NatsConfig =
-                    serde_json::from_value(config.clone())
-                        .context("Failed to parse NATS config")?;
+                    serde_json::from_value(config).context("Failed to parse NATS config")?;
 
                 // Call cleanup directly with the config
                 info!("Cleaning up NATS connector resources");
@@ -321,8 +528,29 @@ impl ConfigStore {
                     }
                 }
             }
+            "kafka" => {
+                let secrets = cdc_core::secret_fields(
+                    &cdc_kafka_connector::KafkaConnectorFactory.config_schema(),
+                );
+                let config = cdc_core::resolve_secret_refs(config, &secrets)
+                    .context("Failed to resolve Kafka config secrets")?;
+                let kafka_config: cdc_kafka_connector::KafkaConfig =
+                    serde_json::from_value(config).context("Failed to parse Kafka config")?;
+
+                info!("Cleaning up Kafka connector resources");
+                match cdc_kafka_connector::cleanup_kafka_consumer_group(&kafka_config).await {
+                    Ok(_) => {
+                        info!("Successfully cleaned up Kafka connector resources");
+                        Ok(())
+                    }
+                    Err(e) => {
+                        warn!("Failed to cleanup Kafka connector resources: {}", e);
+                        // Don't fail deletion on cleanup errors
+                        Ok(())
+                    }
+                }
+            }
             // Add other connector types here as they implement cleanup
-            // "kafka" => { ... }
             _ => {
                 info!(
                     "Connector type '{}' does not require cleanup",
@@ -404,4 +632,108 @@ mod tests {
         store.delete_connector("test-nats").await.unwrap();
         assert_eq!(store.list_connectors().len(), 0);
     }
+
+    #[test]
+    fn test_destination_history_and_rollback() {
+        let mut store = ConfigStore::new("test_configs");
+
+        let destination = DestinationConfigEntry::new(
+            "test-pg".to_string(),
+            "postgres".to_string(),
+            json!({"schema": "v1"}),
+        );
+        store.add_destination(destination.clone()).unwrap();
+
+        let mut updated = destination.clone();
+        updated.config = json!({"schema": "v2"});
+        store.update_destination("test-pg", updated).unwrap();
+        store.delete_destination("test-pg").unwrap();
+
+        let operations: Vec<_> = store
+            .history(ConfigKind::Destination, "test-pg")
+            .iter()
+            .map(|r| (r.revision, r.operation))
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                (1, RevisionOperation::Create),
+                (2, RevisionOperation::Update),
+                (3, RevisionOperation::Delete),
+            ]
+        );
+
+        // A deletion has nothing to restore
+        let err = store
+            .rollback(ConfigKind::Destination, "test-pg", 3)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<RollbackError>(),
+            Some(RollbackError::Deletion { revision: 3, .. })
+        ));
+        let err = store
+            .rollback(ConfigKind::Destination, "test-pg", 9)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<RollbackError>(),
+            Some(RollbackError::NotFound { revision: 9, .. })
+        ));
+
+        // Rolling back recreates the deleted entry
+        store
+            .rollback(ConfigKind::Destination, "test-pg", 1)
+            .unwrap();
+        let restored = store.get_destination("test-pg").unwrap();
+        assert_eq!(restored.config, json!({"schema": "v1"}));
+        assert_eq!(
+            store
+                .get_revision(ConfigKind::Destination, "test-pg", 4)
+                .unwrap()
+                .operation,
+            RevisionOperation::Rollback
+        );
+    }
+
+    #[test]
+    fn test_import_applies_plan_in_bundle_order() {
+        let mut store = ConfigStore::new("test_configs");
+        let bundle = ConfigBundle {
+            connectors: vec![ConnectorConfigEntry::new(
+                "src".to_string(),
+                "nats".to_string(),
+                json!({}),
+            )],
+            destinations: vec![DestinationConfigEntry::new(
+                "sink".to_string(),
+                "stdout".to_string(),
+                json!({}),
+            )],
+            flows: vec![FlowConfigEntry::new(
+                "flow".to_string(),
+                "src".to_string(),
+                vec!["sink".to_string()],
+                100,
+            )],
+            ..Default::default()
+        };
+
+        // A failing entry reports which one it was; callers discard the staged store
+        let mut staged = store.clone();
+        let err = staged
+            .import(
+                &bundle,
+                &[
+                    ImportAction::Create,
+                    ImportAction::Create,
+                    ImportAction::Update,
+                ],
+            )
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to import flow 'flow'"));
+
+        store.import(&bundle, &[ImportAction::Create; 3]).unwrap();
+        assert!(store.get_connector("src").is_some());
+        assert!(store.get_destination("sink").is_some());
+        assert_eq!(store.get_flow("flow").unwrap().connector_name, "src");
+    }
 }
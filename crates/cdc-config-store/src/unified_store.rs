@@ -1,7 +1,11 @@
-use crate::models::{ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry};
-use crate::{ConfigStore, PgConfigStore};
+use crate::bundle::{ConfigBundle, ConflictPolicy, ImportAction, ImportReport};
+use crate::models::{
+    ConfigKind, ConfigRevision, ConnectorConfigEntry, DestinationConfigEntry, FlowConfigEntry,
+};
+use crate::{ConfigCipher, ConfigStore, FlowLeases, PgConfigStore};
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Unified config storage that can use either YAML files or PostgreSQL
 pub enum UnifiedConfigStore {
@@ -22,6 +26,24 @@ impl UnifiedConfigStore {
         )))
     }
 
+    /// Create from PostgreSQL, encrypting secret config fields at rest
+    pub async fn from_postgres_with_cipher(
+        database_url: &str,
+        cipher: ConfigCipher,
+    ) -> Result<Self> {
+        Ok(Self::Postgres(Arc::new(
+            PgConfigStore::new(database_url).await?.with_cipher(cipher),
+        )))
+    }
+
+    /// Flow leases for coordinating multiple instances; only PostgreSQL storage is shared
+    pub fn flow_leases(&self, instance_id: impl Into<String>, ttl: Duration) -> Option<FlowLeases> {
+        match self {
+            Self::File(_) => None,
+            Self::Postgres(store) => Some(store.flow_leases(instance_id, ttl)),
+        }
+    }
+
     // Connector operations
     pub async fn add_connector(&mut self, entry: ConnectorConfigEntry) -> Result<()> {
         match self {
@@ -57,17 +79,17 @@ impl UnifiedConfigStore {
         }
     }
 
-    pub async fn get_connector(&self, name: &str) -> Option<ConnectorConfigEntry> {
+    pub async fn get_connector(&self, name: &str) -> Result<Option<ConnectorConfigEntry>> {
         match self {
-            Self::File(store) => store.get_connector(name).cloned(),
-            Self::Postgres(store) => store.get_connector(name).await.ok().flatten(),
+            Self::File(store) => Ok(store.get_connector(name).cloned()),
+            Self::Postgres(store) => store.get_connector(name).await,
         }
     }
 
-    pub async fn list_connectors(&self) -> Vec<ConnectorConfigEntry> {
+    pub async fn list_connectors(&self) -> Result<Vec<ConnectorConfigEntry>> {
         match self {
-            Self::File(store) => store.list_connectors().into_iter().cloned().collect(),
-            Self::Postgres(store) => store.list_connectors().await.unwrap_or_default(),
+            Self::File(store) => Ok(store.list_connectors().into_iter().cloned().collect()),
+            Self::Postgres(store) => store.list_connectors().await,
         }
     }
 
@@ -106,17 +128,17 @@ impl UnifiedConfigStore {
         }
     }
 
-    pub async fn get_destination(&self, name: &str) -> Option<DestinationConfigEntry> {
+    pub async fn get_destination(&self, name: &str) -> Result<Option<DestinationConfigEntry>> {
         match self {
-            Self::File(store) => store.get_destination(name).cloned(),
-            Self::Postgres(store) => store.get_destination(name).await.ok().flatten(),
+            Self::File(store) => Ok(store.get_destination(name).cloned()),
+            Self::Postgres(store) => store.get_destination(name).await,
         }
     }
 
-    pub async fn list_destinations(&self) -> Vec<DestinationConfigEntry> {
+    pub async fn list_destinations(&self) -> Result<Vec<DestinationConfigEntry>> {
         match self {
-            Self::File(store) => store.list_destinations().into_iter().cloned().collect(),
-            Self::Postgres(store) => store.list_destinations().await.unwrap_or_default(),
+            Self::File(store) => Ok(store.list_destinations().into_iter().cloned().collect()),
+            Self::Postgres(store) => store.list_destinations().await,
         }
     }
 
@@ -151,20 +173,89 @@ impl UnifiedConfigStore {
         }
     }
 
-    pub async fn get_flow(&self, name: &str) -> Option<FlowConfigEntry> {
+    pub async fn get_flow(&self, name: &str) -> Result<Option<FlowConfigEntry>> {
         match self {
-            Self::File(store) => store.get_flow(name).cloned(),
-            Self::Postgres(store) => store.get_flow(name).await.ok().flatten(),
+            Self::File(store) => Ok(store.get_flow(name).cloned()),
+            Self::Postgres(store) => store.get_flow(name).await,
         }
     }
 
-    pub async fn list_flows(&self) -> Vec<FlowConfigEntry> {
+    pub async fn list_flows(&self) -> Result<Vec<FlowConfigEntry>> {
         match self {
-            Self::File(store) => store.list_flows().into_iter().cloned().collect(),
-            Self::Postgres(store) => store.list_flows().await.unwrap_or_default(),
+            Self::File(store) => Ok(store.list_flows().into_iter().cloned().collect()),
+            Self::Postgres(store) => store.list_flows().await,
         }
     }
 
+    // Revision history
+    /// All revisions of an entry, oldest first
+    pub async fn history(&self, kind: ConfigKind, name: &str) -> Result<Vec<ConfigRevision>> {
+        match self {
+            Self::File(store) => Ok(store.history(kind, name).into_iter().cloned().collect()),
+            Self::Postgres(store) => store.history(kind, name).await,
+        }
+    }
+
+    /// Restore an entry to an earlier revision
+    pub async fn rollback(&mut self, kind: ConfigKind, name: &str, revision: i64) -> Result<()> {
+        match self {
+            Self::File(store) => {
+                store.rollback(kind, name, revision)?;
+                store.save()
+            }
+            Self::Postgres(store) => store.rollback(kind, name, revision).await,
+        }
+    }
+
+    // Bulk export/import
+    /// Every connector, destination and flow, sorted by name
+    pub async fn export(&self) -> Result<ConfigBundle> {
+        let mut bundle = ConfigBundle {
+            connectors: self.list_connectors().await?,
+            destinations: self.list_destinations().await?,
+            flows: self.list_flows().await?,
+            ..Default::default()
+        };
+        bundle.connectors.sort_by(|a, b| a.name.cmp(&b.name));
+        bundle.destinations.sort_by(|a, b| a.name.cmp(&b.name));
+        bundle.flows.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(bundle)
+    }
+
+    /// Import a bundle, applying connectors, then destinations, then flows.
+    ///
+    /// Nothing is written on a dry run or when the plan reports errors, and an
+    /// entry that fails to apply rolls back the whole import.
+    pub async fn import(
+        &mut self,
+        bundle: ConfigBundle,
+        policy: ConflictPolicy,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let existing = self.export().await?;
+        let mut report = bundle.plan_import(&existing, policy);
+        report.dry_run = dry_run;
+        if dry_run || !report.errors.is_empty() {
+            return Ok(report);
+        }
+
+        // Items are planned in bundle order: connectors, destinations, flows
+        let actions: Vec<ImportAction> = report.items.iter().map(|item| item.action).collect();
+        match self {
+            Self::File(store) => {
+                // Stage on a copy so a failing entry leaves the loaded config untouched
+                let mut staged = store.clone();
+                staged.import(&bundle, &actions)?;
+                staged.save()?;
+                *store = staged;
+            }
+            Self::Postgres(store) => store.import(&bundle, &actions).await?,
+        }
+
+        report.applied = true;
+        Ok(report)
+    }
+
     // Validation
     pub async fn validate_flow(&self, flow: &FlowConfigEntry) -> Result<()> {
         match self {
@@ -174,20 +265,20 @@ impl UnifiedConfigStore {
     }
 
     /// Check if a connector is being used in any flow
-    pub async fn is_connector_in_use(&self, connector_name: &str) -> bool {
-        let flows = self.list_flows().await;
-        flows
+    pub async fn is_connector_in_use(&self, connector_name: &str) -> Result<bool> {
+        let flows = self.list_flows().await?;
+        Ok(flows
             .iter()
-            .any(|flow| flow.connector_name == connector_name)
+            .any(|flow| flow.connector_name == connector_name))
     }
 
     /// Check if a destination is being used in any flow
-    pub async fn is_destination_in_use(&self, destination_name: &str) -> bool {
-        let flows = self.list_flows().await;
-        flows.iter().any(|flow| {
+    pub async fn is_destination_in_use(&self, destination_name: &str) -> Result<bool> {
+        let flows = self.list_flows().await?;
+        Ok(flows.iter().any(|flow| {
             flow.destination_names
                 .iter()
                 .any(|name| name == destination_name)
-        })
+        }))
     }
 }
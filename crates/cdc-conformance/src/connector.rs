@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use cdc_core::{Connector, DataRecord, Result};
+
+use crate::{conformance_records, within};
+
+/// Plugs a connector implementation into the conformance checks
+#[async_trait]
+pub trait ConnectorHarness: Send {
+    /// A new, not yet connected connector reading from the stand-in
+    async fn create(&mut self) -> Result<Box<dyn Connector>>;
+
+    /// Make `records` available to the connector most recently created.
+    ///
+    /// Only called while that connector is connected, so subscription-based
+    /// sources see everything that is published.
+    async fn publish(&mut self, records: Vec<DataRecord>) -> Result<()>;
+
+    /// Records to publish; override when the source needs a particular shape
+    fn records(&self, count: u64) -> Vec<DataRecord> {
+        conformance_records(count)
+    }
+}
+
+/// Run every connector check in turn
+pub async fn run_connector_checks<H: ConnectorHarness>(harness: &mut H) {
+    check_connector_lifecycle(harness).await;
+    check_connector_receive_counts(harness).await;
+    check_connector_reconnect(harness).await;
+    check_connector_disconnect_idempotent(harness).await;
+}
+
+/// `connect` and `disconnect` are reflected by both `is_connected` and `status`
+pub async fn check_connector_lifecycle<H: ConnectorHarness>(harness: &mut H) {
+    let mut connector = harness.create().await.expect("Failed to create connector");
+    assert_connected(connector.as_ref(), false, "before connect()");
+
+    within("connect()", connector.connect())
+        .await
+        .expect("connect() failed");
+    assert_connected(connector.as_ref(), true, "after connect()");
+
+    within("disconnect()", connector.disconnect())
+        .await
+        .expect("disconnect() failed");
+    assert_connected(connector.as_ref(), false, "after disconnect()");
+}
+
+/// Published records come back in order and are counted in `records_received`
+pub async fn check_connector_receive_counts<H: ConnectorHarness>(harness: &mut H) {
+    let mut connector = harness.create().await.expect("Failed to create connector");
+    within("connect()", connector.connect())
+        .await
+        .expect("connect() failed");
+
+    let records = harness.records(3);
+    harness
+        .publish(records.clone())
+        .await
+        .expect("Failed to publish records");
+
+    for (i, expected) in records.iter().enumerate() {
+        let record = receive(connector.as_mut(), i).await;
+        assert_eq!(
+            record.record, expected.record,
+            "Record {} differs from the one published",
+            i
+        );
+    }
+
+    let status = connector.status();
+    assert_eq!(
+        status.records_received,
+        records.len() as u64,
+        "status().records_received does not match the records received"
+    );
+    assert_eq!(status.errors, 0, "status().errors counted without failures");
+
+    within("disconnect()", connector.disconnect())
+        .await
+        .expect("disconnect() failed");
+}
+
+/// A disconnected connector can connect again and keeps receiving
+pub async fn check_connector_reconnect<H: ConnectorHarness>(harness: &mut H) {
+    let mut connector = harness.create().await.expect("Failed to create connector");
+    within("connect()", connector.connect())
+        .await
+        .expect("connect() failed");
+    within("disconnect()", connector.disconnect())
+        .await
+        .expect("disconnect() failed");
+
+    within("connect() after disconnect()", connector.connect())
+        .await
+        .expect("connect() after disconnect() failed");
+    assert_connected(connector.as_ref(), true, "after reconnecting");
+
+    harness
+        .publish(harness.records(1))
+        .await
+        .expect("Failed to publish records");
+    receive(connector.as_mut(), 0).await;
+    assert_eq!(
+        connector.status().records_received,
+        1,
+        "status().records_received is wrong after reconnecting"
+    );
+
+    within("disconnect()", connector.disconnect())
+        .await
+        .expect("disconnect() failed");
+}
+
+/// `disconnect` succeeds before `connect` and when called twice
+pub async fn check_connector_disconnect_idempotent<H: ConnectorHarness>(harness: &mut H) {
+    let mut connector = harness.create().await.expect("Failed to create connector");
+    within("disconnect() before connect()", connector.disconnect())
+        .await
+        .expect("disconnect() before connect() failed");
+    assert_connected(
+        connector.as_ref(),
+        false,
+        "after disconnect() before connect()",
+    );
+
+    within("connect()", connector.connect())
+        .await
+        .expect("connect() failed");
+    within("disconnect()", connector.disconnect())
+        .await
+        .expect("disconnect() failed");
+    within("second disconnect()", connector.disconnect())
+        .await
+        .expect("second disconnect() failed");
+    assert_connected(connector.as_ref(), false, "after a second disconnect()");
+}
+
+async fn receive(connector: &mut dyn Connector, index: usize) -> DataRecord {
+    within(
+        &format!("receive() of record {}", index),
+        connector.receive(),
+    )
+    .await
+    .unwrap_or_else(|e| panic!("receive() of record {} failed: {}", index, e))
+    .unwrap_or_else(|| panic!("Stream ended before record {} was received", index))
+}
+
+fn assert_connected(connector: &dyn Connector, expected: bool, when: &str) {
+    assert_eq!(
+        connector.is_connected(),
+        expected,
+        "is_connected() is wrong {}",
+        when
+    );
+    assert_eq!(
+        connector.status().connected,
+        expected,
+        "status().connected is wrong {}",
+        when
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::MemoryConnector;
+    use tokio::sync::mpsc::UnboundedSender;
+
+    #[derive(Default)]
+    struct MemoryHarness {
+        sender: Option<UnboundedSender<DataRecord>>,
+    }
+
+    #[async_trait]
+    impl ConnectorHarness for MemoryHarness {
+        async fn create(&mut self) -> Result<Box<dyn Connector>> {
+            let (sender, connector) = MemoryConnector::channel();
+            self.sender = Some(sender);
+            Ok(Box::new(connector))
+        }
+
+        async fn publish(&mut self, records: Vec<DataRecord>) -> Result<()> {
+            let sender = self.sender.as_ref().expect("publish() before create()");
+            for record in records {
+                sender.send(record).ok();
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_connector_conforms() {
+        run_connector_checks(&mut MemoryHarness::default()).await;
+    }
+}
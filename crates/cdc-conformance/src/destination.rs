@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, Result};
+
+use crate::{conformance_records, within};
+
+/// Plugs a destination implementation into the conformance checks
+#[async_trait]
+pub trait DestinationHarness: Send {
+    /// A new, not yet connected destination writing to the stand-in
+    async fn create(&mut self) -> Result<Box<dyn Destination>>;
+
+    /// Records to write; override when the target needs a particular shape
+    fn records(&self, count: u64) -> Vec<DataRecord> {
+        conformance_records(count)
+    }
+}
+
+/// Run every destination check in turn
+pub async fn run_destination_checks<H: DestinationHarness>(harness: &mut H) {
+    check_destination_lifecycle(harness).await;
+    check_destination_counters(harness).await;
+    check_destination_empty_batch(harness).await;
+    check_destination_reconnect(harness).await;
+    check_destination_disconnect_idempotent(harness).await;
+}
+
+/// Writes fail outside `connect`..`disconnect`, and both calls are reflected by
+/// `is_connected` and `status`
+pub async fn check_destination_lifecycle<H: DestinationHarness>(harness: &mut H) {
+    let mut destination = harness
+        .create()
+        .await
+        .expect("Failed to create destination");
+    assert_connected(destination.as_ref(), false, "before connect()");
+    let result = within(
+        "write_batch() before connect()",
+        destination.write_batch(harness.records(1)),
+    )
+    .await;
+    assert!(result.is_err(), "write_batch() before connect() succeeded");
+
+    within("connect()", destination.connect())
+        .await
+        .expect("connect() failed");
+    assert_connected(destination.as_ref(), true, "after connect()");
+
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+    assert_connected(destination.as_ref(), false, "after disconnect()");
+    let result = within(
+        "write_batch() after disconnect()",
+        destination.write_batch(harness.records(1)),
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "write_batch() after disconnect() succeeded"
+    );
+}
+
+/// Successful `write` and `write_batch` calls are counted in `records_written`
+pub async fn check_destination_counters<H: DestinationHarness>(harness: &mut H) {
+    let mut destination = harness
+        .create()
+        .await
+        .expect("Failed to create destination");
+    within("connect()", destination.connect())
+        .await
+        .expect("connect() failed");
+
+    let mut records = harness.records(4);
+    let first = records.remove(0);
+    within("write()", destination.write(first))
+        .await
+        .expect("write() failed");
+    assert_eq!(
+        destination.status().records_written,
+        1,
+        "status().records_written is wrong after write()"
+    );
+
+    within("write_batch()", destination.write_batch(records))
+        .await
+        .expect("write_batch() failed");
+    let status = destination.status();
+    assert_eq!(
+        status.records_written, 4,
+        "status().records_written is wrong after write_batch()"
+    );
+    assert_eq!(status.errors, 0, "status().errors counted without failures");
+    assert_eq!(
+        status.consecutive_errors, 0,
+        "status().consecutive_errors counted without failures"
+    );
+
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+}
+
+/// An empty batch succeeds and leaves the counters alone
+pub async fn check_destination_empty_batch<H: DestinationHarness>(harness: &mut H) {
+    let mut destination = harness
+        .create()
+        .await
+        .expect("Failed to create destination");
+    within("connect()", destination.connect())
+        .await
+        .expect("connect() failed");
+
+    let before = destination.status();
+    within(
+        "write_batch() of an empty batch",
+        destination.write_batch(Vec::new()),
+    )
+    .await
+    .expect("write_batch() of an empty batch failed");
+    let after = destination.status();
+    assert_eq!(
+        after.records_written, before.records_written,
+        "An empty batch changed status().records_written"
+    );
+    assert_eq!(
+        after.errors, before.errors,
+        "An empty batch changed status().errors"
+    );
+
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+}
+
+/// A disconnected destination can connect again and keeps writing and counting
+pub async fn check_destination_reconnect<H: DestinationHarness>(harness: &mut H) {
+    let mut destination = harness
+        .create()
+        .await
+        .expect("Failed to create destination");
+    within("connect()", destination.connect())
+        .await
+        .expect("connect() failed");
+    within("write_batch()", destination.write_batch(harness.records(1)))
+        .await
+        .expect("write_batch() failed");
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+
+    within("connect() after disconnect()", destination.connect())
+        .await
+        .expect("connect() after disconnect() failed");
+    assert_connected(destination.as_ref(), true, "after reconnecting");
+    within(
+        "write_batch() after reconnecting",
+        destination.write_batch(harness.records(1)),
+    )
+    .await
+    .expect("write_batch() after reconnecting failed");
+    assert_eq!(
+        destination.status().records_written,
+        2,
+        "status().records_written is wrong after reconnecting"
+    );
+
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+}
+
+/// `disconnect` succeeds before `connect` and when called twice
+pub async fn check_destination_disconnect_idempotent<H: DestinationHarness>(harness: &mut H) {
+    let mut destination = harness
+        .create()
+        .await
+        .expect("Failed to create destination");
+    within("disconnect() before connect()", destination.disconnect())
+        .await
+        .expect("disconnect() before connect() failed");
+    assert_connected(
+        destination.as_ref(),
+        false,
+        "after disconnect() before connect()",
+    );
+
+    within("connect()", destination.connect())
+        .await
+        .expect("connect() failed");
+    within("disconnect()", destination.disconnect())
+        .await
+        .expect("disconnect() failed");
+    within("second disconnect()", destination.disconnect())
+        .await
+        .expect("second disconnect() failed");
+    assert_connected(destination.as_ref(), false, "after a second disconnect()");
+}
+
+fn assert_connected(destination: &dyn Destination, expected: bool, when: &str) {
+    assert_eq!(
+        destination.is_connected(),
+        expected,
+        "is_connected() is wrong {}",
+        when
+    );
+    assert_eq!(
+        destination.status().connected,
+        expected,
+        "status().connected is wrong {}",
+        when
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::MemoryDestination;
+
+    struct MemoryHarness;
+
+    #[async_trait]
+    impl DestinationHarness for MemoryHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            Ok(Box::new(MemoryDestination::new()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_destination_conforms() {
+        run_destination_checks(&mut MemoryHarness).await;
+    }
+}
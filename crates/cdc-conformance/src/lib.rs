@@ -0,0 +1,62 @@
+//! Conformance checks for [`Connector`](cdc_core::Connector) and
+//! [`Destination`](cdc_core::Destination) implementations.
+//!
+//! Each check is a plain async function that panics with a descriptive message
+//! when the implementation misbehaves, so it can be called from any
+//! `#[tokio::test]`. Implementations plug in through a small harness that
+//! creates fresh instances pointing at a local stand-in (a test server, a
+//! container, or an in-memory double):
+//!
+//! ```ignore
+//! struct PostgresHarness { url: String }
+//!
+//! #[async_trait]
+//! impl DestinationHarness for PostgresHarness {
+//!     async fn create(&mut self) -> Result<Box<dyn Destination>> {
+//!         PostgresDestinationFactory.create(json!({ "url": self.url }))
+//!     }
+//! }
+//!
+//! #[tokio::test]
+//! #[ignore = "needs a local PostgreSQL"]
+//! async fn postgres_conforms() {
+//!     cdc_conformance::run_destination_checks(&mut PostgresHarness { url }).await;
+//! }
+//! ```
+
+mod connector;
+mod destination;
+
+pub use connector::{
+    check_connector_disconnect_idempotent, check_connector_lifecycle,
+    check_connector_receive_counts, check_connector_reconnect, run_connector_checks,
+    ConnectorHarness,
+};
+pub use destination::{
+    check_destination_counters, check_destination_disconnect_idempotent,
+    check_destination_empty_batch, check_destination_lifecycle, check_destination_reconnect,
+    run_destination_checks, DestinationHarness,
+};
+
+use cdc_core::{testing::sample_record, DataRecord};
+use std::time::Duration;
+
+/// Table name used by the default test records
+pub const CONFORMANCE_TABLE: &str = "cdc_conformance";
+
+/// How long a single connector or destination call may take before a check fails
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `count` insert records for [`CONFORMANCE_TABLE`] with ids `0..count`
+pub fn conformance_records(count: u64) -> Vec<DataRecord> {
+    (0..count)
+        .map(|id| sample_record(CONFORMANCE_TABLE, id))
+        .collect()
+}
+
+/// Await `future`, failing the check if it takes longer than [`CALL_TIMEOUT`]
+async fn within<T>(what: &str, future: impl std::future::Future<Output = T>) -> T {
+    tokio::time::timeout(CALL_TIMEOUT, future)
+        .await
+        .unwrap_or_else(|_| panic!("{} did not complete within {:?}", what, CALL_TIMEOUT))
+}
@@ -0,0 +1,84 @@
+use crate::{FileConfig, FileConnector};
+use cdc_core::{Connector, ConnectorFactory, Result};
+use serde_json::Value;
+
+pub struct FileConnectorFactory;
+
+impl ConnectorFactory for FileConnectorFactory {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Connector>> {
+        let config: FileConfig = serde_json::from_value(config)?;
+        Ok(Box::new(FileConnector::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(FileConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::DataRecord;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    struct FileHarness {
+        dir: TempDir,
+        runs: usize,
+        current: Option<PathBuf>,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::ConnectorHarness for FileHarness {
+        async fn create(&mut self) -> Result<Box<dyn Connector>> {
+            // A directory per connector so each one starts without earlier records
+            self.runs += 1;
+            let run = self.dir.path().join(format!("run-{}", self.runs));
+            std::fs::create_dir(&run)?;
+            let pattern = run.join("*.jsonl");
+            self.current = Some(run.join("events.jsonl"));
+            FileConnectorFactory.create(serde_json::json!({
+                "path": pattern.to_str().unwrap(),
+                "follow": true,
+                "poll_interval_ms": 10,
+            }))
+        }
+
+        async fn publish(&mut self, records: Vec<DataRecord>) -> Result<()> {
+            let path = self.current.as_ref().expect("publish() before create()");
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            for record in records {
+                writeln!(file, "{}", serde_json::to_string(&record)?)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = FileConnectorFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["path"]));
+        assert_eq!(properties["follow"]["default"], false);
+        assert_eq!(properties["poll_interval_ms"]["default"], 500);
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = FileHarness {
+            dir: TempDir::new().unwrap(),
+            runs: 0,
+            current: None,
+        };
+        cdc_conformance::run_connector_checks(&mut harness).await;
+    }
+}
@@ -0,0 +1,490 @@
+use async_trait::async_trait;
+use cdc_core::{Connector, ConnectorStatus, DataRecord, Error, Result};
+use flate2::read::MultiGzDecoder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileConfig {
+    /// File to read, or a glob pattern matching several files (e.g., /data/events/*.jsonl.gz).
+    /// Files are read in lexical order; files ending in `.gz` are decompressed
+    pub path: String,
+
+    /// Keep watching for appended lines and new matching files instead of
+    /// stopping at the end of the existing ones
+    #[serde(default)]
+    pub follow: bool,
+
+    /// How often to look for new data once caught up, in milliseconds
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_ms: u64,
+
+    /// File where read positions are saved once their records are written, so a
+    /// restart resumes where it left off. Without it positions are only kept in memory
+    #[serde(default)]
+    pub checkpoint_path: Option<String>,
+}
+
+fn default_poll_interval() -> u64 {
+    500
+}
+
+/// How far one file has been read
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct FilePosition {
+    /// Bytes consumed; counted after decompression for `.gz` files
+    offset: u64,
+
+    /// A compressed file was read to the end (compressed files are never followed)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    finished: bool,
+}
+
+struct OpenFile {
+    path: PathBuf,
+    reader: Box<dyn BufRead + Send + Sync>,
+    compressed: bool,
+}
+
+/// Reads `DataRecord` JSONL from a file or a glob of files
+pub struct FileConnector {
+    config: FileConfig,
+    positions: BTreeMap<PathBuf, FilePosition>,
+    current: Option<OpenFile>,
+    status: ConnectorStatus,
+    /// Positions changed since the last checkpoint write
+    dirty: bool,
+    /// Every file was read to the end (only without `follow`)
+    finished: bool,
+}
+
+impl FileConnector {
+    pub fn new(config: FileConfig) -> Self {
+        Self {
+            config,
+            positions: BTreeMap::new(),
+            current: None,
+            status: ConnectorStatus::default(),
+            dirty: false,
+            finished: false,
+        }
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.config.poll_interval_ms)
+    }
+
+    /// Existing files matching the configured path, in lexical order
+    fn matching_files(&self) -> Result<Vec<PathBuf>> {
+        let entries = glob::glob(&self.config.path).map_err(|e| {
+            Error::Configuration(format!("Invalid file pattern '{}': {}", self.config.path, e))
+        })?;
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| match entry {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!("Skipping unreadable path: {}", e);
+                    None
+                }
+            })
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Whether `path` has bytes past its saved position
+    fn has_unread(&mut self, path: &Path) -> Result<bool> {
+        let position = self.positions.get(path).copied().unwrap_or_default();
+        if is_compressed(path) {
+            return Ok(!position.finished);
+        }
+
+        let len = match fs::metadata(path) {
+            Ok(metadata) => metadata.len(),
+            // Removed since it was listed
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+
+        if len < position.offset {
+            warn!(
+                "{} is shorter than its saved position ({} < {}), reading it again from the start",
+                path.display(),
+                len,
+                position.offset
+            );
+            self.positions.insert(path.to_path_buf(), FilePosition::default());
+            self.dirty = true;
+            return Ok(len > 0);
+        }
+
+        Ok(len > position.offset)
+    }
+
+    /// Open `path` at its saved position
+    fn open(&self, path: &Path) -> Result<OpenFile> {
+        let offset = self.positions.get(path).map_or(0, |p| p.offset);
+        let compressed = is_compressed(path);
+        let mut file = File::open(path)?;
+
+        let reader: Box<dyn BufRead + Send + Sync> = if compressed {
+            let mut reader = BufReader::new(MultiGzDecoder::new(file));
+            // Decompressed offsets can't be seeked to, so read past them
+            io::copy(&mut (&mut reader).take(offset), &mut io::sink())?;
+            Box::new(reader)
+        } else {
+            file.seek(SeekFrom::Start(offset))?;
+            Box::new(BufReader::new(file))
+        };
+
+        debug!("Reading {} from offset {}", path.display(), offset);
+        Ok(OpenFile {
+            path: path.to_path_buf(),
+            reader,
+            compressed,
+        })
+    }
+
+    /// Next record from the open file, closing it once no complete line is left
+    fn read_current(&mut self) -> Result<Option<DataRecord>> {
+        loop {
+            let Some(file) = self.current.as_mut() else {
+                return Ok(None);
+            };
+
+            let mut line = Vec::new();
+            let read = file.reader.read_until(b'\n', &mut line)?;
+            let position = self.positions.entry(file.path.clone()).or_default();
+
+            if read == 0 {
+                if file.compressed {
+                    position.finished = true;
+                    self.dirty = true;
+                }
+                self.current = None;
+                return Ok(None);
+            }
+
+            // A plain file being followed may be mid-append; reread the line on a later pass
+            if !line.ends_with(b"\n") && self.config.follow && !file.compressed {
+                self.current = None;
+                return Ok(None);
+            }
+
+            let line_offset = position.offset;
+            position.offset += read as u64;
+            self.dirty = true;
+
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+
+            return match serde_json::from_slice::<DataRecord>(line) {
+                Ok(record) => Ok(Some(record)),
+                Err(e) => {
+                    error!(
+                        "Invalid record in {} at byte {}: {}",
+                        file.path.display(),
+                        line_offset,
+                        e
+                    );
+                    Err(e.into())
+                }
+            };
+        }
+    }
+
+    /// Next record from any matching file, or `None` when all are caught up
+    fn next_record(&mut self) -> Result<Option<DataRecord>> {
+        if let Some(record) = self.read_current()? {
+            return Ok(Some(record));
+        }
+
+        for path in self.matching_files()? {
+            if !self.has_unread(&path)? {
+                continue;
+            }
+            self.current = Some(self.open(&path)?);
+            if let Some(record) = self.read_current()? {
+                return Ok(Some(record));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn load_checkpoint(&mut self) -> Result<()> {
+        let Some(path) = self.config.checkpoint_path.as_deref() else {
+            return Ok(());
+        };
+
+        match fs::read(path) {
+            Ok(bytes) => {
+                self.positions = serde_json::from_slice(&bytes)?;
+                info!(
+                    "Resuming {} file(s) from checkpoint {}",
+                    self.positions.len(),
+                    path
+                );
+                Ok(())
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write positions to the checkpoint file if they moved since the last write
+    fn save_checkpoint(&mut self) -> Result<()> {
+        let Some(path) = self.config.checkpoint_path.as_deref() else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+
+        // Write then rename so a crash never leaves a partial checkpoint
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&self.positions)?)?;
+        fs::rename(&tmp_path, path)?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    async fn next_or_wait(&mut self) -> Result<Option<DataRecord>> {
+        loop {
+            if !self.finished {
+                if let Some(record) = self.next_record()? {
+                    return Ok(Some(record));
+                }
+
+                if !self.config.follow {
+                    info!("Finished reading files matching {}", self.config.path);
+                    self.finished = true;
+                }
+            }
+
+            tokio::time::sleep(self.poll_interval()).await;
+            if self.finished {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+#[async_trait]
+impl Connector for FileConnector {
+    async fn connect(&mut self) -> Result<()> {
+        glob::Pattern::new(&self.config.path).map_err(|e| {
+            Error::Configuration(format!("Invalid file pattern '{}': {}", self.config.path, e))
+        })?;
+        self.load_checkpoint()?;
+
+        let files = self.matching_files()?.len();
+        if files == 0 && !self.config.follow {
+            warn!("No files match {}", self.config.path);
+        }
+        info!(
+            "Reading {} file(s) matching {}{}",
+            files,
+            self.config.path,
+            if self.config.follow { " (following)" } else { "" }
+        );
+
+        self.current = None;
+        self.finished = false;
+        self.status.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Closing files matching {}", self.config.path);
+        self.current = None;
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn receive(&mut self) -> Result<Option<DataRecord>> {
+        if !self.status.connected {
+            return Err(Error::Connection("File connector not connected".to_string()));
+        }
+
+        match self.next_or_wait().await {
+            Ok(Some(record)) => {
+                self.status.records_received += 1;
+                Ok(Some(record))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> ConnectorStatus {
+        self.status.clone()
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        // Everything read so far is written, so the read positions are safe to resume from
+        self.save_checkpoint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn config(path: impl AsRef<Path>) -> FileConfig {
+        serde_json::from_value(serde_json::json!({
+            "path": path.as_ref().to_str().unwrap(),
+            "poll_interval_ms": 10,
+        }))
+        .unwrap()
+    }
+
+    fn jsonl(ids: std::ops::Range<u64>) -> String {
+        ids.map(|id| serde_json::to_string(&sample_record("users", id)).unwrap() + "\n")
+            .collect()
+    }
+
+    fn append(path: &Path, data: &str) {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(data.as_bytes())
+            .unwrap();
+    }
+
+    async fn receive_ids(connector: &mut FileConnector, count: usize) -> Vec<u64> {
+        let mut ids = Vec::new();
+        for _ in 0..count {
+            let record = connector.receive().await.unwrap().expect("stream ended");
+            ids.push(record.parse_record().unwrap()["id"].as_u64().unwrap());
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_reads_glob_in_order_and_decompresses_gzip() {
+        let dir = TempDir::new().unwrap();
+        append(&dir.path().join("b.jsonl"), &jsonl(2..4));
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(jsonl(0..2).as_bytes()).unwrap();
+        fs::write(dir.path().join("a.jsonl.gz"), encoder.finish().unwrap()).unwrap();
+
+        let mut connector = FileConnector::new(config(dir.path().join("*.jsonl*")));
+        connector.connect().await.unwrap();
+
+        assert_eq!(receive_ids(&mut connector, 4).await, vec![0, 1, 2, 3]);
+        assert!(connector.receive().await.unwrap().is_none());
+        assert_eq!(connector.status().records_received, 4);
+    }
+
+    #[tokio::test]
+    async fn test_follow_waits_for_complete_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.jsonl");
+        append(&path, &jsonl(0..1));
+
+        let mut config = config(&path);
+        config.follow = true;
+        let mut connector = FileConnector::new(config);
+        connector.connect().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 1).await, vec![0]);
+
+        let line = jsonl(1..2);
+        let (head, tail) = line.split_at(10);
+        append(&path, head);
+        let pending = tokio::time::timeout(Duration::from_millis(100), connector.receive()).await;
+        assert!(pending.is_err(), "a partial line was returned");
+
+        append(&path, tail);
+        assert_eq!(receive_ids(&mut connector, 1).await, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_resumes_after_restart() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.jsonl");
+        append(&path, &jsonl(0..3));
+
+        let mut config = config(&path);
+        config.checkpoint_path = Some(dir.path().join("checkpoint.json").display().to_string());
+
+        let mut connector = FileConnector::new(config.clone());
+        connector.connect().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 2).await, vec![0, 1]);
+        connector.commit().await.unwrap();
+        connector.disconnect().await.unwrap();
+
+        append(&path, &jsonl(3..4));
+        let mut connector = FileConnector::new(config);
+        connector.connect().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 2).await, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_position_is_not_persisted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.jsonl");
+        append(&path, &jsonl(0..3));
+
+        let mut config = config(&path);
+        config.checkpoint_path = Some(dir.path().join("checkpoint.json").display().to_string());
+
+        let mut connector = FileConnector::new(config.clone());
+        connector.connect().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 1).await, vec![0]);
+        connector.commit().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 2).await, vec![1, 2]);
+        assert!(connector.receive().await.unwrap().is_none());
+        connector.disconnect().await.unwrap();
+
+        // Records read after the last commit may not have been written, so they come again
+        let mut connector = FileConnector::new(config);
+        connector.connect().await.unwrap();
+        assert_eq!(receive_ids(&mut connector, 2).await, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_line_is_counted_and_skipped() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("events.jsonl");
+        append(&path, "not json\n");
+        append(&path, &jsonl(0..1));
+
+        let mut connector = FileConnector::new(config(&path));
+        connector.connect().await.unwrap();
+
+        assert!(connector.receive().await.is_err());
+        assert_eq!(receive_ids(&mut connector, 1).await, vec![0]);
+        assert_eq!(connector.status().errors, 1);
+    }
+}
@@ -0,0 +1,5 @@
+mod factory;
+mod file_connector;
+
+pub use factory::FileConnectorFactory;
+pub use file_connector::{FileConfig, FileConnector};
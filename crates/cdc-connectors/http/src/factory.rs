@@ -0,0 +1,84 @@
+use crate::{HttpConnector, HttpConnectorConfig};
+use cdc_core::{Connector, ConnectorFactory, IngestHub, Result};
+use serde_json::Value;
+
+/// Creates connectors fed by `POST /api/ingest/{flow}` through `hub`, which must be
+/// the hub the API server submits to
+pub struct HttpConnectorFactory {
+    hub: IngestHub,
+}
+
+impl HttpConnectorFactory {
+    pub fn new(hub: IngestHub) -> Self {
+        Self { hub }
+    }
+}
+
+impl ConnectorFactory for HttpConnectorFactory {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Connector>> {
+        let config: HttpConnectorConfig = serde_json::from_value(config)?;
+        Ok(Box::new(HttpConnector::new(config, self.hub.clone())))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(HttpConnectorConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::{DataRecord, SECRET_SCHEMA_KEYWORD};
+
+    struct HttpHarness {
+        hub: IngestHub,
+        runs: usize,
+    }
+
+    impl HttpHarness {
+        fn flow(&self) -> String {
+            format!("run-{}", self.runs)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::ConnectorHarness for HttpHarness {
+        async fn create(&mut self) -> Result<Box<dyn Connector>> {
+            self.runs += 1;
+            let mut connector = HttpConnectorFactory::new(self.hub.clone())
+                .create(serde_json::json!({ "auth_token": "secret" }))?;
+            connector.bind_flow(&self.flow());
+            Ok(connector)
+        }
+
+        async fn publish(&mut self, records: Vec<DataRecord>) -> Result<()> {
+            self.hub
+                .submit(&self.flow(), Some("secret"), records)
+                .map_err(|e| cdc_core::Error::Connection(e.to_string()))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = HttpConnectorFactory::new(IngestHub::new()).config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["queue_capacity"]["default"], 10000);
+        assert_eq!(properties["acknowledge"]["default"], "written");
+        assert_eq!(properties["auth_token"][SECRET_SCHEMA_KEYWORD], true);
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = HttpHarness {
+            hub: IngestHub::new(),
+            runs: 0,
+        };
+        cdc_conformance::run_connector_checks(&mut harness).await;
+    }
+}
@@ -0,0 +1,299 @@
+use async_trait::async_trait;
+use cdc_core::{Connector, ConnectorStatus, DataRecord, Error, IngestHub, IngestQueue, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpConnectorConfig {
+    /// Records that can be queued before `POST /api/ingest/{flow}` answers 429
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+
+    /// Token producers must send as `Authorization: Bearer <token>`; required unless
+    /// `allow_anonymous` is set
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub auth_token: Option<String>,
+
+    /// Accept records from anyone who can reach the API when no `auth_token` is set
+    #[serde(default)]
+    pub allow_anonymous: bool,
+
+    /// When `POST /api/ingest/{flow}` answers: once the flow has written the records
+    /// (`written`, 200) or as soon as they are queued (`queued`, 202)
+    #[serde(default)]
+    pub acknowledge: IngestAcknowledge,
+
+    /// With `acknowledge: written`, how long a request waits for its records to be
+    /// written before it is answered 202 as queued but not yet written, in milliseconds
+    #[serde(default = "default_write_timeout")]
+    pub write_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestAcknowledge {
+    /// Answer once the records are written to every destination; records dropped
+    /// unwritten because the flow stopped are answered with 503
+    #[default]
+    Written,
+    /// Answer once the records are queued; queued records are lost if the flow stops
+    /// before writing them
+    Queued,
+}
+
+fn default_queue_capacity() -> usize {
+    10000
+}
+
+fn default_write_timeout() -> u64 {
+    30000
+}
+
+impl Default for HttpConnectorConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: default_queue_capacity(),
+            auth_token: None,
+            allow_anonymous: false,
+            acknowledge: IngestAcknowledge::default(),
+            write_timeout_ms: default_write_timeout(),
+        }
+    }
+}
+
+/// Receives records pushed to the API for its flow
+pub struct HttpConnector {
+    config: HttpConnectorConfig,
+    hub: IngestHub,
+    flow: Option<String>,
+    queue: Option<IngestQueue>,
+    status: ConnectorStatus,
+}
+
+impl HttpConnector {
+    pub fn new(config: HttpConnectorConfig, hub: IngestHub) -> Self {
+        Self {
+            config,
+            hub,
+            flow: None,
+            queue: None,
+            status: ConnectorStatus::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for HttpConnector {
+    fn bind_flow(&mut self, flow: &str) {
+        self.flow = Some(flow.to_string());
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        if self.config.queue_capacity == 0 {
+            return Err(Error::Configuration(
+                "queue_capacity must be at least 1".to_string(),
+            ));
+        }
+        // Records are addressed by flow name, so there is nothing to accept outside a flow
+        let flow = self.flow.as_deref().ok_or_else(|| {
+            Error::Configuration(
+                "HTTP connector only accepts records as part of a running flow".to_string(),
+            )
+        })?;
+
+        if self.config.auth_token.is_none() {
+            if !self.config.allow_anonymous {
+                return Err(Error::Configuration(
+                    "auth_token is required; set allow_anonymous to accept records without one"
+                        .to_string(),
+                ));
+            }
+            warn!(
+                "/api/ingest/{} has no auth_token: anyone who can reach the API can inject records",
+                flow
+            );
+        }
+
+        self.queue = Some(self.hub.open(
+            flow,
+            self.config.queue_capacity,
+            self.config.auth_token.clone(),
+            (self.config.acknowledge == IngestAcknowledge::Written)
+                .then(|| Duration::from_millis(self.config.write_timeout_ms)),
+        ));
+        info!("Accepting records on /api/ingest/{}", flow);
+
+        self.status.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        // Requests whose records were not written are answered with an error
+        if self.queue.take().is_some() {
+            info!(
+                "Stopped accepting records on /api/ingest/{}",
+                self.flow.as_deref().unwrap_or_default()
+            );
+        }
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn receive(&mut self) -> Result<Option<DataRecord>> {
+        let queue = self
+            .queue
+            .as_mut()
+            .ok_or_else(|| Error::Connection("HTTP connector not connected".to_string()))?;
+
+        let record = queue.recv().await;
+        if record.is_some() {
+            self.status.records_received += 1;
+        }
+        Ok(record)
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        if let Some(queue) = &mut self.queue {
+            queue.acknowledge();
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> ConnectorStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use cdc_core::IngestError;
+
+    #[tokio::test]
+    async fn test_requires_flow() {
+        let mut connector = HttpConnector::new(HttpConnectorConfig::default(), IngestHub::new());
+        assert!(connector.connect().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requires_token_unless_anonymous_is_allowed() {
+        let hub = IngestHub::new();
+        let mut connector = HttpConnector::new(HttpConnectorConfig::default(), hub.clone());
+        connector.bind_flow("orders");
+        assert!(matches!(
+            connector.connect().await,
+            Err(Error::Configuration(_))
+        ));
+
+        let mut connector = HttpConnector::new(
+            HttpConnectorConfig {
+                allow_anonymous: true,
+                ..Default::default()
+            },
+            hub.clone(),
+        );
+        connector.bind_flow("orders");
+        connector.connect().await.unwrap();
+        assert!(hub
+            .submit("orders", None, vec![sample_record("users", 1)])
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_commit_acknowledges_written_records() {
+        let hub = IngestHub::new();
+        let mut connector = HttpConnector::new(
+            HttpConnectorConfig {
+                auth_token: Some("secret".to_string()),
+                ..Default::default()
+            },
+            hub.clone(),
+        );
+        connector.bind_flow("orders");
+        connector.connect().await.unwrap();
+
+        let written = hub
+            .submit("orders", Some("secret"), vec![sample_record("users", 1)])
+            .unwrap()
+            .written
+            .unwrap();
+        let pending = hub
+            .submit("orders", Some("secret"), vec![sample_record("users", 2)])
+            .unwrap()
+            .written
+            .unwrap();
+
+        connector.receive().await.unwrap().unwrap();
+        connector.commit().await.unwrap();
+        assert_eq!(written.await, Ok(()));
+
+        // Stopping before the second record is written fails its request
+        connector.disconnect().await.unwrap();
+        assert!(pending.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests_wait_up_to_write_timeout() {
+        let hub = IngestHub::new();
+        let mut connector = HttpConnector::new(
+            HttpConnectorConfig {
+                auth_token: Some("secret".to_string()),
+                write_timeout_ms: 250,
+                ..Default::default()
+            },
+            hub.clone(),
+        );
+        connector.bind_flow("orders");
+        connector.connect().await.unwrap();
+
+        let submission = hub
+            .submit("orders", Some("secret"), vec![sample_record("users", 1)])
+            .unwrap();
+        assert!(submission.written.is_some());
+        assert_eq!(submission.write_timeout, Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_stops_accepting_records() {
+        let hub = IngestHub::new();
+        let mut connector = HttpConnector::new(
+            HttpConnectorConfig {
+                queue_capacity: 2,
+                auth_token: Some("secret".to_string()),
+                acknowledge: IngestAcknowledge::Queued,
+                ..Default::default()
+            },
+            hub.clone(),
+        );
+        connector.bind_flow("orders");
+        connector.connect().await.unwrap();
+
+        let records = vec![sample_record("users", 1), sample_record("users", 2)];
+        let submission = hub.submit("orders", Some("secret"), records).unwrap();
+        assert_eq!(submission.accepted, 2);
+        assert!(submission.written.is_none());
+        assert_eq!(
+            hub.submit("orders", Some("secret"), vec![sample_record("users", 3)])
+                .unwrap_err(),
+            IngestError::Full
+        );
+        connector.receive().await.unwrap().unwrap();
+        assert_eq!(connector.status().records_received, 1);
+
+        connector.disconnect().await.unwrap();
+        assert_eq!(
+            hub.submit("orders", Some("secret"), vec![sample_record("users", 4)])
+                .unwrap_err(),
+            IngestError::NotFound
+        );
+        assert!(connector.receive().await.is_err());
+    }
+}
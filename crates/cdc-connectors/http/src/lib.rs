@@ -0,0 +1,5 @@
+mod factory;
+mod http_connector;
+
+pub use factory::HttpConnectorFactory;
+pub use http_connector::{HttpConnector, HttpConnectorConfig, IngestAcknowledge};
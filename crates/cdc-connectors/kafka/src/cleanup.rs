@@ -0,0 +1,50 @@
+use cdc_core::{Error, Result};
+use rdkafka::admin::{AdminClient, AdminOptions};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::types::RDKafkaErrorCode;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::KafkaConfig;
+
+/// Clean up the Kafka consumer group
+///
+/// This function connects to the brokers and deletes the consumer group together
+/// with its committed offsets. The group must have no active members.
+///
+/// # Arguments
+/// * `config` - Kafka configuration containing connection details and the group ID
+///
+/// # Returns
+/// * `Ok(())` if the group was deleted or did not exist
+/// * `Err` if connection or deletion failed
+pub async fn cleanup_kafka_consumer_group(config: &KafkaConfig) -> Result<()> {
+    info!("Cleaning up Kafka consumer group '{}'", config.group_id);
+
+    let admin: AdminClient<DefaultClientContext> = config.client_config().create().map_err(|e| {
+        Error::Connection(format!("Failed to connect to Kafka for cleanup: {}", e))
+    })?;
+
+    let options = AdminOptions::new().operation_timeout(Some(Duration::from_secs(30)));
+    let results = admin
+        .delete_groups(&[config.group_id.as_str()], &options)
+        .await
+        .map_err(|e| Error::Connection(format!("Failed to delete consumer group: {}", e)))?;
+
+    for result in results {
+        match result {
+            Ok(group) => info!("Successfully deleted Kafka consumer group '{}'", group),
+            Err((group, RDKafkaErrorCode::GroupIdNotFound)) => {
+                info!("Consumer group '{}' not found, already deleted", group);
+            }
+            Err((group, code)) => {
+                warn!("Failed to delete consumer group '{}': {}", group, code);
+                return Err(Error::Connection(format!(
+                    "Failed to delete consumer group '{}': {}",
+                    group, code
+                )));
+            }
+        }
+    }
+    Ok(())
+}
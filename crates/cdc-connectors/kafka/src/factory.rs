@@ -13,4 +13,119 @@ impl ConnectorFactory for KafkaConnectorFactory {
         let config: KafkaConfig = serde_json::from_value(config)?;
         Ok(Box::new(KafkaConnector::new(config)))
     }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(KafkaConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::{DataRecord, Error};
+    use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+    use rdkafka::client::DefaultClientContext;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = KafkaConnectorFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["sasl_password"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["ssl_key_password"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert!(properties["sasl_username"].get(cdc_core::SECRET_SCHEMA_KEYWORD).is_none());
+        assert_eq!(properties["auto_offset_reset"]["default"], "earliest");
+        assert_eq!(properties["security_protocol"]["default"], "plaintext");
+    }
+
+    #[test]
+    fn test_client_config_includes_security_options() {
+        let config: KafkaConfig = serde_json::from_value(serde_json::json!({
+            "brokers": ["a:9092", "b:9092"],
+            "topic": "events",
+            "group_id": "cdc",
+            "security_protocol": "sasl_ssl",
+            "sasl_mechanism": "SCRAM-SHA-512",
+            "sasl_username": "user",
+            "sasl_password": "secret",
+        }))
+        .unwrap();
+        let client = config.client_config();
+
+        assert_eq!(client.get("bootstrap.servers"), Some("a:9092,b:9092"));
+        assert_eq!(client.get("security.protocol"), Some("sasl_ssl"));
+        assert_eq!(client.get("sasl.mechanism"), Some("SCRAM-SHA-512"));
+        assert_eq!(client.get("sasl.password"), Some("secret"));
+        assert_eq!(client.get("ssl.ca.location"), None);
+    }
+
+    struct KafkaHarness {
+        brokers: String,
+        topic: Option<String>,
+        producer: FutureProducer,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::ConnectorHarness for KafkaHarness {
+        async fn create(&mut self) -> Result<Box<dyn Connector>> {
+            // A fresh topic and group per connector so each one starts without earlier records
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            let topic = format!("cdc-conformance-{}", nanos);
+
+            let admin: AdminClient<DefaultClientContext> = ClientConfig::new()
+                .set("bootstrap.servers", &self.brokers)
+                .create()
+                .map_err(|e| Error::Connection(e.to_string()))?;
+            admin
+                .create_topics(
+                    &[NewTopic::new(&topic, 1, TopicReplication::Fixed(1))],
+                    &AdminOptions::new(),
+                )
+                .await
+                .map_err(|e| Error::Connection(e.to_string()))?;
+
+            self.topic = Some(topic.clone());
+            KafkaConnectorFactory.create(serde_json::json!({
+                "brokers": [self.brokers],
+                "topic": topic,
+                "group_id": topic,
+            }))
+        }
+
+        async fn publish(&mut self, records: Vec<DataRecord>) -> Result<()> {
+            let topic = self.topic.as_ref().expect("publish() before create()");
+            for record in records {
+                let payload = serde_json::to_string(&record)?;
+                self.producer
+                    .send(
+                        FutureRecord::<(), _>::to(topic).payload(&payload),
+                        Duration::from_secs(5),
+                    )
+                    .await
+                    .map_err(|(e, _)| Error::Connection(e.to_string()))?;
+            }
+            Ok(())
+        }
+    }
+
+    /// `CONFORMANCE_KAFKA_BROKERS=localhost:9092 cargo test -p cdc-kafka-connector -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a Kafka broker in CONFORMANCE_KAFKA_BROKERS"]
+    async fn test_conformance() {
+        let brokers = std::env::var("CONFORMANCE_KAFKA_BROKERS")
+            .expect("CONFORMANCE_KAFKA_BROKERS is not set");
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .create()
+            .expect("Failed to create producer");
+        let mut harness = KafkaHarness {
+            brokers,
+            topic: None,
+            producer,
+        };
+        cdc_conformance::run_connector_checks(&mut harness).await;
+    }
 }
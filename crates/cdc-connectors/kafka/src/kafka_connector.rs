@@ -1,21 +1,121 @@
 use async_trait::async_trait;
 use cdc_core::{Connector, ConnectorStatus, DataRecord, Error, Result};
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, Message, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::types::RDKafkaErrorCode;
+use rdkafka::util::Timeout;
+use rdkafka::{Offset, TopicPartitionList};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::{info, error};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How long `connect` waits for topic metadata before giving up
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a dead-letter message may wait for delivery
+const DEAD_LETTER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a connector stopped at an unparseable message repeats the error
+const BLOCKED_REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct KafkaConfig {
-    /// Kafka brokers
+    /// Kafka brokers (host:port)
     pub brokers: Vec<String>,
-    
+
     /// Topic to consume from
     pub topic: String,
-    
+
     /// Consumer group ID
     pub group_id: String,
-    
-    /// Start from beginning or latest
-    pub auto_offset_reset: String,
+
+    /// Where to start when the group has no committed offset
+    #[serde(default)]
+    pub auto_offset_reset: AutoOffsetReset,
+
+    /// How often offsets of written records are committed, in milliseconds
+    #[serde(default = "default_commit_interval")]
+    pub commit_interval_ms: u64,
+
+    /// Topic that unparseable messages are copied to before they are skipped;
+    /// without it the connector stops at the first such message
+    #[serde(default)]
+    pub dead_letter_topic: Option<String>,
+
+    /// Protocol used to talk to the brokers
+    #[serde(default)]
+    pub security_protocol: SecurityProtocol,
+
+    /// SASL mechanism (PLAIN, SCRAM-SHA-256 or SCRAM-SHA-512)
+    #[serde(default)]
+    pub sasl_mechanism: Option<String>,
+
+    /// SASL username
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+
+    /// SASL password
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub sasl_password: Option<String>,
+
+    /// CA certificate (PEM file) used to verify the brokers
+    #[serde(default)]
+    pub ssl_ca_location: Option<String>,
+
+    /// Client certificate (PEM file) for mutual TLS
+    #[serde(default)]
+    pub ssl_certificate_location: Option<String>,
+
+    /// Client private key (PEM file) for mutual TLS
+    #[serde(default)]
+    pub ssl_key_location: Option<String>,
+
+    /// Password of the client private key
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub ssl_key_password: Option<String>,
+}
+
+fn default_commit_interval() -> u64 {
+    5000 // 5 seconds
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoOffsetReset {
+    /// Start from the oldest retained message
+    #[default]
+    Earliest,
+    /// Start from messages produced after the consumer joins
+    Latest,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityProtocol {
+    #[default]
+    Plaintext,
+    Ssl,
+    SaslPlaintext,
+    SaslSsl,
+}
+
+impl SecurityProtocol {
+    fn as_str(self) -> &'static str {
+        match self {
+            SecurityProtocol::Plaintext => "plaintext",
+            SecurityProtocol::Ssl => "ssl",
+            SecurityProtocol::SaslPlaintext => "sasl_plaintext",
+            SecurityProtocol::SaslSsl => "sasl_ssl",
+        }
+    }
 }
 
 impl Default for KafkaConfig {
@@ -24,15 +124,71 @@ impl Default for KafkaConfig {
             brokers: vec!["localhost:9092".to_string()],
             topic: "cdc-events".to_string(),
             group_id: "cdc-consumer".to_string(),
-            auto_offset_reset: "earliest".to_string(),
+            auto_offset_reset: AutoOffsetReset::Earliest,
+            commit_interval_ms: 5000,
+            dead_letter_topic: None,
+            security_protocol: SecurityProtocol::Plaintext,
+            sasl_mechanism: None,
+            sasl_username: None,
+            sasl_password: None,
+            ssl_ca_location: None,
+            ssl_certificate_location: None,
+            ssl_key_location: None,
+            ssl_key_password: None,
+        }
+    }
+}
+
+impl KafkaConfig {
+    /// librdkafka settings for connecting to the brokers (shared by the consumer and cleanup)
+    pub(crate) fn client_config(&self) -> ClientConfig {
+        let mut client = ClientConfig::new();
+        client
+            .set("bootstrap.servers", self.brokers.join(","))
+            .set("security.protocol", self.security_protocol.as_str());
+
+        let optional = [
+            ("sasl.mechanism", &self.sasl_mechanism),
+            ("sasl.username", &self.sasl_username),
+            ("sasl.password", &self.sasl_password),
+            ("ssl.ca.location", &self.ssl_ca_location),
+            ("ssl.certificate.location", &self.ssl_certificate_location),
+            ("ssl.key.location", &self.ssl_key_location),
+            ("ssl.key.password", &self.ssl_key_password),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                client.set(key, value);
+            }
         }
+        client
     }
 }
 
+/// An unparseable message waiting to be copied to the dead-letter topic
+struct DeadLetter {
+    partition: i32,
+    offset: i64,
+    key: Option<Vec<u8>>,
+    payload: Vec<u8>,
+    error: String,
+}
+
 pub struct KafkaConnector {
     config: KafkaConfig,
     status: ConnectorStatus,
-    // consumer: Option<StreamConsumer>,  // Uncomment when using rdkafka
+    consumer: Option<Arc<StreamConsumer>>,
+    producer: Option<FutureProducer>,
+    /// Next offset per partition after the messages handed to the flow
+    received: HashMap<i32, i64>,
+    /// Kept until delivered, so a cancelled `receive` retries it
+    dead_letter: Option<DeadLetter>,
+    /// Unparseable message the connector stopped at, without a dead-letter topic
+    blocked: Option<String>,
+    last_blocked_report: Instant,
+    /// Offsets were stored since the last commit
+    uncommitted: bool,
+    last_commit: Instant,
 }
 
 impl KafkaConnector {
@@ -40,62 +196,284 @@ impl KafkaConnector {
         Self {
             config,
             status: ConnectorStatus::default(),
+            consumer: None,
+            producer: None,
+            received: HashMap::new(),
+            dead_letter: None,
+            blocked: None,
+            last_blocked_report: Instant::now(),
+            uncommitted: false,
+            last_commit: Instant::now(),
+        }
+    }
+
+    /// Copy the pending unparseable message to the dead-letter topic, then skip it
+    async fn send_dead_letter(&mut self) -> Result<()> {
+        let (Some(letter), Some(producer), Some(topic)) = (
+            self.dead_letter.as_ref(),
+            self.producer.as_ref(),
+            self.config.dead_letter_topic.as_deref(),
+        ) else {
+            return Ok(());
+        };
+
+        let mut record = FutureRecord::to(topic)
+            .payload(&letter.payload)
+            .headers(OwnedHeaders::new().insert(Header {
+                key: "cdc-error",
+                value: Some(&letter.error),
+            }));
+        if let Some(key) = &letter.key {
+            record = record.key(key);
+        }
+        producer
+            .send(record, Timeout::After(DEAD_LETTER_TIMEOUT))
+            .await
+            .map_err(|(e, _)| {
+                Error::Connection(format!(
+                    "Failed to send message {} to dead-letter topic '{}': {}",
+                    letter.offset, topic, e
+                ))
+            })?;
+
+        warn!(
+            "Sent unparseable message {}/{}@{} to dead-letter topic '{}'",
+            self.config.topic, letter.partition, letter.offset, topic
+        );
+        self.received.insert(letter.partition, letter.offset + 1);
+        self.dead_letter = None;
+        Ok(())
+    }
+
+    /// Commit stored offsets in the background once `commit_interval_ms` has passed
+    fn maybe_commit(&mut self) {
+        let interval = Duration::from_millis(self.config.commit_interval_ms);
+        if !self.uncommitted || self.last_commit.elapsed() < interval {
+            return;
         }
+        let Some(consumer) = self.consumer.as_ref() else {
+            return;
+        };
+
+        match consumer.commit_consumer_state(CommitMode::Async) {
+            Ok(()) => debug!("Committed offsets for group '{}'", self.config.group_id),
+            Err(e) => warn!(
+                "Failed to commit offsets for group '{}': {}",
+                self.config.group_id, e
+            ),
+        }
+        self.uncommitted = false;
+        self.last_commit = Instant::now();
     }
 }
 
 #[async_trait]
 impl Connector for KafkaConnector {
     async fn connect(&mut self) -> Result<()> {
-        info!("Connecting to Kafka brokers: {:?}", self.config.brokers);
-        
-        // TODO: Implement actual Kafka connection
-        // Example with rdkafka:
-        // let consumer: StreamConsumer = ClientConfig::new()
-        //     .set("bootstrap.servers", &self.config.brokers.join(","))
-        //     .set("group.id", &self.config.group_id)
-        //     .set("auto.offset.reset", &self.config.auto_offset_reset)
-        //     .create()?;
-        // 
-        // consumer.subscribe(&[&self.config.topic])?;
-        // self.consumer = Some(consumer);
-        
+        info!(
+            "Connecting to Kafka brokers: {:?} (topic={}, group={})",
+            self.config.brokers, self.config.topic, self.config.group_id
+        );
+
+        let auto_offset_reset = match self.config.auto_offset_reset {
+            AutoOffsetReset::Earliest => "earliest",
+            AutoOffsetReset::Latest => "latest",
+        };
+        let consumer: StreamConsumer = self
+            .config
+            .client_config()
+            .set("group.id", &self.config.group_id)
+            .set("auto.offset.reset", auto_offset_reset)
+            // Offsets are stored once the flow has written the records and committed by the connector
+            .set("enable.auto.commit", "false")
+            .set("enable.auto.offset.store", "false")
+            .create()
+            .map_err(|e| Error::Connection(format!("Failed to create Kafka consumer: {}", e)))?;
+        let consumer = Arc::new(consumer);
+
+        // Fetching metadata fails fast on unreachable brokers or bad credentials
+        let probe = Arc::clone(&consumer);
+        let topic = self.config.topic.clone();
+        tokio::task::spawn_blocking(move || {
+            probe.fetch_metadata(Some(&topic), METADATA_TIMEOUT)
+        })
+        .await
+        .map_err(|e| Error::Connection(format!("Kafka metadata task failed: {}", e)))?
+        .map_err(|e| Error::Connection(format!("Failed to connect to Kafka: {}", e)))?;
+
+        consumer
+            .subscribe(&[&self.config.topic])
+            .map_err(|e| Error::Connection(format!("Failed to subscribe: {}", e)))?;
+
+        if self.config.dead_letter_topic.is_some() {
+            let producer: FutureProducer = self.config.client_config().create().map_err(|e| {
+                Error::Connection(format!("Failed to create dead-letter producer: {}", e))
+            })?;
+            self.producer = Some(producer);
+        }
+
+        info!("Connected to Kafka successfully");
+        self.consumer = Some(consumer);
+        self.received.clear();
+        self.dead_letter = None;
+        self.blocked = None;
+        self.uncommitted = false;
+        self.last_commit = Instant::now();
         self.status.connected = true;
-        info!("Connected to Kafka successfully (stub implementation)");
-        
+
         Ok(())
     }
-    
+
     async fn disconnect(&mut self) -> Result<()> {
         info!("Disconnecting from Kafka");
         self.status.connected = false;
-        Ok(())
+
+        self.producer = None;
+        let Some(consumer) = self.consumer.take() else {
+            return Ok(());
+        };
+        // Only offsets stored by `commit` are committed; unwritten records are redelivered
+        let commit = std::mem::take(&mut self.uncommitted);
+
+        // Committing synchronously and closing the consumer both block
+        let result = tokio::task::spawn_blocking(move || {
+            let result = if commit {
+                consumer.commit_consumer_state(CommitMode::Sync)
+            } else {
+                Ok(())
+            };
+            consumer.unsubscribe();
+            drop(consumer);
+            result
+        })
+        .await
+        .map_err(|e| Error::Connection(format!("Kafka disconnect task failed: {}", e)))?;
+
+        match result {
+            Ok(()) | Err(KafkaError::ConsumerCommit(RDKafkaErrorCode::NoOffset)) => {
+                info!("Disconnected from Kafka");
+                Ok(())
+            }
+            Err(e) => Err(Error::Connection(format!(
+                "Failed to commit offsets on disconnect: {}",
+                e
+            ))),
+        }
     }
-    
+
     fn is_connected(&self) -> bool {
         self.status.connected
     }
-    
+
     async fn receive(&mut self) -> Result<Option<DataRecord>> {
-        // TODO: Implement actual message consumption
-        // Example with rdkafka:
-        // if let Some(consumer) = &self.consumer {
-        //     match consumer.recv().await {
-        //         Ok(message) => {
-        //             let payload = message.payload().ok_or(...)?;
-        //             let record = serde_json::from_slice(payload)?;
-        //             self.status.records_received += 1;
-        //             Ok(Some(record))
-        //         }
-        //         Err(e) => Err(...)
-        //     }
-        // }
-        
-        // Stub: return None to indicate no more messages
-        Ok(None)
+        self.maybe_commit();
+
+        if let Some(blocked) = &self.blocked {
+            // Stay on the message so it is redelivered after a restart, and keep the error visible
+            if self.last_blocked_report.elapsed() < BLOCKED_REPORT_INTERVAL {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                return Ok(None);
+            }
+            self.last_blocked_report = Instant::now();
+            return Err(Error::Pipeline(blocked.clone()));
+        }
+        if self.dead_letter.is_some() {
+            self.send_dead_letter().await?;
+        }
+
+        let consumer = self
+            .consumer
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        let message = match consumer.recv().await {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to receive message from Kafka: {}", e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                return Err(Error::Connection(format!("Failed to receive message: {}", e)));
+            }
+        };
+
+        debug!(
+            "Received message from Kafka: {}/{}@{}",
+            message.topic(),
+            message.partition(),
+            message.offset()
+        );
+        let payload = message.payload().unwrap_or_default();
+        let (partition, offset) = (message.partition(), message.offset());
+
+        match serde_json::from_slice::<DataRecord>(payload) {
+            Ok(record) => {
+                // Stored for committing once the flow has written the record
+                self.received.insert(partition, offset + 1);
+                self.status.records_received += 1;
+                Ok(Some(record))
+            }
+            Err(e) => {
+                let err_msg = format!(
+                    "Failed to deserialize message {}/{}@{} into DataRecord: {}",
+                    self.config.topic, partition, offset, e
+                );
+                error!("{}", err_msg);
+                self.status.errors += 1;
+                self.status.last_error = Some(err_msg.clone());
+
+                if self.config.dead_letter_topic.is_none() {
+                    self.blocked = Some(format!(
+                        "Stopped at unparseable message: {}; set dead_letter_topic to skip such messages",
+                        err_msg
+                    ));
+                    self.last_blocked_report = Instant::now();
+                    return Err(Error::Serialization(e));
+                }
+
+                self.dead_letter = Some(DeadLetter {
+                    partition,
+                    offset,
+                    key: message.key().map(<[u8]>::to_vec),
+                    payload: payload.to_vec(),
+                    error: err_msg,
+                });
+                drop(message);
+                self.send_dead_letter().await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        let Some(consumer) = self.consumer.as_ref() else {
+            return Ok(());
+        };
+        if self.received.is_empty() {
+            return Ok(());
+        }
+
+        let mut offsets = TopicPartitionList::new();
+        for (partition, offset) in self.received.drain() {
+            offsets
+                .add_partition_offset(&self.config.topic, partition, Offset::Offset(offset))
+                .map_err(|e| Error::Connection(format!("Invalid offset {}: {}", offset, e)))?;
+        }
+        consumer
+            .store_offsets(&offsets)
+            .map_err(|e| Error::Connection(format!("Failed to store offsets: {}", e)))?;
+        self.uncommitted = true;
+        self.maybe_commit();
+        Ok(())
     }
-    
+
     fn status(&self) -> ConnectorStatus {
         self.status.clone()
     }
 }
+
+#[async_trait]
+impl cdc_core::ConnectorCleanup for KafkaConnector {
+    async fn cleanup(&self) -> Result<()> {
+        crate::cleanup::cleanup_kafka_consumer_group(&self.config).await
+    }
+}
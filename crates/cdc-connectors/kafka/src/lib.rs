@@ -1,5 +1,7 @@
+mod cleanup;
 mod kafka_connector;
 mod factory;
 
-pub use kafka_connector::{KafkaConnector, KafkaConfig};
+pub use cleanup::cleanup_kafka_consumer_group;
+pub use kafka_connector::{AutoOffsetReset, KafkaConnector, KafkaConfig, SecurityProtocol};
 pub use factory::KafkaConnectorFactory;
@@ -13,4 +13,8 @@ impl ConnectorFactory for NatsConnectorFactory {
         let config: NatsConfig = serde_json::from_value(config)?;
         Ok(Box::new(NatsConnector::new(config)))
     }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(NatsConfig).to_value()
+    }
 }
@@ -4,12 +4,13 @@ use async_nats::jetstream::consumer::pull::Stream;
 use async_nats::jetstream::consumer::PullConsumer;
 use async_nats::{jetstream, Client, Subscriber};
 use async_trait::async_trait;
-use cdc_core::{Connector, ConnectorStatus, DataRecord, Error, Result};
+use cdc_core::{redact_url, Connector, ConnectorStatus, DataRecord, Error, Result};
 use futures::StreamExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NatsConfig {
     /// NATS server URL(s)
     pub servers: Vec<String>,
@@ -32,10 +33,12 @@ pub struct NatsConfig {
 
     /// Optional password for authentication
     #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
     pub password: Option<String>,
 
     /// Optional token for authentication
     #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
     pub token: Option<String>,
 }
 
@@ -79,7 +82,8 @@ impl NatsConnector {
 #[async_trait]
 impl Connector for NatsConnector {
     async fn connect(&mut self) -> Result<()> {
-        info!("Connecting to NATS servers: {:?}", self.config.servers);
+        let servers: Vec<_> = self.config.servers.iter().map(|s| redact_url(s)).collect();
+        info!("Connecting to NATS servers: {:?}", servers);
 
         // Build connection options with authentication if provided
         let mut opts = async_nats::ConnectOptions::new();
@@ -164,6 +168,8 @@ impl Connector for NatsConnector {
         if let Some(subscriber) = self.subscriber.take() {
             drop(subscriber);
         }
+        self.message_stream = None;
+        self.consumer = None;
 
         if let Some(client) = self.client.take() {
             client
@@ -228,6 +234,7 @@ impl Connector for NatsConnector {
                     Error::Connection(format!("Failed to acknowledge message: {}", e))
                 })?;
 
+                self.status.records_received += 1;
                 return Ok(Some(record));
             }
             info!("receive message 02");
@@ -13,4 +13,22 @@ impl ConnectorFactory for RedisConnectorFactory {
         let config: RedisConfig = serde_json::from_value(config)?;
         Ok(Box::new(RedisConnector::new(config)))
     }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(RedisConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_url_secret() {
+        let schema = RedisConnectorFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["url"]["default"], "redis://localhost:6379");
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+    }
 }
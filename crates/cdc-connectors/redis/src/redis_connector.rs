@@ -1,14 +1,16 @@
 use ::async_trait::async_trait;
-use cdc_core::{Connector, ConnectorStatus, DataRecord, Error, Result};
+use cdc_core::{redact_url, Connector, ConnectorStatus, DataRecord, Error, Result};
 use redis::{aio::MultiplexedConnection, AsyncCommands, Client, ConnectionInfo};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use tracing::{debug, error, info};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RedisConfig {
     /// Redis connection URL (e.g., redis://localhost:6379)
     #[serde(default = "default_url")]
+    #[schemars(extend("x-secret" = true))]
     pub url: String,
 
     /// Stream key to read from
@@ -180,7 +182,8 @@ impl Connector for RedisConnector {
     async fn connect(&mut self) -> Result<()> {
         info!(
             "Connecting to Redis: {} (db={})",
-            self.config.url, self.config.database
+            redact_url(&self.config.url),
+            self.config.database
         );
 
         let mut connection_info: ConnectionInfo = Client::open(self.config.url.as_str())
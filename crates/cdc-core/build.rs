@@ -0,0 +1,16 @@
+use std::process::Command;
+
+fn main() {
+    // Plugins must be built with the same compiler as the host; record it so
+    // the loader can compare versions before trusting a plugin's declaration.
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=CDC_RUSTC_VERSION={}", version.trim());
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
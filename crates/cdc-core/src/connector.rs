@@ -19,6 +19,16 @@ pub trait Connector: Send + Sync {
 
     /// Get connector status information
     fn status(&self) -> ConnectorStatus;
+
+    /// Tell the connector the name of the flow it feeds; called once when the
+    /// flow is built, before `connect`
+    fn bind_flow(&mut self, _flow: &str) {}
+
+    /// Called once every record received so far has been written to all
+    /// destinations, so the connector can acknowledge them at the source
+    async fn commit(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -21,6 +21,25 @@ pub trait Destination: Send + Sync {
 
     /// Get destination status information
     fn status(&self) -> DestinationStatus;
+
+    /// Called about once a second by the flow, also while no records arrive,
+    /// for work that must not wait for the next batch (e.g. time-based rolling)
+    async fn tick(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether records accepted by `write_batch` are not durable yet, e.g. because
+    /// they sit in an object whose upload is not complete. The flow only commits the
+    /// source while no destination holds records back
+    fn has_pending(&self) -> bool {
+        false
+    }
+
+    /// Whether the next `tick` makes the held-back records durable; the flow then
+    /// flushes its buffer first, so that afterwards the source can be committed
+    fn pending_due(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -16,6 +16,9 @@ pub enum Error {
     #[error("Pipeline error: {0}")]
     Pipeline(String),
     
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+    
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     
@@ -1,6 +1,18 @@
 use crate::{Connector, Destination, Result};
 use serde_json::Value;
 
+/// Schema keyword marking a config field as secret (passwords, tokens, keys)
+pub const SECRET_SCHEMA_KEYWORD: &str = "x-secret";
+
+/// Fallback schema for factories that don't describe their configuration
+fn open_config_schema() -> Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "additionalProperties": true
+    })
+}
+
 /// Factory trait for creating connectors
 pub trait ConnectorFactory: Send + Sync {
     /// Get the name/type identifier for this connector
@@ -8,6 +20,13 @@ pub trait ConnectorFactory: Send + Sync {
     
     /// Create a new connector instance from configuration
     fn create(&self, config: Value) -> Result<Box<dyn Connector>>;
+
+    /// JSON Schema describing the configuration accepted by `create`.
+    ///
+    /// Fields holding credentials are marked with `"x-secret": true`.
+    fn config_schema(&self) -> Value {
+        open_config_schema()
+    }
 }
 
 /// Factory trait for creating destinations
@@ -17,4 +36,11 @@ pub trait DestinationFactory: Send + Sync {
     
     /// Create a new destination instance from configuration
     fn create(&self, config: Value) -> Result<Box<dyn Destination>>;
+
+    /// JSON Schema describing the configuration accepted by `create`.
+    ///
+    /// Fields holding credentials are marked with `"x-secret": true`.
+    fn config_schema(&self) -> Value {
+        open_config_schema()
+    }
 }
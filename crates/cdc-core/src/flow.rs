@@ -6,7 +6,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 
 /// Configuration structures for flows
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,7 +93,7 @@ pub enum FlowCommand {
 }
 
 /// Flow status
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FlowStatus {
     Running,
     Stopped,
@@ -109,7 +109,10 @@ pub struct Flow {
     batch_size: usize,
     buffer: Vec<DataRecord>,
     control_rx: Option<mpsc::Receiver<FlowCommand>>,
-    last_flush: std::time::Instant,
+    last_flush: tokio::time::Instant,
+    last_tick: tokio::time::Instant,
+    /// A flush succeeded but the source commit waits for destinations holding records back
+    commit_deferred: bool,
     messages_received: Arc<RwLock<u64>>,
     destination_error_counters: Vec<u64>,
     error_threshold: u64,
@@ -119,10 +122,11 @@ pub struct Flow {
 impl Flow {
     pub fn new(
         name: String,
-        connector: Box<dyn Connector>,
+        mut connector: Box<dyn Connector>,
         destinations: Vec<Box<dyn Destination>>,
         batch_size: usize,
     ) -> Self {
+        connector.bind_flow(&name);
         let dest_count = destinations.len();
         Self {
             name,
@@ -131,7 +135,9 @@ impl Flow {
             batch_size,
             buffer: Vec::with_capacity(batch_size),
             control_rx: None,
-            last_flush: std::time::Instant::now(),
+            last_flush: tokio::time::Instant::now(),
+            last_tick: tokio::time::Instant::now(),
+            commit_deferred: false,
             messages_received: Arc::new(RwLock::new(0)),
             destination_error_counters: vec![0; dest_count],
             error_threshold: 20, // Default threshold
@@ -144,6 +150,12 @@ impl Flow {
         self
     }
 
+    /// Consecutive write failures on one destination before the flow reports it
+    pub fn with_error_threshold(mut self, error_threshold: u64) -> Self {
+        self.error_threshold = error_threshold;
+        self
+    }
+
     pub fn with_control(mut self, control_rx: mpsc::Receiver<FlowCommand>) -> Self {
         self.control_rx = Some(control_rx);
         self
@@ -154,14 +166,14 @@ impl Flow {
         info!("Creating flow '{}'", config.name);
 
         // Create connector
-        let connector_factory = registry.get_connector_factory(&config.connector.connector_type)?;
-        let connector = connector_factory.create(config.connector.config)?;
+        let connector = registry
+            .create_connector(&config.connector.connector_type, &config.connector.config)?;
 
         // Create destinations
         let mut destinations = Vec::new();
         for dest_config in config.destinations {
-            let dest_factory = registry.get_destination_factory(&dest_config.destination_type)?;
-            let destination = dest_factory.create(dest_config.config)?;
+            let destination =
+                registry.create_destination(&dest_config.destination_type, &dest_config.config)?;
             destinations.push(destination);
         }
 
@@ -226,6 +238,27 @@ impl Flow {
                 }
             }
 
+            if self.last_tick.elapsed() >= Duration::from_secs(1) {
+                self.last_tick = tokio::time::Instant::now();
+
+                // Records made durable by the tick then cover everything received
+                if !self.buffer.is_empty()
+                    && self.destinations.iter().any(|dest| dest.pending_due())
+                {
+                    if let Err(e) = self.flush().await {
+                        error!("[{}] Flush before tick failed: {}", self.name, e);
+                    }
+                }
+                for (idx, dest) in self.destinations.iter_mut().enumerate() {
+                    if let Err(e) = dest.tick().await {
+                        error!("[{}] Destination {} tick failed: {}", self.name, idx, e);
+                    }
+                }
+                if self.commit_deferred && self.buffer.is_empty() {
+                    self.commit_source().await;
+                }
+            }
+
             // Receive with timeout to allow control and flush checks
             match tokio::time::timeout(Duration::from_millis(500), self.connector.receive()).await {
                 Ok(Ok(Some(record))) => {
@@ -250,19 +283,25 @@ impl Flow {
             }
         }
 
-        // Flush remaining records
-        if !self.buffer.is_empty() {
-            let _ = self.flush().await.map_err(|e| {
+        // Flush remaining records; the source is only acknowledged if this succeeds
+        let flushed = self
+            .flush()
+            .await
+            .map_err(|e| {
                 error!("[{}] Final flush failed: {}", self.name, e);
                 e
-            });
-        }
+            })
+            .is_ok();
 
-        // Disconnect
-        self.connector.disconnect().await?;
+        // Disconnect destinations first: they make held-back records durable on the
+        // way out, after which the deferred commit can go through
         for dest in &mut self.destinations {
             dest.disconnect().await?;
         }
+        if flushed && self.commit_deferred {
+            self.commit_source().await;
+        }
+        self.connector.disconnect().await?;
 
         info!("[{}] Flow stopped", self.name);
         Ok(())
@@ -270,7 +309,8 @@ impl Flow {
 
     async fn flush(&mut self) -> Result<()> {
         if self.buffer.is_empty() {
-            self.last_flush = std::time::Instant::now();
+            self.last_flush = tokio::time::Instant::now();
+            self.commit_source().await;
             return Ok(());
         }
 
@@ -344,9 +384,28 @@ impl Flow {
             }
         }
 
-        self.last_flush = std::time::Instant::now();
+        self.last_flush = tokio::time::Instant::now();
+        self.commit_source().await;
         Ok(())
     }
+
+    /// Let the connector acknowledge everything received so far, now that it is written,
+    /// unless a destination still holds some of it back
+    async fn commit_source(&mut self) {
+        if self.destinations.iter().any(|dest| dest.has_pending()) {
+            debug!(
+                "[{}] Deferring source commit until destinations catch up",
+                self.name
+            );
+            self.commit_deferred = true;
+            return;
+        }
+
+        self.commit_deferred = false;
+        if let Err(e) = self.connector.commit().await {
+            error!("[{}] Failed to commit source position: {}", self.name, e);
+        }
+    }
 }
 
 /// Flow handle for managing running flow
@@ -380,14 +439,14 @@ impl FlowBuilder {
         batch_size: usize,
     ) -> Result<Flow> {
         // Create connector
-        let connector_factory = self.registry.get_connector_factory(connector_type)?;
-        let connector = connector_factory.create(connector_config.clone())?;
+        let connector = self
+            .registry
+            .create_connector(connector_type, connector_config)?;
 
         // Create destinations
         let mut dest_instances = Vec::new();
         for (dest_type, dest_config) in destinations {
-            let dest_factory = self.registry.get_destination_factory(dest_type)?;
-            let destination = dest_factory.create(dest_config.clone())?;
+            let destination = self.registry.create_destination(dest_type, dest_config)?;
             dest_instances.push(destination);
         }
 
@@ -548,4 +607,416 @@ impl FlowOrchestrator {
         }
         Ok(())
     }
+
+    /// Stop every flow and wait for each to flush its buffer and disconnect.
+    ///
+    /// Flows still running after `timeout` are aborted. Returns the number of flows stopped.
+    pub async fn shutdown(&self, timeout: Duration) -> usize {
+        let handles: Vec<_> = self.flows.lock().await.drain().map(|(_, h)| h).collect();
+        for handle in &handles {
+            handle.control_tx.send(FlowCommand::Stop).await.ok();
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let stopped = handles.len();
+        for handle in handles {
+            let abort = handle.task_handle.abort_handle();
+            if tokio::time::timeout_at(deadline, handle.task_handle)
+                .await
+                .is_err()
+            {
+                error!(
+                    "Flow '{}' did not stop within {:?}, aborting it",
+                    handle.name, timeout
+                );
+                abort.abort();
+            }
+        }
+        stopped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{sample_record, MemoryConnector, MemoryDestination};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    fn records(count: u64) -> Vec<DataRecord> {
+        (0..count).map(|id| sample_record("users", id)).collect()
+    }
+
+    /// Run a flow with a control channel; returns the sender and the task
+    fn spawn_flow(flow: Flow) -> (mpsc::Sender<FlowCommand>, JoinHandle<Result<()>>) {
+        let (tx, rx) = mpsc::channel(8);
+        let task = tokio::spawn(flow.with_control(rx).run());
+        (tx, task)
+    }
+
+    async fn stop(tx: mpsc::Sender<FlowCommand>, task: JoinHandle<Result<()>>) {
+        tx.send(FlowCommand::Stop).await.unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[derive(Default)]
+    struct CountingNotifier {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Notifier for CountingNotifier {
+        async fn send_error_notification(&self, _flow_name: &str, _details: &str) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_writes_full_batches_and_flushes_rest_on_stop() {
+        let destination = MemoryDestination::new();
+        let flow = Flow::new(
+            "batching".to_string(),
+            Box::new(MemoryConnector::new(records(7))),
+            vec![Box::new(destination.clone())],
+            3,
+        );
+
+        let (tx, task) = spawn_flow(flow);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(destination.batch_sizes(), vec![3, 3]);
+
+        stop(tx, task).await;
+        assert_eq!(destination.batch_sizes(), vec![3, 3, 1]);
+        assert!(!destination.is_connected());
+
+        // Records arrive in order
+        let ids: Vec<_> = destination
+            .records()
+            .iter()
+            .map(|r| r.parse_record().unwrap()["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ids, (0..7).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_flushes_partial_batch_after_timeout() {
+        let destination = MemoryDestination::new();
+        let (sender, connector) = MemoryConnector::channel();
+        let flow = Flow::new(
+            "flush-timeout".to_string(),
+            Box::new(connector),
+            vec![Box::new(destination.clone())],
+            100,
+        );
+
+        let (tx, task) = spawn_flow(flow);
+        for record in records(2) {
+            sender.send(record).unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert!(destination.batch_sizes().is_empty());
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(destination.batch_sizes(), vec![2]);
+
+        stop(tx, task).await;
+        assert_eq!(destination.batch_sizes(), vec![2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_keeps_records_while_destination_fails() {
+        let destination = MemoryDestination::new();
+        destination.fail_next(2);
+        let notifier = Arc::new(CountingNotifier::default());
+        let flow = Flow::new(
+            "retry".to_string(),
+            Box::new(MemoryConnector::new(records(3))),
+            vec![Box::new(destination.clone())],
+            1,
+        )
+        .with_error_threshold(3)
+        .with_notifier(notifier.clone());
+
+        let (tx, task) = spawn_flow(flow);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        stop(tx, task).await;
+
+        // Failed batches are retried with the next record, nothing is lost
+        assert_eq!(destination.write_attempts(), 3);
+        assert_eq!(destination.records().len(), 3);
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 0);
+    }
+
+    /// Records how many records had been received each time the flow committed
+    struct CommitTracking {
+        inner: MemoryConnector,
+        received: usize,
+        commits: Arc<std::sync::Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl Connector for CommitTracking {
+        async fn connect(&mut self) -> Result<()> {
+            self.inner.connect().await
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            self.inner.disconnect().await
+        }
+
+        fn is_connected(&self) -> bool {
+            self.inner.is_connected()
+        }
+
+        async fn receive(&mut self) -> Result<Option<DataRecord>> {
+            let record = self.inner.receive().await?;
+            self.received += usize::from(record.is_some());
+            Ok(record)
+        }
+
+        fn status(&self) -> crate::ConnectorStatus {
+            self.inner.status()
+        }
+
+        async fn commit(&mut self) -> Result<()> {
+            self.commits.lock().unwrap().push(self.received);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_commits_source_only_after_successful_flush() {
+        let destination = MemoryDestination::new();
+        destination.fail_next(1);
+        let commits = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let connector = CommitTracking {
+            inner: MemoryConnector::new(records(4)),
+            received: 0,
+            commits: commits.clone(),
+        };
+        let flow = Flow::new(
+            "commit".to_string(),
+            Box::new(connector),
+            vec![Box::new(destination.clone())],
+            2,
+        );
+
+        let (tx, task) = spawn_flow(flow);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        stop(tx, task).await;
+
+        // The failed first batch is not committed; the retry covers it
+        assert_eq!(*commits.lock().unwrap(), vec![3, 4]);
+        assert_eq!(destination.records().len(), 4);
+    }
+
+    /// Holds written records back until a tick once `due` is set, like a destination
+    /// filling an object that is only uploaded later
+    struct Holding {
+        inner: MemoryDestination,
+        held: Vec<DataRecord>,
+        due: Arc<AtomicBool>,
+    }
+
+    impl Holding {
+        async fn release(&mut self) -> Result<()> {
+            if !self.held.is_empty() {
+                self.inner
+                    .write_batch(std::mem::take(&mut self.held))
+                    .await?;
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Destination for Holding {
+        async fn connect(&mut self) -> Result<()> {
+            self.inner.connect().await
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            self.release().await?;
+            self.inner.disconnect().await
+        }
+
+        fn is_connected(&self) -> bool {
+            self.inner.is_connected()
+        }
+
+        async fn write(&mut self, record: DataRecord) -> Result<()> {
+            self.write_batch(vec![record]).await
+        }
+
+        async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+            self.held.extend(records);
+            Ok(())
+        }
+
+        fn status(&self) -> crate::DestinationStatus {
+            self.inner.status()
+        }
+
+        async fn tick(&mut self) -> Result<()> {
+            if self.pending_due() {
+                self.release().await?;
+            }
+            Ok(())
+        }
+
+        fn has_pending(&self) -> bool {
+            !self.held.is_empty()
+        }
+
+        fn pending_due(&self) -> bool {
+            self.due.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_defers_commit_while_destination_holds_records() {
+        let destination = MemoryDestination::new();
+        let due = Arc::new(AtomicBool::new(false));
+        let commits = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (sender, inner) = MemoryConnector::channel();
+        let connector = CommitTracking {
+            inner,
+            received: 0,
+            commits: commits.clone(),
+        };
+        let flow = Flow::new(
+            "deferred".to_string(),
+            Box::new(connector),
+            vec![Box::new(Holding {
+                inner: destination.clone(),
+                held: Vec::new(),
+                due: due.clone(),
+            })],
+            2,
+        );
+
+        let (tx, task) = spawn_flow(flow);
+        for record in records(3) {
+            sender.send(record).unwrap();
+        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(commits.lock().unwrap().is_empty());
+
+        // The buffered third record is flushed before the tick releases everything
+        due.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(*commits.lock().unwrap(), vec![3]);
+        assert_eq!(destination.records().len(), 3);
+        due.store(false, Ordering::SeqCst);
+
+        // Records still held back on stop are committed once disconnecting releases them
+        sender.send(sample_record("users", 3)).unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        stop(tx, task).await;
+        assert_eq!(*commits.lock().unwrap(), vec![3, 4]);
+        assert_eq!(destination.records().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flow_notifies_when_error_threshold_reached() {
+        let destination = MemoryDestination::new();
+        destination.set_failing(true);
+        let notifier = Arc::new(CountingNotifier::default());
+        let (sender, connector) = MemoryConnector::channel();
+        let flow = Flow::new(
+            "threshold".to_string(),
+            Box::new(connector),
+            vec![Box::new(destination.clone())],
+            1,
+        )
+        .with_error_threshold(3)
+        .with_notifier(notifier.clone());
+
+        let (tx, task) = spawn_flow(flow);
+        for record in records(2) {
+            sender.send(record).unwrap();
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(destination.write_attempts(), 2);
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 0);
+
+        sender.send(sample_record("users", 2)).unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(destination.write_attempts(), 3);
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 1);
+
+        // Once the destination recovers the retained records go through
+        destination.set_failing(false);
+        stop(tx, task).await;
+        assert_eq!(destination.records().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_orchestrator_lifecycle() {
+        let orchestrator = FlowOrchestrator::new(Arc::new(Registry::new()));
+        let destination = MemoryDestination::new();
+        let new_flow = |destination: &MemoryDestination| {
+            Flow::new(
+                "lifecycle".to_string(),
+                Box::new(MemoryConnector::new(records(5))),
+                vec![Box::new(destination.clone())],
+                10,
+            )
+        };
+
+        orchestrator.add_flow(new_flow(&destination)).await.unwrap();
+        assert!(orchestrator
+            .add_flow(new_flow(&MemoryDestination::new()))
+            .await
+            .is_err());
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(matches!(
+            orchestrator.get_flow_status("lifecycle").await,
+            Some(FlowStatus::Running)
+        ));
+        assert_eq!(
+            orchestrator.get_flow_message_count("lifecycle").await,
+            Some(5)
+        );
+        assert!(destination.is_connected());
+
+        orchestrator.stop_flow("lifecycle").await.unwrap();
+        assert!(matches!(
+            orchestrator.get_flow_status("lifecycle").await,
+            Some(FlowStatus::Stopped)
+        ));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!destination.is_connected());
+        assert_eq!(destination.batch_sizes(), vec![5]);
+
+        orchestrator.remove_flow("lifecycle").await.unwrap();
+        assert!(orchestrator.list_flows().await.is_empty());
+        assert!(orchestrator.stop_flow("lifecycle").await.is_err());
+        orchestrator.wait_all().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_orchestrator_shutdown_flushes_flows() {
+        let orchestrator = FlowOrchestrator::new(Arc::new(Registry::new()));
+        let destination = MemoryDestination::new();
+        orchestrator
+            .add_flow(Flow::new(
+                "shutdown".to_string(),
+                Box::new(MemoryConnector::new(records(3))),
+                vec![Box::new(destination.clone())],
+                10,
+            ))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        // The buffered records are written before shutdown returns
+        assert_eq!(orchestrator.shutdown(Duration::from_secs(30)).await, 1);
+        assert_eq!(destination.records().len(), 3);
+        assert!(!destination.is_connected());
+        assert!(orchestrator.list_flows().await.is_empty());
+    }
 }
@@ -0,0 +1,316 @@
+use crate::DataRecord;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Why pushed records were not queued
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngestError {
+    /// No connector of the flow is accepting records on this instance
+    NotFound,
+    /// The token does not match the one the flow's connector requires
+    Unauthorized,
+    /// The queue has no room for the records right now
+    Full,
+    /// More records than the queue can hold at once
+    TooLarge { capacity: usize },
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "flow is not accepting records"),
+            Self::Unauthorized => write!(f, "invalid or missing ingest token"),
+            Self::Full => write!(f, "ingest queue is full"),
+            Self::TooLarge { capacity } => {
+                write!(
+                    f,
+                    "request exceeds the ingest queue capacity of {}",
+                    capacity
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+/// Records queued by `IngestHub::submit`
+#[derive(Debug)]
+pub struct Submission {
+    pub accepted: usize,
+    /// Resolves once the flow has written the records, when the connector acknowledges
+    /// them only then; it fails if they are dropped unwritten because the flow stopped
+    pub written: Option<oneshot::Receiver<()>>,
+    /// How long to wait on `written` before answering that the records are only queued
+    pub write_timeout: Duration,
+}
+
+/// A queued record, carrying the acknowledgement of its request when it is the last one
+type Queued = (DataRecord, Option<oneshot::Sender<()>>);
+
+struct IngestEndpoint {
+    sender: mpsc::Sender<Queued>,
+    auth_token: Option<String>,
+    write_timeout: Option<Duration>,
+}
+
+/// Hands records pushed to the API over to the connectors of running flows.
+///
+/// Connectors open a bounded queue under their flow's name while connected;
+/// the API submits records to it by flow name.
+#[derive(Clone, Default)]
+pub struct IngestHub {
+    endpoints: Arc<RwLock<HashMap<String, IngestEndpoint>>>,
+}
+
+impl IngestHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept records for `flow` until the returned queue is dropped, replacing any
+    /// queue already open for it. With a `write_timeout`, submissions wait up to that
+    /// long for `IngestQueue::acknowledge` instead of completing once queued
+    pub fn open(
+        &self,
+        flow: &str,
+        capacity: usize,
+        auth_token: Option<String>,
+        write_timeout: Option<Duration>,
+    ) -> IngestQueue {
+        let (sender, receiver) = mpsc::channel(capacity);
+        self.endpoints
+            .write()
+            .expect("ingest lock poisoned")
+            .insert(
+                flow.to_string(),
+                IngestEndpoint {
+                    sender: sender.clone(),
+                    auth_token,
+                    write_timeout,
+                },
+            );
+        IngestQueue {
+            flow: flow.to_string(),
+            hub: self.clone(),
+            sender,
+            receiver,
+            received: Vec::new(),
+        }
+    }
+
+    /// Queue all of `records` for `flow`, or none of them
+    pub fn submit(
+        &self,
+        flow: &str,
+        token: Option<&str>,
+        records: Vec<DataRecord>,
+    ) -> std::result::Result<Submission, IngestError> {
+        let (sender, write_timeout) = {
+            let endpoints = self.endpoints.read().expect("ingest lock poisoned");
+            let endpoint = endpoints.get(flow).ok_or(IngestError::NotFound)?;
+            if let Some(expected) = &endpoint.auth_token {
+                if !token.is_some_and(|token| tokens_match(token, expected)) {
+                    return Err(IngestError::Unauthorized);
+                }
+            }
+            (endpoint.sender.clone(), endpoint.write_timeout)
+        };
+
+        let count = records.len();
+        if count == 0 {
+            return Ok(Submission {
+                accepted: 0,
+                written: None,
+                write_timeout: Duration::ZERO,
+            });
+        }
+        if count > sender.max_capacity() {
+            return Err(IngestError::TooLarge {
+                capacity: sender.max_capacity(),
+            });
+        }
+
+        let permits = sender.try_reserve_many(count).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => IngestError::Full,
+            mpsc::error::TrySendError::Closed(_) => IngestError::NotFound,
+        })?;
+        let (mut ack, written) = if write_timeout.is_some() {
+            let (ack, written) = oneshot::channel();
+            (Some(ack), Some(written))
+        } else {
+            (None, None)
+        };
+        for (i, (permit, record)) in permits.zip(records).enumerate() {
+            // Records are received in order, so the last one stands for the request
+            let record_ack = if i + 1 == count { ack.take() } else { None };
+            permit.send((record, record_ack));
+        }
+        Ok(Submission {
+            accepted: count,
+            written,
+            write_timeout: write_timeout.unwrap_or_default(),
+        })
+    }
+
+    /// Whether a connector is accepting records for `flow`
+    pub fn is_open(&self, flow: &str) -> bool {
+        self.endpoints
+            .read()
+            .expect("ingest lock poisoned")
+            .contains_key(flow)
+    }
+}
+
+/// Compare tokens without exiting early on the first differing byte
+fn tokens_match(token: &str, expected: &str) -> bool {
+    token.len() == expected.len()
+        && token
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Records submitted for one flow, in order; stops accepting records when dropped,
+/// failing the submissions that were not acknowledged
+pub struct IngestQueue {
+    flow: String,
+    hub: IngestHub,
+    sender: mpsc::Sender<Queued>,
+    receiver: mpsc::Receiver<Queued>,
+    /// Acknowledgements of the requests received so far
+    received: Vec<oneshot::Sender<()>>,
+}
+
+impl IngestQueue {
+    /// Next submitted record; cancel-safe
+    pub async fn recv(&mut self) -> Option<DataRecord> {
+        let (record, ack) = self.receiver.recv().await?;
+        self.received.extend(ack);
+        Some(record)
+    }
+
+    /// Complete the submissions of every record received so far, once they are written
+    pub fn acknowledge(&mut self) {
+        for ack in self.received.drain(..) {
+            // The producer may have given up waiting
+            let _ = ack.send(());
+        }
+    }
+}
+
+impl Drop for IngestQueue {
+    fn drop(&mut self) {
+        let mut endpoints = self.hub.endpoints.write().expect("ingest lock poisoned");
+        // A newer queue may have replaced this one already
+        if endpoints
+            .get(&self.flow)
+            .is_some_and(|endpoint| endpoint.sender.same_channel(&self.sender))
+        {
+            endpoints.remove(&self.flow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_record;
+
+    fn records(count: u64) -> Vec<DataRecord> {
+        (0..count).map(|id| sample_record("users", id)).collect()
+    }
+
+    /// Number of records `submit` accepted
+    fn submit(
+        hub: &IngestHub,
+        flow: &str,
+        token: Option<&str>,
+        count: u64,
+    ) -> std::result::Result<usize, IngestError> {
+        hub.submit(flow, token, records(count))
+            .map(|submission| submission.accepted)
+    }
+
+    #[tokio::test]
+    async fn test_submit_queues_records_in_order() {
+        let hub = IngestHub::new();
+        let mut queue = hub.open("orders", 8, None, None);
+
+        assert_eq!(submit(&hub, "orders", None, 3), Ok(3));
+        for id in 0..3 {
+            let record = queue.recv().await.unwrap();
+            assert_eq!(record.record, sample_record("users", id).record);
+        }
+        assert_eq!(submit(&hub, "other", None, 1), Err(IngestError::NotFound));
+    }
+
+    #[test]
+    fn test_submit_is_all_or_nothing_when_full() {
+        let hub = IngestHub::new();
+        let _queue = hub.open("orders", 4, None, None);
+
+        assert_eq!(submit(&hub, "orders", None, 3), Ok(3));
+        assert_eq!(submit(&hub, "orders", None, 2), Err(IngestError::Full));
+        assert_eq!(submit(&hub, "orders", None, 1), Ok(1));
+        assert_eq!(
+            submit(&hub, "orders", None, 5),
+            Err(IngestError::TooLarge { capacity: 4 })
+        );
+    }
+
+    #[test]
+    fn test_submit_checks_token() {
+        let hub = IngestHub::new();
+        let _queue = hub.open("orders", 4, Some("secret".to_string()), None);
+
+        assert_eq!(
+            submit(&hub, "orders", None, 1),
+            Err(IngestError::Unauthorized)
+        );
+        assert_eq!(
+            submit(&hub, "orders", Some("secreT"), 1),
+            Err(IngestError::Unauthorized)
+        );
+        assert_eq!(submit(&hub, "orders", Some("secret"), 1), Ok(1));
+    }
+
+    #[test]
+    fn test_dropping_queue_closes_only_its_endpoint() {
+        let hub = IngestHub::new();
+        let old = hub.open("orders", 4, None, None);
+        let new = hub.open("orders", 4, None, None);
+
+        drop(old);
+        assert!(hub.is_open("orders"));
+        drop(new);
+        assert!(!hub.is_open("orders"));
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_completes_received_submissions() {
+        let hub = IngestHub::new();
+        let mut queue = hub.open("orders", 8, None, Some(Duration::from_secs(1)));
+
+        let first = hub.submit("orders", None, records(2)).unwrap();
+        let second = hub.submit("orders", None, records(1)).unwrap();
+        let mut first = first.written.unwrap();
+        let second = second.written.unwrap();
+
+        // Only requests whose last record was received are acknowledged
+        queue.recv().await.unwrap();
+        queue.acknowledge();
+        assert!(first.try_recv().is_err());
+        queue.recv().await.unwrap();
+        queue.acknowledge();
+        assert_eq!(first.await, Ok(()));
+
+        // Records dropped with the queue fail their submission
+        drop(queue);
+        assert!(second.await.is_err());
+    }
+}
@@ -4,19 +4,34 @@ mod destination;
 mod error;
 mod factory;
 mod flow;
+mod ingest;
 mod notification;
 mod pipeline;
+mod plugin;
 mod registry;
+mod secrets;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use connector::{Connector, ConnectorCleanup, ConnectorStatus};
 pub use data_record::{DataRecord, Operation};
 pub use destination::{Destination, DestinationStatus};
 pub use error::{Error, Result};
-pub use factory::{ConnectorFactory, DestinationFactory};
+pub use factory::{ConnectorFactory, DestinationFactory, SECRET_SCHEMA_KEYWORD};
 pub use flow::{
     ConnectorConfig, DestinationConfig, Flow, FlowBuilder, FlowCommand, FlowConfig, FlowHandle,
     FlowOrchestrator, FlowStatus,
 };
+pub use ingest::{IngestError, IngestHub, IngestQueue, Submission};
 pub use notification::{EmailNotifier, NoOpNotifier, Notifier};
 pub use pipeline::{Pipeline, PipelineStatus};
+pub use plugin::{
+    PluginDeclaration, PluginInfo, PluginRegistrar, PluginSource, CORE_VERSION,
+    PLUGIN_ABI_VERSION, PLUGIN_DECLARATION_SYMBOL, RUSTC_VERSION,
+};
 pub use registry::Registry;
+pub use secrets::{
+    contains_masked, is_masked_copy, is_secret_field, is_secret_ref, redact_config, redact_url,
+    resolve_secret_refs, resolve_secret_refs_with, restore_masked, secret_fields, SecretRefPolicy,
+    MASKED_VALUE, SECRETS_DIR_ENV, SECRET_ENV_PREFIXES_ENV,
+};
@@ -0,0 +1,154 @@
+use crate::{ConnectorFactory, DestinationFactory, Error, Result};
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Version of the plugin ABI.
+///
+/// Bump this whenever `PluginDeclaration`, `PluginRegistrar` or any trait
+/// reachable from a plugin (factories, connectors, destinations) changes shape.
+pub const PLUGIN_ABI_VERSION: u32 = 6;
+
+/// Version of `cdc-core` the current binary (or plugin) was compiled against
+pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Version of the compiler the current binary (or plugin) was compiled with
+pub const RUSTC_VERSION: &str = env!("CDC_RUSTC_VERSION");
+
+/// Name of the symbol every plugin library must export (see `export_plugin!`)
+pub const PLUGIN_DECLARATION_SYMBOL: &[u8] = b"CDC_PLUGIN_DECLARATION\0";
+
+/// Declaration exported by a plugin library.
+///
+/// `abi_version` is the first field of a `repr(C)` struct so it can always be
+/// read safely; the remaining fields are only trusted once it matches.
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub rustc_version: &'static str,
+    pub core_version: &'static str,
+    pub register: fn(&mut dyn PluginRegistrar),
+}
+
+impl PluginDeclaration {
+    /// Check that the plugin was built against a compatible ABI, compiler and core version
+    pub fn check_compatibility(&self) -> Result<()> {
+        if self.abi_version != PLUGIN_ABI_VERSION {
+            return Err(Error::Plugin(format!(
+                "ABI version mismatch: plugin has {}, host expects {}",
+                self.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        if self.rustc_version != RUSTC_VERSION {
+            return Err(Error::Plugin(format!(
+                "Compiler mismatch: plugin built with '{}', host built with '{}'",
+                self.rustc_version, RUSTC_VERSION
+            )));
+        }
+
+        if self.core_version != CORE_VERSION {
+            return Err(Error::Plugin(format!(
+                "cdc-core version mismatch: plugin built against {}, host uses {}",
+                self.core_version, CORE_VERSION
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Sink a plugin registers its factories into
+pub trait PluginRegistrar {
+    /// Register a connector factory provided by the plugin
+    fn register_connector(&mut self, factory: Arc<dyn ConnectorFactory>);
+
+    /// Register a destination factory provided by the plugin
+    fn register_destination(&mut self, factory: Arc<dyn DestinationFactory>);
+}
+
+/// Where a registered factory came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "path", rename_all = "lowercase")]
+pub enum PluginSource {
+    /// Compiled into the binary and registered at startup
+    Builtin,
+    /// Loaded from a shared library at the given path
+    Dynamic(PathBuf),
+}
+
+impl fmt::Display for PluginSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Builtin => write!(f, "the builtin plugins"),
+            Self::Dynamic(path) => write!(f, "plugin '{}'", path.display()),
+        }
+    }
+}
+
+/// A registered connector or destination type
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub source: PluginSource,
+}
+
+/// Export a plugin declaration from a `cdylib` crate.
+///
+/// ```ignore
+/// fn register(registrar: &mut dyn cdc_core::PluginRegistrar) {
+///     registrar.register_connector(std::sync::Arc::new(MyConnectorFactory));
+/// }
+///
+/// cdc_core::export_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($register:expr) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        pub static CDC_PLUGIN_DECLARATION: $crate::PluginDeclaration = $crate::PluginDeclaration {
+            abi_version: $crate::PLUGIN_ABI_VERSION,
+            rustc_version: $crate::RUSTC_VERSION,
+            core_version: $crate::CORE_VERSION,
+            register: $register,
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noop_register(_registrar: &mut dyn PluginRegistrar) {}
+
+    fn declaration() -> PluginDeclaration {
+        PluginDeclaration {
+            abi_version: PLUGIN_ABI_VERSION,
+            rustc_version: RUSTC_VERSION,
+            core_version: CORE_VERSION,
+            register: noop_register,
+        }
+    }
+
+    #[test]
+    fn test_compatible_declaration_is_accepted() {
+        assert!(declaration().check_compatibility().is_ok());
+    }
+
+    #[test]
+    fn test_incompatible_declarations_are_rejected() {
+        let mut decl = declaration();
+        decl.abi_version = PLUGIN_ABI_VERSION + 1;
+        assert!(decl.check_compatibility().is_err());
+
+        let mut decl = declaration();
+        decl.rustc_version = "rustc 0.0.0";
+        assert!(decl.check_compatibility().is_err());
+
+        let mut decl = declaration();
+        decl.core_version = "0.0.0";
+        assert!(decl.check_compatibility().is_err());
+    }
+}
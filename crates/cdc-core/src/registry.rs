@@ -1,11 +1,22 @@
-use crate::{ConnectorFactory, DestinationFactory, Error, Result};
-use std::collections::HashMap;
+use crate::plugin::{PluginDeclaration, PluginInfo, PluginRegistrar, PluginSource};
+use crate::secrets::{redact_config, resolve_secret_refs, secret_fields};
+use crate::{
+    Connector, ConnectorFactory, Destination, DestinationFactory, Error, Result,
+    PLUGIN_DECLARATION_SYMBOL,
+};
+use libloading::Library;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
+use tracing::{info, warn};
 
 /// Registry for connector and destination factories
 pub struct Registry {
-    connector_factories: HashMap<String, Arc<dyn ConnectorFactory>>,
-    destination_factories: HashMap<String, Arc<dyn DestinationFactory>>,
+    connector_factories: HashMap<String, (Arc<dyn ConnectorFactory>, PluginSource)>,
+    destination_factories: HashMap<String, (Arc<dyn DestinationFactory>, PluginSource)>,
+    // Must be declared after the factories so the libraries outlive them on drop
+    libraries: Vec<Library>,
 }
 
 impl Registry {
@@ -13,45 +24,241 @@ impl Registry {
         Self {
             connector_factories: HashMap::new(),
             destination_factories: HashMap::new(),
+            libraries: Vec::new(),
         }
     }
-    
-    /// Register a connector factory
+
+    /// Register a connector factory; a type that is already registered is kept
     pub fn register_connector(&mut self, factory: Arc<dyn ConnectorFactory>) {
         let name = factory.name().to_string();
-        self.connector_factories.insert(name, factory);
+        if self.connector_factories.contains_key(&name) {
+            warn!("Connector '{}' is already registered, ignoring the new one", name);
+            return;
+        }
+        self.connector_factories
+            .insert(name, (factory, PluginSource::Builtin));
     }
-    
-    /// Register a destination factory
+
+    /// Register a destination factory; a type that is already registered is kept
     pub fn register_destination(&mut self, factory: Arc<dyn DestinationFactory>) {
         let name = factory.name().to_string();
-        self.destination_factories.insert(name, factory);
+        if self.destination_factories.contains_key(&name) {
+            warn!("Destination '{}' is already registered, ignoring the new one", name);
+            return;
+        }
+        self.destination_factories
+            .insert(name, (factory, PluginSource::Builtin));
+    }
+
+    /// Register everything a plugin provides, or nothing if any of its types is already
+    /// registered, so a plugin cannot take over the configs (and credentials) of another
+    fn register_plugin(
+        &mut self,
+        registration: PluginRegistration,
+        source: PluginSource,
+    ) -> Result<()> {
+        let mut connectors = HashSet::new();
+        for factory in &registration.connectors {
+            let name = factory.name();
+            if let Some((_, existing)) = self.connector_factories.get(name) {
+                return Err(Error::Plugin(format!(
+                    "Connector '{}' of {} is already provided by {}",
+                    name, source, existing
+                )));
+            }
+            if !connectors.insert(name) {
+                return Err(Error::Plugin(format!(
+                    "Connector '{}' is registered twice by {}",
+                    name, source
+                )));
+            }
+        }
+        let mut destinations = HashSet::new();
+        for factory in &registration.destinations {
+            let name = factory.name();
+            if let Some((_, existing)) = self.destination_factories.get(name) {
+                return Err(Error::Plugin(format!(
+                    "Destination '{}' of {} is already provided by {}",
+                    name, source, existing
+                )));
+            }
+            if !destinations.insert(name) {
+                return Err(Error::Plugin(format!(
+                    "Destination '{}' is registered twice by {}",
+                    name, source
+                )));
+            }
+        }
+
+        for factory in registration.connectors {
+            info!("Plugin registered connector: {}", factory.name());
+            self.connector_factories
+                .insert(factory.name().to_string(), (factory, source.clone()));
+        }
+        for factory in registration.destinations {
+            info!("Plugin registered destination: {}", factory.name());
+            self.destination_factories
+                .insert(factory.name().to_string(), (factory, source.clone()));
+        }
+        Ok(())
     }
-    
+
     /// Get a connector factory by name
     pub fn get_connector_factory(&self, name: &str) -> Result<Arc<dyn ConnectorFactory>> {
         self.connector_factories
             .get(name)
-            .cloned()
+            .map(|(factory, _)| factory.clone())
             .ok_or_else(|| Error::Configuration(format!("Connector factory '{}' not found", name)))
     }
-    
+
     /// Get a destination factory by name
     pub fn get_destination_factory(&self, name: &str) -> Result<Arc<dyn DestinationFactory>> {
         self.destination_factories
             .get(name)
-            .cloned()
+            .map(|(factory, _)| factory.clone())
             .ok_or_else(|| Error::Configuration(format!("Destination factory '{}' not found", name)))
     }
-    
-    /// List all registered connector types
-    pub fn list_connectors(&self) -> Vec<String> {
-        self.connector_factories.keys().cloned().collect()
+
+    /// Create a connector, resolving `${env:..}` / `${file:..}` references in its secret
+    /// fields first
+    pub fn create_connector(&self, connector_type: &str, config: &Value) -> Result<Box<dyn Connector>> {
+        let factory = self.get_connector_factory(connector_type)?;
+        let secrets = secret_fields(&factory.config_schema());
+        factory.create(resolve_secret_refs(config, &secrets)?)
+    }
+
+    /// Create a destination, resolving `${env:..}` / `${file:..}` references in its
+    /// secret fields first
+    pub fn create_destination(
+        &self,
+        destination_type: &str,
+        config: &Value,
+    ) -> Result<Box<dyn Destination>> {
+        let factory = self.get_destination_factory(destination_type)?;
+        let secrets = secret_fields(&factory.config_schema());
+        factory.create(resolve_secret_refs(config, &secrets)?)
+    }
+
+    /// Mask the secrets of a connector config for display
+    pub fn redact_connector_config(&self, connector_type: &str, config: &Value) -> Value {
+        redact_config(config, &self.connector_secret_fields(connector_type))
+    }
+
+    /// Mask the secrets of a destination config for display
+    pub fn redact_destination_config(&self, destination_type: &str, config: &Value) -> Value {
+        redact_config(config, &self.destination_secret_fields(destination_type))
+    }
+
+    /// Names of the secret fields in a connector type's config schema
+    pub fn connector_secret_fields(&self, connector_type: &str) -> HashSet<String> {
+        self.get_connector_factory(connector_type)
+            .map(|factory| secret_fields(&factory.config_schema()))
+            .unwrap_or_default()
+    }
+
+    /// Names of the secret fields in a destination type's config schema
+    pub fn destination_secret_fields(&self, destination_type: &str) -> HashSet<String> {
+        self.get_destination_factory(destination_type)
+            .map(|factory| secret_fields(&factory.config_schema()))
+            .unwrap_or_default()
+    }
+
+    /// List all registered connector types and where they were loaded from
+    pub fn list_connectors(&self) -> Vec<PluginInfo> {
+        let mut list: Vec<_> = self
+            .connector_factories
+            .iter()
+            .map(|(name, (_, source))| PluginInfo {
+                name: name.clone(),
+                source: source.clone(),
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
+    /// List all registered destination types and where they were loaded from
+    pub fn list_destinations(&self) -> Vec<PluginInfo> {
+        let mut list: Vec<_> = self
+            .destination_factories
+            .iter()
+            .map(|(name, (_, source))| PluginInfo {
+                name: name.clone(),
+                source: source.clone(),
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
+    /// Load a plugin from a shared library and register the factories it provides.
+    ///
+    /// The library is rejected before its registration function is called if its
+    /// declared ABI, compiler or `cdc-core` version does not match the host, and
+    /// registers nothing if it provides a type that is already registered. Loading
+    /// it still runs its static initialisers, so only trusted libraries belong in the
+    /// plugin directory.
+    pub fn load_plugin(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        // SAFETY: loading a library runs its initialisers; plugins are trusted
+        // code placed in the plugin directory by the operator.
+        let library = unsafe { Library::new(path) }.map_err(|e| {
+            Error::Plugin(format!("Failed to load '{}': {}", path.display(), e))
+        })?;
+
+        let declaration: &PluginDeclaration = unsafe {
+            let symbol = library
+                .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+                .map_err(|e| {
+                    Error::Plugin(format!(
+                        "'{}' is not a CDC plugin (missing declaration): {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+            &**symbol
+        };
+
+        declaration.check_compatibility().map_err(|e| {
+            Error::Plugin(format!("Rejected plugin '{}': {}", path.display(), e))
+        })?;
+
+        let mut registration = PluginRegistration::default();
+        (declaration.register)(&mut registration);
+        // On error the factories are dropped here, before the library whose code they use
+        self.register_plugin(registration, PluginSource::Dynamic(path.to_path_buf()))?;
+
+        self.libraries.push(library);
+        info!("Loaded plugin: {}", path.display());
+        Ok(())
     }
-    
-    /// List all registered destination types
-    pub fn list_destinations(&self) -> Vec<String> {
-        self.destination_factories.keys().cloned().collect()
+
+    /// Load every shared library found in `dir`.
+    ///
+    /// Plugins that fail to load or are incompatible are logged and skipped.
+    /// Returns the number of plugins loaded.
+    pub fn load_plugin_dir(&mut self, dir: impl AsRef<Path>) -> Result<usize> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().and_then(|ext| ext.to_str())
+                        == Some(std::env::consts::DLL_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            match self.load_plugin(&path) {
+                Ok(_) => loaded += 1,
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        Ok(loaded)
     }
 }
 
@@ -60,3 +267,127 @@ impl Default for Registry {
         Self::new()
     }
 }
+
+/// Registrar handed to plugins, collecting what they register so it can be checked
+/// before anything is added to the registry
+#[derive(Default)]
+struct PluginRegistration {
+    connectors: Vec<Arc<dyn ConnectorFactory>>,
+    destinations: Vec<Arc<dyn DestinationFactory>>,
+}
+
+impl PluginRegistrar for PluginRegistration {
+    fn register_connector(&mut self, factory: Arc<dyn ConnectorFactory>) {
+        self.connectors.push(factory);
+    }
+
+    fn register_destination(&mut self, factory: Arc<dyn DestinationFactory>) {
+        self.destinations.push(factory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MemoryConnector, MemoryDestination};
+
+    struct NamedConnector(&'static str);
+
+    impl ConnectorFactory for NamedConnector {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn create(&self, _config: Value) -> Result<Box<dyn Connector>> {
+            Ok(Box::new(MemoryConnector::new(Vec::new())))
+        }
+    }
+
+    struct NamedDestination(&'static str);
+
+    impl DestinationFactory for NamedDestination {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn create(&self, _config: Value) -> Result<Box<dyn Destination>> {
+            Ok(Box::new(MemoryDestination::new()))
+        }
+    }
+
+    #[test]
+    fn test_plugin_cannot_replace_registered_types() {
+        let mut registry = Registry::new();
+        registry.register_connector(Arc::new(NamedConnector("kafka")));
+        registry.register_destination(Arc::new(NamedDestination("postgres")));
+        let plugin = PluginSource::Dynamic("/plugins/libevil.so".into());
+
+        // A clashing plugin registers none of its types, not even the new ones
+        let registration = PluginRegistration {
+            connectors: vec![Arc::new(NamedConnector("webhook"))],
+            destinations: vec![Arc::new(NamedDestination("postgres"))],
+        };
+        let error = registry
+            .register_plugin(registration, plugin.clone())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'postgres'"));
+        assert!(error.contains("/plugins/libevil.so"));
+        assert!(error.contains("builtin"));
+        assert!(registry.get_connector_factory("webhook").is_err());
+        assert_eq!(
+            registry.list_destinations()[0].source,
+            PluginSource::Builtin
+        );
+
+        let registration = PluginRegistration {
+            connectors: vec![
+                Arc::new(NamedConnector("webhook")),
+                Arc::new(NamedConnector("webhook")),
+            ],
+            destinations: Vec::new(),
+        };
+        assert!(registry.register_plugin(registration, plugin.clone()).is_err());
+
+        let registration = PluginRegistration {
+            connectors: vec![Arc::new(NamedConnector("webhook"))],
+            destinations: Vec::new(),
+        };
+        registry.register_plugin(registration, plugin.clone()).unwrap();
+        let other = PluginSource::Dynamic("/plugins/libother.so".into());
+        let registration = PluginRegistration {
+            connectors: vec![Arc::new(NamedConnector("webhook"))],
+            destinations: Vec::new(),
+        };
+        let error = registry
+            .register_plugin(registration, other)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("libother.so") && error.contains("libevil.so"));
+
+        // Builtins keep the first registration too
+        registry.register_connector(Arc::new(NamedConnector("webhook")));
+        let webhook = registry
+            .list_connectors()
+            .into_iter()
+            .find(|info| info.name == "webhook")
+            .unwrap();
+        assert_eq!(webhook.source, plugin);
+    }
+
+    #[test]
+    fn test_load_plugin_rejects_non_library() {
+        let path = std::env::temp_dir().join(format!(
+            "cdc-not-a-plugin-{}.{}",
+            std::process::id(),
+            std::env::consts::DLL_EXTENSION
+        ));
+        std::fs::write(&path, b"not a shared library").unwrap();
+
+        let mut registry = Registry::new();
+        assert!(matches!(registry.load_plugin(&path), Err(Error::Plugin(_))));
+        assert!(registry.list_connectors().is_empty());
+
+        std::fs::remove_file(path).ok();
+    }
+}
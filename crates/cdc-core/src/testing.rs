@@ -0,0 +1,213 @@
+//! In-memory connector and destination for exercising flows without external services.
+//!
+//! Enabled with the `testing` feature (always available to this crate's own tests).
+
+use crate::{
+    Connector, ConnectorStatus, DataRecord, Destination, DestinationStatus, Error, Result,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Build a record for `table` with an `id` field, as PeerDB would publish it
+pub fn sample_record(table: &str, id: u64) -> DataRecord {
+    DataRecord::new(
+        serde_json::json!({ "id": id }).to_string(),
+        serde_json::json!({ "table_name": table, "database_name": "test" }).to_string(),
+        "insert".to_string(),
+        None,
+    )
+}
+
+/// Connector that yields records from a list or a channel.
+///
+/// Once every record has been received it waits like an idle live source
+/// instead of ending the stream.
+pub struct MemoryConnector {
+    rx: mpsc::UnboundedReceiver<DataRecord>,
+    connected: bool,
+    records_received: u64,
+}
+
+impl MemoryConnector {
+    /// Connector that yields `records` in order
+    pub fn new(records: Vec<DataRecord>) -> Self {
+        let (tx, connector) = Self::channel();
+        for record in records {
+            tx.send(record).ok();
+        }
+        connector
+    }
+
+    /// Connector fed by sending records on the returned channel
+    pub fn channel() -> (mpsc::UnboundedSender<DataRecord>, Self) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let connector = Self {
+            rx,
+            connected: false,
+            records_received: 0,
+        };
+        (tx, connector)
+    }
+}
+
+#[async_trait]
+impl Connector for MemoryConnector {
+    async fn connect(&mut self) -> Result<()> {
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    async fn receive(&mut self) -> Result<Option<DataRecord>> {
+        if !self.connected {
+            return Err(Error::Connection(
+                "Memory connector not connected".to_string(),
+            ));
+        }
+
+        match self.rx.recv().await {
+            Some(record) => {
+                self.records_received += 1;
+                Ok(Some(record))
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    fn status(&self) -> ConnectorStatus {
+        ConnectorStatus {
+            connected: self.connected,
+            records_received: self.records_received,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Default)]
+struct MemoryDestinationState {
+    connected: bool,
+    batches: Vec<Vec<DataRecord>>,
+    write_attempts: u64,
+    failing: bool,
+    fail_next: u64,
+    errors: u64,
+    consecutive_errors: u64,
+}
+
+/// Destination that keeps every batch written to it, with injectable failures.
+///
+/// Clones share state, so keep a clone to inspect writes after handing the
+/// destination to a flow.
+#[derive(Clone, Default)]
+pub struct MemoryDestination {
+    state: Arc<Mutex<MemoryDestinationState>>,
+}
+
+impl MemoryDestination {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail the next `count` writes, then succeed again
+    pub fn fail_next(&self, count: u64) {
+        self.state.lock().unwrap().fail_next = count;
+    }
+
+    /// Fail every write until called again with `false`
+    pub fn set_failing(&self, failing: bool) {
+        self.state.lock().unwrap().failing = failing;
+    }
+
+    /// Successfully written batches, in order
+    pub fn batches(&self) -> Vec<Vec<DataRecord>> {
+        self.state.lock().unwrap().batches.clone()
+    }
+
+    /// Sizes of the successfully written batches, in order
+    pub fn batch_sizes(&self) -> Vec<usize> {
+        self.state
+            .lock()
+            .unwrap()
+            .batches
+            .iter()
+            .map(Vec::len)
+            .collect()
+    }
+
+    /// All successfully written records, in order
+    pub fn records(&self) -> Vec<DataRecord> {
+        self.batches().into_iter().flatten().collect()
+    }
+
+    /// Writes attempted, including failed ones
+    pub fn write_attempts(&self) -> u64 {
+        self.state.lock().unwrap().write_attempts
+    }
+}
+
+#[async_trait]
+impl Destination for MemoryDestination {
+    async fn connect(&mut self) -> Result<()> {
+        self.state.lock().unwrap().connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.state.lock().unwrap().connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.state.lock().unwrap().connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.connected {
+            return Err(Error::Connection(
+                "Memory destination not connected".to_string(),
+            ));
+        }
+        state.write_attempts += 1;
+
+        let fail = if state.fail_next > 0 {
+            state.fail_next -= 1;
+            true
+        } else {
+            state.failing
+        };
+        if fail {
+            state.errors += 1;
+            state.consecutive_errors += 1;
+            return Err(Error::Connection("Injected write failure".to_string()));
+        }
+
+        state.consecutive_errors = 0;
+        state.batches.push(records);
+        Ok(())
+    }
+
+    fn status(&self) -> DestinationStatus {
+        let state = self.state.lock().unwrap();
+        DestinationStatus {
+            connected: state.connected,
+            records_written: state.batches.iter().map(|b| b.len() as u64).sum(),
+            errors: state.errors,
+            consecutive_errors: state.consecutive_errors,
+            last_error: None,
+        }
+    }
+}
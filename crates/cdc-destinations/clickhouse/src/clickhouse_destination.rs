@@ -0,0 +1,445 @@
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Column holding 1 for deleted rows; ReplacingMergeTree drops them when merging
+pub const DELETED_COLUMN: &str = "_is_deleted";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClickHouseConfig {
+    /// ClickHouse HTTP interface URL
+    #[serde(default = "default_url")]
+    #[schemars(extend("x-secret" = true))]
+    pub url: String,
+
+    /// Target database name
+    #[serde(default = "default_database")]
+    pub database: String,
+
+    /// ClickHouse user
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// ClickHouse password
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub password: Option<String>,
+
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    pub auto_create_tables: bool,
+
+    /// Columns identifying a row; the sorting key of created tables
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// Column holding the row version (record timestamp in microseconds)
+    #[serde(default = "default_version_column")]
+    pub version_column: String,
+
+    /// HTTP request timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_url() -> String {
+    "http://localhost:8123".to_string()
+}
+
+fn default_database() -> String {
+    "default".to_string()
+}
+
+fn default_auto_create_tables() -> bool {
+    true
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+fn default_version_column() -> String {
+    "_version".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for ClickHouseConfig {
+    fn default() -> Self {
+        Self {
+            url: default_url(),
+            database: default_database(),
+            username: None,
+            password: None,
+            auto_create_tables: true,
+            primary_key: default_primary_key(),
+            version_column: default_version_column(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+pub struct ClickHouseDestination {
+    config: ClickHouseConfig,
+    client: Option<reqwest::Client>,
+    /// Tables created (or found) since connecting
+    ensured_tables: HashSet<String>,
+    status: DestinationStatus,
+}
+
+impl ClickHouseDestination {
+    pub fn new(config: ClickHouseConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            ensured_tables: HashSet::new(),
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Quote identifier with backticks, escaping backslashes and backticks
+    fn quote_identifier(identifier: &str) -> String {
+        format!(
+            "`{}`",
+            identifier.replace('\\', "\\\\").replace('`', "\\`")
+        )
+    }
+
+    fn qualified_table(&self, table: &str) -> String {
+        format!(
+            "{}.{}",
+            Self::quote_identifier(&self.config.database),
+            Self::quote_identifier(table)
+        )
+    }
+
+    /// Infer ClickHouse type from JSON value
+    fn infer_clickhouse_type(value: &Value) -> &'static str {
+        match value {
+            Value::Bool(_) => "Bool",
+            Value::Number(n) if n.is_i64() => "Int64",
+            Value::Number(n) if n.is_u64() => "UInt64",
+            Value::Number(_) => "Float64",
+            // Nested values are sent as JSON text
+            _ => "String",
+        }
+    }
+
+    /// Row for `FORMAT JSONEachRow`, with version and delete flag
+    fn build_row(&self, record: &DataRecord) -> Result<Map<String, Value>> {
+        let mut row: Map<String, Value> = serde_json::from_str(&record.record)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        if operation == Operation::Update {
+            if let Ok(Some(changes)) = record.parse_changes() {
+                row.extend(changes);
+            }
+        }
+
+        for key in &self.config.primary_key {
+            if row.get(key).is_none_or(Value::is_null) {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Record has no value for primary key column '{}'",
+                    key
+                )));
+            }
+        }
+
+        for value in row.values_mut() {
+            if value.is_object() || value.is_array() {
+                *value = Value::String(value.to_string());
+            }
+        }
+
+        row.insert(
+            self.config.version_column.clone(),
+            Value::from(record.timestamp.timestamp_micros()),
+        );
+        row.insert(
+            DELETED_COLUMN.to_string(),
+            Value::from(u8::from(operation == Operation::Delete)),
+        );
+        Ok(row)
+    }
+
+    /// CREATE TABLE for `rows`, typing each column from its first non-null value
+    fn create_table_query(&self, table: &str, rows: &[Map<String, Value>]) -> String {
+        let mut columns: Vec<(&String, Option<&Value>)> = Vec::new();
+        let mut index: HashMap<&String, usize> = HashMap::new();
+        for row in rows {
+            for (name, value) in row {
+                if name == &self.config.version_column || name == DELETED_COLUMN {
+                    continue;
+                }
+                let value = Some(value).filter(|v| !v.is_null());
+                match index.get(name) {
+                    Some(&i) => {
+                        if columns[i].1.is_none() {
+                            columns[i].1 = value;
+                        }
+                    }
+                    None => {
+                        index.insert(name, columns.len());
+                        columns.push((name, value));
+                    }
+                }
+            }
+        }
+
+        let mut column_defs: Vec<String> = columns
+            .into_iter()
+            .map(|(name, value)| {
+                let col_type = value.map_or("String", Self::infer_clickhouse_type);
+                if self.config.primary_key.contains(name) {
+                    format!("{} {}", Self::quote_identifier(name), col_type)
+                } else {
+                    format!("{} Nullable({})", Self::quote_identifier(name), col_type)
+                }
+            })
+            .collect();
+        let version = Self::quote_identifier(&self.config.version_column);
+        let deleted = Self::quote_identifier(DELETED_COLUMN);
+        column_defs.push(format!("{} UInt64", version));
+        column_defs.push(format!("{} UInt8", deleted));
+
+        let order_by: Vec<String> = self
+            .config
+            .primary_key
+            .iter()
+            .map(|key| Self::quote_identifier(key))
+            .collect();
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({}) ENGINE = ReplacingMergeTree({}, {}) ORDER BY ({})",
+            self.qualified_table(table),
+            column_defs.join(", "),
+            version,
+            deleted,
+            order_by.join(", ")
+        )
+    }
+
+    /// Run `query` over HTTP, sending `data` as the request body after it
+    async fn execute(&self, query: &str, data: Option<String>) -> Result<()> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        let mut request = client
+            .post(&self.config.url)
+            .query(&[("database", &self.config.database)]);
+        request = match data {
+            Some(data) => request.query(&[("query", query)]).body(data),
+            None => request.body(query.to_string()),
+        };
+        if let Some(username) = &self.config.username {
+            request = request.basic_auth(username, self.config.password.as_ref());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Connection(format!("ClickHouse request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Generic(anyhow::anyhow!(
+                "ClickHouse returned {}: {}",
+                status,
+                body.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn write_records(&mut self, records: &[DataRecord]) -> Result<()> {
+        // Build every row before sending so a bad record fails the batch up front
+        let mut tables: Vec<(String, Vec<Map<String, Value>>)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for record in records {
+            let table = record
+                .table_name()
+                .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+            let row = self.build_row(record)?;
+            match index.get(&table) {
+                Some(&i) => tables[i].1.push(row),
+                None => {
+                    index.insert(table.clone(), tables.len());
+                    tables.push((table, vec![row]));
+                }
+            }
+        }
+
+        for (table, rows) in &tables {
+            if self.config.auto_create_tables && !self.ensured_tables.contains(table) {
+                let query = self.create_table_query(table, rows);
+                info!("Ensuring table: {}", query);
+                self.execute(&query, None).await?;
+                self.ensured_tables.insert(table.clone());
+            }
+
+            let body = rows
+                .iter()
+                .map(|row| Value::Object(row.clone()).to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let query = format!(
+                "INSERT INTO {} FORMAT JSONEachRow",
+                self.qualified_table(table)
+            );
+            debug!("Inserting {} row(s) into {}", rows.len(), table);
+            self.execute(&query, Some(body)).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Destination for ClickHouseDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!("Connecting to ClickHouse: {}", redact_url(&self.config.url));
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .build()
+            .map_err(|e| Error::Connection(format!("Failed to create HTTP client: {}", e)))?;
+        self.client = Some(client);
+
+        if let Err(e) = self.execute("SELECT 1", None).await {
+            self.client = None;
+            return Err(Error::Connection(format!(
+                "Failed to connect to ClickHouse: {}",
+                e
+            )));
+        }
+
+        info!("Connected to ClickHouse successfully");
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from ClickHouse");
+        self.client = None;
+        self.ensured_tables.clear();
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.client.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        match self.write_records(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+
+    fn destination() -> ClickHouseDestination {
+        ClickHouseDestination::new(ClickHouseConfig::default())
+    }
+
+    #[test]
+    fn test_build_row_marks_deletes_and_versions() {
+        let destination = destination();
+        let mut record = sample_record("users", 7);
+
+        let row = destination.build_row(&record).unwrap();
+        assert_eq!(row["id"], 7);
+        assert_eq!(row[DELETED_COLUMN], 0);
+        assert_eq!(row["_version"], record.timestamp.timestamp_micros());
+
+        record.action = "delete".to_string();
+        let row = destination.build_row(&record).unwrap();
+        assert_eq!(row[DELETED_COLUMN], 1);
+    }
+
+    #[test]
+    fn test_build_row_merges_changes_and_stringifies_nested_values() {
+        let destination = destination();
+        let record = DataRecord::new(
+            serde_json::json!({ "id": 1, "name": "a", "tags": ["x"] }).to_string(),
+            serde_json::json!({ "table_name": "users" }).to_string(),
+            "update".to_string(),
+            Some(serde_json::json!({ "name": "b" }).to_string()),
+        );
+
+        let row = destination.build_row(&record).unwrap();
+        assert_eq!(row["name"], "b");
+        assert_eq!(row["tags"], "[\"x\"]");
+    }
+
+    #[test]
+    fn test_build_row_requires_primary_key() {
+        let destination = destination();
+        let record = DataRecord::new(
+            serde_json::json!({ "name": "a" }).to_string(),
+            serde_json::json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+
+        assert!(destination.build_row(&record).is_err());
+    }
+
+    #[test]
+    fn test_create_table_query() {
+        let destination = destination();
+        let record = DataRecord::new(
+            serde_json::json!({ "id": 1, "score": 1.5, "note": null }).to_string(),
+            serde_json::json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        let rows = vec![destination.build_row(&record).unwrap()];
+
+        assert_eq!(
+            destination.create_table_query("users", &rows),
+            "CREATE TABLE IF NOT EXISTS `default`.`users` (`id` Int64, \
+             `score` Nullable(Float64), `note` Nullable(String), `_version` UInt64, \
+             `_is_deleted` UInt8) ENGINE = ReplacingMergeTree(`_version`, `_is_deleted`) \
+             ORDER BY (`id`)"
+        );
+    }
+}
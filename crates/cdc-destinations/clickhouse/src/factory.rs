@@ -0,0 +1,57 @@
+use crate::{ClickHouseConfig, ClickHouseDestination};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct ClickHouseDestinationFactory;
+
+impl DestinationFactory for ClickHouseDestinationFactory {
+    fn name(&self) -> &str {
+        "clickhouse"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: ClickHouseConfig = serde_json::from_value(config)?;
+        Ok(Box::new(ClickHouseDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(ClickHouseConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = ClickHouseDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["password"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["url"]["default"], "http://localhost:8123");
+        assert_eq!(properties["database"]["default"], "default");
+        assert_eq!(properties["primary_key"]["default"], serde_json::json!(["id"]));
+    }
+
+    struct ClickHouseHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for ClickHouseHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            ClickHouseDestinationFactory.create(serde_json::json!({ "url": self.url }))
+        }
+    }
+
+    /// `CONFORMANCE_CLICKHOUSE_URL=http://localhost:8123 cargo test -p cdc-clickhouse-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a ClickHouse server in CONFORMANCE_CLICKHOUSE_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_CLICKHOUSE_URL")
+            .expect("CONFORMANCE_CLICKHOUSE_URL is not set");
+        cdc_conformance::run_destination_checks(&mut ClickHouseHarness { url }).await;
+    }
+}
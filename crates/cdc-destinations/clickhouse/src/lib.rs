@@ -0,0 +1,5 @@
+mod clickhouse_destination;
+mod factory;
+
+pub use clickhouse_destination::{ClickHouseConfig, ClickHouseDestination};
+pub use factory::ClickHouseDestinationFactory;
@@ -0,0 +1,985 @@
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use deltalake::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use deltalake::arrow::json::ReaderBuilder;
+use deltalake::arrow::record_batch::RecordBatch;
+use deltalake::datafusion::common::Column;
+use deltalake::datafusion::prelude::{Expr, SessionContext};
+use deltalake::kernel::{DataType, StructField};
+use deltalake::protocol::SaveMode;
+use deltalake::{ensure_table_uri, DeltaTable, DeltaTableBuilder, DeltaTableError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{error, info};
+use url::Url;
+use uuid::Uuid;
+
+/// Column holding the operation of each row in append mode
+const OPERATION_COLUMN: &str = "_cdc_operation";
+
+/// Column holding the record timestamp of each row in append mode
+const TIMESTAMP_COLUMN: &str = "_cdc_timestamp";
+
+/// Column of the merge source flagging keys whose latest operation is a delete
+const DELETED_COLUMN: &str = "_cdc_deleted";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeltaDestinationConfig {
+    /// Directory or URL under which each table is stored as `{location}/{table}`, with
+    /// characters other than letters, digits, `_`, `-` and `.` in the name replaced by `_`
+    /// (e.g., /var/lib/cdc/delta or s3://bucket/prefix)
+    pub location: String,
+
+    /// Endpoint of an S3-compatible service (e.g., http://localhost:9000 for MinIO);
+    /// AWS S3 is used when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Bucket region; taken from the `AWS_*` environment variables when unset
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// Access key ID; taken from the `AWS_*` environment variables when unset
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+
+    /// Secret access key
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub secret_access_key: Option<String>,
+
+    /// Session token for temporary credentials
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub session_token: Option<String>,
+
+    /// Additional delta-rs storage options (e.g., AWS_S3_LOCKING_PROVIDER); they take
+    /// precedence over the fields above
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub storage_options: HashMap<String, String>,
+
+    /// How batches are applied to the tables
+    #[serde(default)]
+    pub mode: DeltaWriteMode,
+
+    /// Columns identifying a row in merge mode
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    pub auto_create_tables: bool,
+
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    pub auto_add_columns: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeltaWriteMode {
+    /// Append every record as a row, with its operation and timestamp
+    #[default]
+    Append,
+    /// Upsert and delete rows by primary key, keeping the current state of each row
+    Merge,
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+fn default_auto_create_tables() -> bool {
+    true
+}
+
+fn default_auto_add_columns() -> bool {
+    true
+}
+
+impl Default for DeltaDestinationConfig {
+    fn default() -> Self {
+        Self {
+            location: "delta".to_string(),
+            endpoint: None,
+            region: None,
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            storage_options: HashMap::new(),
+            mode: DeltaWriteMode::Append,
+            primary_key: default_primary_key(),
+            auto_create_tables: true,
+            auto_add_columns: true,
+        }
+    }
+}
+
+/// Rows of one table in a batch, in the order they were received
+#[derive(Debug)]
+struct TableBatch {
+    table: String,
+    ids: Vec<Uuid>,
+    rows: Vec<Map<String, Value>>,
+    operations: Vec<Operation>,
+}
+
+pub struct DeltaDestination {
+    config: DeltaDestinationConfig,
+    /// Validated `location`, set while connected
+    location: Option<Url>,
+    /// Tables loaded so far; a table is reloaded after a failed commit
+    tables: HashMap<String, DeltaTable>,
+    /// Records in tables committed by a batch that then failed, so sending the batch
+    /// again does not apply them twice
+    committed: HashSet<Uuid>,
+    status: DestinationStatus,
+}
+
+impl DeltaDestination {
+    pub fn new(config: DeltaDestinationConfig) -> Self {
+        Self {
+            config,
+            location: None,
+            tables: HashMap::new(),
+            committed: HashSet::new(),
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// delta-rs storage options built from the configured S3 settings
+    fn storage_options(&self, url: &Url) -> HashMap<String, String> {
+        let mut options = HashMap::new();
+        if matches!(url.scheme(), "s3" | "s3a") {
+            let settings = [
+                ("AWS_ENDPOINT_URL", &self.config.endpoint),
+                ("AWS_REGION", &self.config.region),
+                ("AWS_ACCESS_KEY_ID", &self.config.access_key_id),
+                ("AWS_SECRET_ACCESS_KEY", &self.config.secret_access_key),
+                ("AWS_SESSION_TOKEN", &self.config.session_token),
+            ];
+            for (key, value) in settings {
+                if let Some(value) = value {
+                    options.insert(key.to_string(), value.clone());
+                }
+            }
+            if let Some(endpoint) = &self.config.endpoint {
+                if endpoint.starts_with("http://") {
+                    options.insert("AWS_ALLOW_HTTP".to_string(), "true".to_string());
+                }
+            }
+            // S3 has no atomic rename; without a locking provider commits rely on the flow
+            // being the only writer of its tables
+            if !self
+                .config
+                .storage_options
+                .contains_key("AWS_S3_LOCKING_PROVIDER")
+            {
+                options.insert("AWS_S3_ALLOW_UNSAFE_RENAME".to_string(), "true".to_string());
+            }
+        }
+        options.extend(self.config.storage_options.clone());
+        options
+    }
+
+    /// Delta column type inferred from a JSON value; arrays and objects are stored as JSON text
+    fn infer_delta_type(value: &Value) -> DataType {
+        match value {
+            Value::Bool(_) => DataType::BOOLEAN,
+            Value::Number(n) if n.is_i64() => DataType::LONG,
+            Value::Number(_) => DataType::DOUBLE,
+            _ => DataType::STRING,
+        }
+    }
+
+    /// Group records by table, merging update changes, so a bad record fails the batch
+    /// before anything is committed. Records already committed by an earlier attempt
+    /// are skipped
+    fn table_batches(&self, records: &[DataRecord]) -> Result<Vec<TableBatch>> {
+        let mut batches: Vec<TableBatch> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for record in records {
+            if self.committed.contains(&record.id) {
+                continue;
+            }
+            let table = record
+                .table_name()
+                .map(|table| sanitize_table_name(&table))
+                .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+            let mut data: Map<String, Value> = serde_json::from_str(&record.record)
+                .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+            let operation = record.operation();
+            if operation == Operation::Update {
+                if let Ok(Some(changes)) = record.parse_changes() {
+                    data.extend(changes);
+                }
+            }
+
+            match self.config.mode {
+                DeltaWriteMode::Append => {
+                    let name = match operation {
+                        Operation::Insert => "insert",
+                        Operation::Update => "update",
+                        Operation::Delete => "delete",
+                        Operation::Snapshot => "snapshot",
+                    };
+                    data.insert(OPERATION_COLUMN.to_string(), Value::from(name));
+                    data.insert(
+                        TIMESTAMP_COLUMN.to_string(),
+                        Value::from(record.timestamp.to_rfc3339()),
+                    );
+                }
+                DeltaWriteMode::Merge => {
+                    for key in &self.config.primary_key {
+                        if data.get(key).is_none_or(Value::is_null) {
+                            return Err(Error::Generic(anyhow::anyhow!(
+                                "Record for table {} has no value for primary key column '{}'",
+                                table,
+                                key
+                            )));
+                        }
+                    }
+                }
+            }
+
+            let i = *index.entry(table.clone()).or_insert_with(|| {
+                batches.push(TableBatch {
+                    table,
+                    ids: Vec::new(),
+                    rows: Vec::new(),
+                    operations: Vec::new(),
+                });
+                batches.len() - 1
+            });
+            batches[i].ids.push(record.id);
+            batches[i].rows.push(data);
+            batches[i].operations.push(operation);
+        }
+
+        Ok(batches)
+    }
+
+    /// Load a table, or return an unloaded handle to create it if it doesn't exist;
+    /// `table` is a sanitized name, so it stays a single segment under the location
+    async fn load_table(&mut self, table: &str) -> Result<DeltaTable> {
+        if let Some(delta_table) = self.tables.remove(table) {
+            return Ok(delta_table);
+        }
+
+        let location = self
+            .location
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+        let uri = format!("{}/{}", location.as_str().trim_end_matches('/'), table);
+        let url = ensure_table_uri(&uri).map_err(delta_error)?;
+        let mut delta_table = DeltaTableBuilder::from_url(url.clone())
+            .map_err(delta_error)?
+            .with_storage_options(self.storage_options(&url))
+            .build()
+            .map_err(delta_error)?;
+
+        match delta_table.load().await {
+            Ok(()) | Err(DeltaTableError::NotATable(_)) => Ok(delta_table),
+            Err(e) => Err(delta_error(e)),
+        }
+    }
+
+    /// Create the table or add missing columns
+    async fn ensure_table(
+        &self,
+        delta_table: DeltaTable,
+        batch: &TableBatch,
+    ) -> Result<DeltaTable> {
+        // Columns of the batch with the type of their first non-null value
+        let mut columns: Vec<(&String, DataType)> = Vec::new();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for row in &batch.rows {
+            for (name, value) in row {
+                match seen.get(&name.to_lowercase()) {
+                    Some(&i) if columns[i].1 == DataType::STRING && !value.is_null() => {
+                        columns[i].1 = Self::infer_delta_type(value);
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen.insert(name.to_lowercase(), columns.len());
+                        columns.push((name, Self::infer_delta_type(value)));
+                    }
+                }
+            }
+        }
+        if self.config.mode == DeltaWriteMode::Append {
+            if let Some(&i) = seen.get(TIMESTAMP_COLUMN) {
+                columns[i].1 = DataType::TIMESTAMP;
+            }
+        }
+
+        if delta_table.version().is_none() {
+            if !self.config.auto_create_tables {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} does not exist and auto_create_tables is disabled",
+                    batch.table
+                )));
+            }
+
+            info!("Table {} does not exist, creating it", batch.table);
+            let fields = columns
+                .into_iter()
+                .map(|(name, data_type)| StructField::new(name.clone(), data_type, true));
+            return delta_table
+                .create()
+                .with_table_name(&batch.table)
+                .with_columns(fields)
+                .await
+                .map_err(delta_error);
+        }
+
+        let schema = delta_table.snapshot().map_err(delta_error)?.schema();
+        let existing: Vec<String> = schema
+            .fields()
+            .map(|field| field.name().to_lowercase())
+            .collect();
+        let missing: Vec<StructField> = columns
+            .into_iter()
+            .filter(|(name, _)| !existing.contains(&name.to_lowercase()))
+            .map(|(name, data_type)| StructField::new(name.clone(), data_type, true))
+            .collect();
+        if missing.is_empty() {
+            return Ok(delta_table);
+        }
+        if !self.config.auto_add_columns {
+            return Err(Error::Generic(anyhow::anyhow!(
+                "Table {} has no column {} and auto_add_columns is disabled",
+                batch.table,
+                missing[0].name()
+            )));
+        }
+
+        info!(
+            "Adding {} missing column(s) to table {}",
+            missing.len(),
+            batch.table
+        );
+        delta_table
+            .add_columns()
+            .with_fields(missing)
+            .await
+            .map_err(delta_error)
+    }
+
+    /// Arrow batch of `rows` with `schema`, whose fields take values from the
+    /// case-insensitively matching keys; JSON documents are stored as text
+    fn record_batch(schema: Arc<ArrowSchema>, rows: &[&Map<String, Value>]) -> Result<RecordBatch> {
+        let fields: HashMap<String, &Field> = schema
+            .fields()
+            .iter()
+            .map(|field| (field.name().to_lowercase(), field.as_ref()))
+            .collect();
+        let rows: Vec<Map<String, Value>> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .filter_map(|(name, value)| {
+                        let field = fields.get(&name.to_lowercase())?;
+                        let value = match value {
+                            Value::Array(_) | Value::Object(_)
+                                if matches!(
+                                    field.data_type(),
+                                    ArrowDataType::Utf8
+                                        | ArrowDataType::LargeUtf8
+                                        | ArrowDataType::Utf8View
+                                ) =>
+                            {
+                                Value::from(value.to_string())
+                            }
+                            value => value.clone(),
+                        };
+                        Some((field.name().clone(), value))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut decoder = ReaderBuilder::new(schema.clone())
+            .with_coerce_primitive(true)
+            .build_decoder()
+            .map_err(arrow_error)?;
+        decoder.serialize(&rows).map_err(arrow_error)?;
+        Ok(decoder
+            .flush()
+            .map_err(arrow_error)?
+            .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+    }
+
+    /// Append every row of the batch in one commit
+    async fn append(delta_table: DeltaTable, batch: &TableBatch) -> Result<DeltaTable> {
+        let schema = delta_table
+            .snapshot()
+            .map_err(delta_error)?
+            .snapshot()
+            .arrow_schema();
+        let rows: Vec<&Map<String, Value>> = batch.rows.iter().collect();
+        let record_batch = Self::record_batch(schema, &rows)?;
+
+        delta_table
+            .write(vec![record_batch])
+            .with_save_mode(SaveMode::Append)
+            .await
+            .map_err(delta_error)
+    }
+
+    /// Merge the latest row of each key in one commit: matched keys are updated or
+    /// deleted, and unmatched keys are inserted unless their latest operation is a delete
+    async fn merge(&self, delta_table: DeltaTable, batch: &TableBatch) -> Result<DeltaTable> {
+        let table_schema = delta_table
+            .snapshot()
+            .map_err(delta_error)?
+            .snapshot()
+            .arrow_schema();
+
+        let mut latest: Vec<usize> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for (i, row) in batch.rows.iter().enumerate() {
+            let key: Vec<&Value> = self
+                .config
+                .primary_key
+                .iter()
+                .map(|column| &row[column])
+                .collect();
+            let key = serde_json::to_string(&key)?;
+            match positions.get(&key) {
+                Some(&position) => latest[position] = i,
+                None => {
+                    positions.insert(key, latest.len());
+                    latest.push(i);
+                }
+            }
+        }
+
+        // Only the columns present in the batch are written, so updates leave other
+        // columns of matched rows untouched
+        let present: Vec<String> = batch
+            .rows
+            .iter()
+            .flat_map(|row| row.keys().map(|name| name.to_lowercase()))
+            .collect();
+        let mut fields: Vec<Field> = table_schema
+            .fields()
+            .iter()
+            .filter(|field| present.contains(&field.name().to_lowercase()))
+            .map(|field| field.as_ref().clone())
+            .collect();
+        let columns: Vec<String> = fields.iter().map(|field| field.name().clone()).collect();
+        for key in &self.config.primary_key {
+            if !columns
+                .iter()
+                .any(|column| column.eq_ignore_ascii_case(key))
+            {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} has no primary key column '{}'",
+                    batch.table,
+                    key
+                )));
+            }
+        }
+        fields.push(Field::new(DELETED_COLUMN, ArrowDataType::Boolean, false));
+
+        let rows: Vec<Map<String, Value>> = latest
+            .iter()
+            .map(|&i| {
+                let mut row = batch.rows[i].clone();
+                row.insert(
+                    DELETED_COLUMN.to_string(),
+                    Value::from(batch.operations[i] == Operation::Delete),
+                );
+                row
+            })
+            .collect();
+        let rows: Vec<&Map<String, Value>> = rows.iter().collect();
+        let record_batch = Self::record_batch(Arc::new(ArrowSchema::new(fields)), &rows)?;
+        let source = SessionContext::new()
+            .read_batch(record_batch)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("DataFusion error: {}", e)))?;
+
+        let source_column = |name: &str| Expr::Column(Column::new(Some("source"), name));
+        let predicate = self
+            .config
+            .primary_key
+            .iter()
+            .map(|key| {
+                let column = columns
+                    .iter()
+                    .find(|column| column.eq_ignore_ascii_case(key))
+                    .expect("primary key columns are checked above");
+                Expr::Column(Column::new(Some("target"), column)).eq(source_column(column))
+            })
+            .reduce(Expr::and)
+            .expect("primary key is not empty");
+
+        let (delta_table, metrics) = delta_table
+            .merge(source, predicate)
+            .with_source_alias("source")
+            .with_target_alias("target")
+            .when_matched_delete(|delete| delete.predicate(source_column(DELETED_COLUMN).is_true()))
+            .and_then(|merge| {
+                merge.when_matched_update(|update| {
+                    columns.iter().fold(update, |update, column| {
+                        update.update(Column::new_unqualified(column), source_column(column))
+                    })
+                })
+            })
+            .and_then(|merge| {
+                merge.when_not_matched_insert(|insert| {
+                    columns.iter().fold(
+                        insert.predicate(source_column(DELETED_COLUMN).is_false()),
+                        |insert, column| {
+                            insert.set(Column::new_unqualified(column), source_column(column))
+                        },
+                    )
+                })
+            })
+            .map_err(delta_error)?
+            .await
+            .map_err(delta_error)?;
+
+        info!(
+            "Merged into table {}: {} inserted, {} updated, {} deleted",
+            batch.table,
+            metrics.num_target_rows_inserted,
+            metrics.num_target_rows_updated,
+            metrics.num_target_rows_deleted
+        );
+        Ok(delta_table)
+    }
+
+    /// Apply the rows of one table as a single commit
+    async fn write_table(&mut self, batch: &TableBatch) -> Result<()> {
+        let delta_table = self.load_table(&batch.table).await?;
+        let delta_table = self.ensure_table(delta_table, batch).await?;
+        let delta_table = match self.config.mode {
+            DeltaWriteMode::Append => Self::append(delta_table, batch).await?,
+            DeltaWriteMode::Merge => self.merge(delta_table, batch).await?,
+        };
+        self.tables.insert(batch.table.clone(), delta_table);
+        Ok(())
+    }
+}
+
+/// Keep table names to a single, portable path segment under the location
+fn sanitize_table_name(table: &str) -> String {
+    let name: String = table
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        name.replace('.', "_")
+    } else {
+        name
+    }
+}
+
+fn delta_error(e: DeltaTableError) -> Error {
+    Error::Generic(anyhow::anyhow!("Delta error: {}", e))
+}
+
+fn arrow_error(e: deltalake::arrow::error::ArrowError) -> Error {
+    Error::Generic(anyhow::anyhow!("Arrow error: {}", e))
+}
+
+#[async_trait]
+impl Destination for DeltaDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting to Delta Lake location: {}",
+            redact_url(&self.config.location)
+        );
+
+        let location = ensure_table_uri(&self.config.location)
+            .map_err(|e| Error::Connection(format!("Invalid Delta Lake location: {}", e)))?;
+
+        info!("Connected to Delta Lake location successfully");
+        self.location = Some(location);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from Delta Lake location");
+
+        self.location = None;
+        self.tables.clear();
+        self.status.connected = false;
+
+        info!("Disconnected from Delta Lake location");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.location.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        let mut result = self.table_batches(&records);
+        if let Ok(batches) = &result {
+            for batch in batches {
+                if let Err(e) = self.write_table(batch).await {
+                    result = Err(e);
+                    break;
+                }
+                self.committed.extend(batch.ids.iter().copied());
+            }
+        }
+
+        match result {
+            Ok(_) => {
+                self.committed.clear();
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use deltalake::arrow::json::ArrayWriter;
+    use deltalake::operations::collect_sendable_stream;
+    use serde_json::json;
+
+    async fn connected(dir: &tempfile::TempDir, mode: DeltaWriteMode) -> DeltaDestination {
+        let mut destination = DeltaDestination::new(DeltaDestinationConfig {
+            location: dir.path().to_string_lossy().into_owned(),
+            mode,
+            ..Default::default()
+        });
+        destination.connect().await.unwrap();
+        destination
+    }
+
+    fn record(action: &str, row: Value) -> DataRecord {
+        DataRecord::new(
+            row.to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            action.to_string(),
+            None,
+        )
+    }
+
+    /// Rows of the table ordered by id, with columns in table order
+    async fn rows(dir: &tempfile::TempDir, table: &str) -> (Vec<String>, Vec<Value>) {
+        let url = ensure_table_uri(dir.path().join(table).to_string_lossy()).unwrap();
+        let delta_table = DeltaTableBuilder::from_url(url)
+            .unwrap()
+            .load()
+            .await
+            .unwrap();
+        let (_, stream) = delta_table.scan_table().await.unwrap();
+        let batches = collect_sendable_stream(stream).await.unwrap();
+        let columns = batches[0]
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+
+        let mut writer = ArrayWriter::new(Vec::new());
+        writer
+            .write_batches(&batches.iter().collect::<Vec<_>>())
+            .unwrap();
+        writer.finish().unwrap();
+        let mut rows: Vec<Value> = serde_json::from_slice(&writer.into_inner()).unwrap();
+        rows.sort_by_key(|row| row["id"].as_i64());
+        (columns, rows)
+    }
+
+    #[tokio::test]
+    async fn test_append_records_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DeltaWriteMode::Append).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a" })),
+                record("insert", json!({ "id": 2, "name": "b" })),
+            ])
+            .await
+            .unwrap();
+        destination
+            .write_batch(vec![
+                DataRecord::new(
+                    json!({ "id": 3, "name": "c" }).to_string(),
+                    json!({ "table_name": "users" }).to_string(),
+                    "update".to_string(),
+                    Some(json!({ "name": "z" }).to_string()),
+                ),
+                record("delete", json!({ "id": 4 })),
+            ])
+            .await
+            .unwrap();
+
+        let (columns, rows) = rows(&dir, "users").await;
+        assert!(columns.contains(&TIMESTAMP_COLUMN.to_string()));
+        let rows: Vec<(Value, Value, Value)> = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row["id"].clone(),
+                    row.get("name").cloned().unwrap_or(Value::Null),
+                    row[OPERATION_COLUMN].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (json!(1), json!("a"), json!("insert")),
+                (json!(2), json!("b"), json!("insert")),
+                (json!(3), json!("z"), json!("update")),
+                (json!(4), Value::Null, json!("delete")),
+            ]
+        );
+        assert_eq!(destination.status().records_written, 4);
+    }
+
+    #[tokio::test]
+    async fn test_merge_upserts_and_deletes_by_primary_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DeltaWriteMode::Merge).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a" })),
+                record("insert", json!({ "id": 2, "name": "b" })),
+                record("insert", json!({ "id": 3, "name": "c" })),
+            ])
+            .await
+            .unwrap();
+        destination
+            .write_batch(vec![
+                record("update", json!({ "id": 1, "name": "z" })),
+                record("delete", json!({ "id": 2 })),
+                // Latest operation per key wins within a batch
+                record("insert", json!({ "id": 4, "name": "d" })),
+                record("delete", json!({ "id": 4 })),
+                record("delete", json!({ "id": 5 })),
+                record("insert", json!({ "id": 5, "name": "e" })),
+            ])
+            .await
+            .unwrap();
+
+        let (columns, rows) = rows(&dir, "users").await;
+        assert_eq!(columns, vec!["id", "name"]);
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1, "name": "z" }),
+                json!({ "id": 3, "name": "c" }),
+                json!({ "id": 5, "name": "e" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_leaves_columns_missing_from_batch_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DeltaWriteMode::Merge).await;
+
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 1, "name": "a", "score": 1.5 }),
+            ))
+            .await
+            .unwrap();
+        destination
+            .write(record("update", json!({ "id": 1, "name": "b" })))
+            .await
+            .unwrap();
+
+        let (_, rows) = rows(&dir, "users").await;
+        assert_eq!(rows, vec![json!({ "id": 1, "name": "b", "score": 1.5 })]);
+    }
+
+    #[tokio::test]
+    async fn test_adds_columns_with_inferred_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DeltaWriteMode::Merge).await;
+
+        destination
+            .write(record("insert", json!({ "id": 1, "name": null })))
+            .await
+            .unwrap();
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 2, "Name": "b", "active": true, "tags": ["x"] }),
+            ))
+            .await
+            .unwrap();
+
+        let url = ensure_table_uri(dir.path().join("users").to_string_lossy()).unwrap();
+        let delta_table = DeltaTableBuilder::from_url(url)
+            .unwrap()
+            .load()
+            .await
+            .unwrap();
+        let schema = delta_table.snapshot().unwrap().schema();
+        let types: Vec<(String, DataType)> = schema
+            .fields()
+            .map(|field| (field.name().clone(), field.data_type().clone()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("id".to_string(), DataType::LONG),
+                ("name".to_string(), DataType::STRING),
+                ("active".to_string(), DataType::BOOLEAN),
+                ("tags".to_string(), DataType::STRING),
+            ]
+        );
+
+        let (_, rows) = rows(&dir, "users").await;
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "id": 1 }),
+                json!({ "id": 2, "name": "b", "active": true, "tags": "[\"x\"]" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_new_columns_when_auto_add_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = DeltaDestination::new(DeltaDestinationConfig {
+            location: dir.path().to_string_lossy().into_owned(),
+            auto_add_columns: false,
+            ..Default::default()
+        });
+        destination.connect().await.unwrap();
+
+        destination
+            .write(record("insert", json!({ "id": 1 })))
+            .await
+            .unwrap();
+        let result = destination
+            .write(record("insert", json!({ "id": 2, "name": "b" })))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(destination.status().errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_requires_primary_key_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = DeltaDestination::new(DeltaDestinationConfig {
+            location: dir.path().to_string_lossy().into_owned(),
+            mode: DeltaWriteMode::Merge,
+            primary_key: vec!["tenant".to_string(), "id".to_string()],
+            ..Default::default()
+        });
+        destination.connect().await.unwrap();
+
+        assert!(destination.write(sample_record("users", 1)).await.is_err());
+        destination
+            .write(record("insert", json!({ "tenant": "acme", "id": 1 })))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retried_batch_skips_committed_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DeltaWriteMode::Append).await;
+        let order = |id: i64| {
+            DataRecord::new(
+                json!({ "id": id }).to_string(),
+                json!({ "table_name": "orders" }).to_string(),
+                "insert".to_string(),
+                None,
+            )
+        };
+
+        // A file where the orders table would go makes its commit fail after users
+        // has been committed
+        std::fs::write(dir.path().join("orders"), b"").unwrap();
+        let mut batch = vec![record("insert", json!({ "id": 1 })), order(2)];
+        assert!(destination.write_batch(batch.clone()).await.is_err());
+        assert_eq!(rows(&dir, "users").await.1.len(), 1);
+
+        // The flow sends the batch again with newer records appended
+        std::fs::remove_file(dir.path().join("orders")).unwrap();
+        batch.push(record("insert", json!({ "id": 3 })));
+        destination.write_batch(batch).await.unwrap();
+
+        let ids = |rows: Vec<Value>| -> Vec<Value> {
+            rows.into_iter().map(|row| row["id"].clone()).collect()
+        };
+        assert_eq!(ids(rows(&dir, "users").await.1), vec![json!(1), json!(3)]);
+        assert_eq!(ids(rows(&dir, "orders").await.1), vec![json!(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_table_names_stay_under_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().join("lake");
+        let mut destination = DeltaDestination::new(DeltaDestinationConfig {
+            location: location.to_string_lossy().into_owned(),
+            ..Default::default()
+        });
+        destination.connect().await.unwrap();
+
+        for table in ["../escaped", "a/b", ".."] {
+            destination
+                .write(DataRecord::new(
+                    json!({ "id": 1 }).to_string(),
+                    json!({ "table_name": table }).to_string(),
+                    "insert".to_string(),
+                    None,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let mut tables: Vec<String> = std::fs::read_dir(&location)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        tables.sort();
+        assert_eq!(tables, vec![".._escaped", "__", "a_b"]);
+        assert!(!dir.path().join("escaped").exists());
+    }
+}
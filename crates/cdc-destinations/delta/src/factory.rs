@@ -0,0 +1,63 @@
+use crate::{DeltaDestination, DeltaDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct DeltaDestinationFactory;
+
+impl DestinationFactory for DeltaDestinationFactory {
+    fn name(&self) -> &str {
+        "delta"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: DeltaDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(DeltaDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(DeltaDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = DeltaDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["location"]));
+        assert_eq!(properties["mode"]["default"], "append");
+        assert_eq!(
+            properties["primary_key"]["default"],
+            serde_json::json!(["id"])
+        );
+        assert_eq!(properties["secret_access_key"]["x-secret"], true);
+        assert_eq!(properties["storage_options"]["x-secret"], true);
+    }
+
+    struct DeltaHarness {
+        dir: tempfile::TempDir,
+        created: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for DeltaHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            self.created += 1;
+            let location = self.dir.path().join(format!("run-{}", self.created));
+            DeltaDestinationFactory.create(serde_json::json!({ "location": location }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = DeltaHarness {
+            dir: tempfile::tempdir().unwrap(),
+            created: 0,
+        };
+        cdc_conformance::run_destination_checks(&mut harness).await;
+    }
+}
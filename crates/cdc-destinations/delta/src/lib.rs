@@ -0,0 +1,5 @@
+mod delta_destination;
+mod factory;
+
+pub use delta_destination::{DeltaDestination, DeltaDestinationConfig, DeltaWriteMode};
+pub use factory::DeltaDestinationFactory;
@@ -0,0 +1,746 @@
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use duckdb::types::Value as DuckValue;
+use duckdb::{appender_params_from_iter, Connection, Transaction};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DuckdbConfig {
+    /// Path of the database file; created if it does not exist
+    pub path: String,
+
+    /// Columns identifying a row; updates and deletes replace the row with the same key
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    pub auto_create_tables: bool,
+
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    pub auto_add_columns: bool,
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+fn default_auto_create_tables() -> bool {
+    true
+}
+
+fn default_auto_add_columns() -> bool {
+    true
+}
+
+impl Default for DuckdbConfig {
+    fn default() -> Self {
+        Self {
+            path: "cdc.duckdb".to_string(),
+            primary_key: default_primary_key(),
+            auto_create_tables: true,
+            auto_add_columns: true,
+        }
+    }
+}
+
+/// Rows of one table in a batch, in the order they were received
+#[derive(Debug)]
+struct TableBatch {
+    table: String,
+    rows: Vec<StagedRow>,
+}
+
+#[derive(Debug)]
+struct StagedRow {
+    data: Map<String, Value>,
+    deleted: bool,
+}
+
+pub struct DuckdbDestination {
+    config: DuckdbConfig,
+    connection: Option<Arc<Mutex<Connection>>>,
+    status: DestinationStatus,
+}
+
+impl DuckdbDestination {
+    pub fn new(config: DuckdbConfig) -> Self {
+        Self {
+            config,
+            connection: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Quote identifier, doubling any embedded quotes
+    fn quote_identifier(identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    /// Infer DuckDB column type from JSON value; JSON documents are stored as VARCHAR
+    fn infer_duckdb_type(value: &Value) -> &'static str {
+        match value {
+            Value::Bool(_) => "BOOLEAN",
+            Value::Number(n) if n.is_i64() => "BIGINT",
+            Value::Number(n) if n.is_u64() => "UBIGINT",
+            Value::Number(_) => "DOUBLE",
+            _ => "VARCHAR",
+        }
+    }
+
+    /// Staged text of a value; DuckDB casts it to the column type when merging
+    fn staged_text(value: &Value) -> DuckValue {
+        match value {
+            Value::Null => DuckValue::Null,
+            Value::String(s) => DuckValue::Text(s.clone()),
+            other => DuckValue::Text(other.to_string()),
+        }
+    }
+
+    /// Group records by table, merging update changes, so a bad record fails the batch
+    /// before anything is written
+    fn table_batches(&self, records: &[DataRecord]) -> Result<Vec<TableBatch>> {
+        let mut batches: Vec<TableBatch> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for record in records {
+            let table = record
+                .table_name()
+                .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+            let mut data: Map<String, Value> = serde_json::from_str(&record.record)
+                .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+            let operation = record.operation();
+            if operation == Operation::Update {
+                if let Ok(Some(changes)) = record.parse_changes() {
+                    data.extend(changes);
+                }
+            }
+
+            for key in &self.config.primary_key {
+                if data.get(key).is_none_or(Value::is_null) {
+                    return Err(Error::Generic(anyhow::anyhow!(
+                        "Record for table {} has no value for primary key column '{}'",
+                        table,
+                        key
+                    )));
+                }
+            }
+
+            let row = StagedRow {
+                data,
+                deleted: operation == Operation::Delete,
+            };
+            match index.get(&table) {
+                Some(&i) => batches[i].rows.push(row),
+                None => {
+                    index.insert(table.clone(), batches.len());
+                    batches.push(TableBatch {
+                        table,
+                        rows: vec![row],
+                    });
+                }
+            }
+        }
+
+        Ok(batches)
+    }
+
+    /// Columns of `table` with their types, in table order; empty if it does not exist
+    fn table_columns(tx: &Transaction, table: &str) -> Result<Vec<(String, String)>> {
+        let mut statement = tx
+            .prepare(
+                "SELECT column_name, data_type FROM information_schema.columns
+                 WHERE table_schema = 'main' AND table_name = ?
+                 ORDER BY ordinal_position",
+            )
+            .map_err(db_error)?;
+        let columns = statement
+            .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_error)?
+            .collect::<duckdb::Result<Vec<(String, String)>>>()
+            .map_err(db_error)?;
+        Ok(columns)
+    }
+
+    /// Create the table or add missing columns, returning its columns
+    fn ensure_table(
+        tx: &Transaction,
+        config: &DuckdbConfig,
+        batch: &TableBatch,
+    ) -> Result<Vec<(String, String)>> {
+        let table = Self::quote_identifier(&batch.table);
+        let columns = Self::table_columns(tx, &batch.table)?;
+
+        // Columns of the batch with the type of their first non-null value
+        let mut batch_columns: Vec<(&String, &'static str)> = Vec::new();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for row in &batch.rows {
+            for (name, value) in &row.data {
+                match seen.get(&name.to_lowercase()) {
+                    Some(&i) if batch_columns[i].1 == "VARCHAR" && !value.is_null() => {
+                        batch_columns[i].1 = Self::infer_duckdb_type(value);
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen.insert(name.to_lowercase(), batch_columns.len());
+                        batch_columns.push((name, Self::infer_duckdb_type(value)));
+                    }
+                }
+            }
+        }
+
+        if columns.is_empty() {
+            if !config.auto_create_tables {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} does not exist and auto_create_tables is disabled",
+                    batch.table
+                )));
+            }
+
+            // No PRIMARY KEY constraint: rows are keyed by the merge, and DuckDB rejects
+            // deleting and re-inserting a key within one transaction on constrained tables
+            let column_defs: Vec<String> = batch_columns
+                .iter()
+                .map(|(name, data_type)| format!("{} {}", Self::quote_identifier(name), data_type))
+                .collect();
+            let query = format!("CREATE TABLE {} ({})", table, column_defs.join(", "));
+            info!("Creating table: {}", query);
+            tx.execute_batch(&query).map_err(db_error)?;
+        } else {
+            let existing: Vec<String> = columns
+                .iter()
+                .map(|(name, _)| name.to_lowercase())
+                .collect();
+            let missing: Vec<_> = batch_columns
+                .iter()
+                .filter(|(name, _)| !existing.contains(&name.to_lowercase()))
+                .collect();
+            if missing.is_empty() {
+                return Ok(columns);
+            }
+            if !config.auto_add_columns {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} has no column {} and auto_add_columns is disabled",
+                    batch.table,
+                    missing[0].0
+                )));
+            }
+
+            for (name, data_type) in &missing {
+                let query = format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table,
+                    Self::quote_identifier(name),
+                    data_type
+                );
+                info!("Adding column: {}", query);
+                tx.execute_batch(&query).map_err(db_error)?;
+            }
+        }
+
+        Self::table_columns(tx, &batch.table)
+    }
+
+    /// Append the rows to a staging table with a presence flag per column, fold each key's
+    /// rows into its latest value of every column present, then delete keys whose latest
+    /// operation is a delete, update only the present columns of existing rows and insert
+    /// the new ones. A delete within the batch resets the key, so rows after it start empty
+    fn merge(
+        tx: &Transaction,
+        config: &DuckdbConfig,
+        batch: &TableBatch,
+        columns: &[(String, String)],
+    ) -> Result<()> {
+        let table = Self::quote_identifier(&batch.table);
+        let position: HashMap<String, usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.to_lowercase(), i))
+            .collect();
+
+        let key_columns = config
+            .primary_key
+            .iter()
+            .map(|key| match position.get(&key.to_lowercase()) {
+                Some(&i) => Ok(Self::quote_identifier(&columns[i].0)),
+                None => Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} has no primary key column '{}'",
+                    batch.table,
+                    key
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let column_names: Vec<String> = columns
+            .iter()
+            .map(|(name, _)| Self::quote_identifier(name))
+            .collect();
+
+        let present: Vec<String> = (0..columns.len())
+            .map(|i| format!("_cdc_present_{}", i))
+            .collect();
+
+        let staging_columns: Vec<String> = column_names
+            .iter()
+            .map(|name| format!("{} VARCHAR", name))
+            .chain(present.iter().map(|flag| format!("{} BOOLEAN", flag)))
+            .collect();
+        tx.execute_batch(&format!(
+            "CREATE TEMP TABLE _cdc_stage ({}, _cdc_seq BIGINT, _cdc_deleted BOOLEAN)",
+            staging_columns.join(", ")
+        ))
+        .map_err(db_error)?;
+
+        {
+            let mut appender = tx
+                .appender_to_catalog_and_db("_cdc_stage", "temp", "main")
+                .map_err(db_error)?;
+            for (seq, row) in batch.rows.iter().enumerate() {
+                let mut values = vec![DuckValue::Null; columns.len()];
+                let mut flags = vec![DuckValue::Boolean(false); columns.len()];
+                for (name, value) in &row.data {
+                    if let Some(&i) = position.get(&name.to_lowercase()) {
+                        values[i] = Self::staged_text(value);
+                        flags[i] = DuckValue::Boolean(true);
+                    }
+                }
+                values.extend(flags);
+                values.push(DuckValue::BigInt(seq as i64));
+                values.push(DuckValue::Boolean(row.deleted));
+                appender
+                    .append_row(appender_params_from_iter(values))
+                    .map_err(db_error)?;
+            }
+            appender.flush().map_err(db_error)?;
+        }
+
+        // Latest value of each column among the rows after the key's last delete
+        let mut folded = Vec::new();
+        let mut updates = Vec::new();
+        for (i, ((_, data_type), name)) in columns.iter().zip(&column_names).enumerate() {
+            if key_columns.contains(name) {
+                folded.push(format!("CAST({} AS {}) AS {}", name, data_type, name));
+                continue;
+            }
+            let rows = format!("{} AND _cdc_seq > _cdc_reset", present[i]);
+            folded.push(format!(
+                "CAST(arg_max_null({}, _cdc_seq) FILTER (WHERE {}) AS {}) AS {}",
+                name, rows, data_type, name
+            ));
+            folded.push(format!(
+                "coalesce(bool_or({}) FILTER (WHERE _cdc_seq > _cdc_reset), false) AS {}",
+                present[i], present[i]
+            ));
+            updates.push(format!(
+                "{} = CASE WHEN s.{} THEN s.{} ELSE {}.{} END",
+                name, present[i], name, table, name
+            ));
+        }
+        let key_match: Vec<String> = key_columns
+            .iter()
+            .map(|key| format!("s.{} = {}.{}", key, table, key))
+            .collect();
+
+        let mut statements = vec![
+            format!(
+                "CREATE TEMP TABLE _cdc_latest AS
+                 SELECT {}, arg_max(_cdc_deleted, _cdc_seq) AS _cdc_deleted,
+                        max(_cdc_reset) >= 0 AS _cdc_reset
+                 FROM (
+                    SELECT *, max(CASE WHEN _cdc_deleted THEN _cdc_seq ELSE -1 END)
+                        OVER (PARTITION BY {}) AS _cdc_reset
+                    FROM temp._cdc_stage
+                 ) GROUP BY {}",
+                folded.join(", "),
+                key_columns.join(", "),
+                key_columns.join(", ")
+            ),
+            // Deleted keys, and keys deleted earlier in the batch that are re-inserted whole
+            format!(
+                "DELETE FROM {} WHERE EXISTS (
+                    SELECT 1 FROM temp._cdc_latest s WHERE {} AND (s._cdc_deleted OR s._cdc_reset)
+                 )",
+                table,
+                key_match.join(" AND ")
+            ),
+        ];
+        if !updates.is_empty() {
+            statements.push(format!(
+                "UPDATE {} SET {} FROM temp._cdc_latest s WHERE {} AND NOT s._cdc_deleted",
+                table,
+                updates.join(", "),
+                key_match.join(" AND ")
+            ));
+        }
+        statements.push(format!(
+            "INSERT INTO {} ({}) SELECT {} FROM temp._cdc_latest s
+             WHERE NOT s._cdc_deleted AND NOT EXISTS (SELECT 1 FROM {} WHERE {})",
+            table,
+            column_names.join(", "),
+            column_names.join(", "),
+            table,
+            key_match.join(" AND ")
+        ));
+        statements.push("DROP TABLE temp._cdc_stage; DROP TABLE temp._cdc_latest".to_string());
+
+        for statement in &statements {
+            debug!("Executing: {}", statement);
+            tx.execute_batch(statement).map_err(db_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply every table's rows in one transaction
+    fn apply(
+        connection: &mut Connection,
+        config: &DuckdbConfig,
+        batches: &[TableBatch],
+    ) -> Result<()> {
+        let tx = connection.transaction().map_err(db_error)?;
+        for batch in batches {
+            let columns = Self::ensure_table(&tx, config, batch)?;
+            Self::merge(&tx, config, batch, &columns)?;
+        }
+        // Dropping the transaction without committing rolls it back
+        tx.commit().map_err(db_error)
+    }
+}
+
+fn db_error(e: duckdb::Error) -> Error {
+    Error::Generic(anyhow::anyhow!("Database error: {}", e))
+}
+
+#[async_trait]
+impl Destination for DuckdbDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!("Opening DuckDB database: {}", self.config.path);
+
+        let path = self.config.path.clone();
+        let connection = tokio::task::spawn_blocking(move || Connection::open(path))
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Open task failed: {}", e)))?
+            .map_err(|e| Error::Connection(format!("Failed to open DuckDB database: {}", e)))?;
+
+        info!("Opened DuckDB database successfully");
+        self.connection = Some(Arc::new(Mutex::new(connection)));
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Closing DuckDB database");
+
+        self.status.connected = false;
+        if let Some(connection) = self.connection.take() {
+            // Closing checkpoints the write-ahead log into the database file
+            tokio::task::spawn_blocking(move || drop(connection))
+                .await
+                .map_err(|e| Error::Generic(anyhow::anyhow!("Close task failed: {}", e)))?;
+        }
+
+        info!("Closed DuckDB database");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        let connection = self
+            .connection
+            .clone()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        let result = match self.table_batches(&records) {
+            Ok(batches) => {
+                let config = self.config.clone();
+                tokio::task::spawn_blocking(move || {
+                    let mut connection = connection.lock().expect("connection lock poisoned");
+                    Self::apply(&mut connection, &config, &batches)
+                })
+                .await
+                .map_err(|e| Error::Generic(anyhow::anyhow!("Write task failed: {}", e)))
+                .and_then(|result| result)
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    async fn connected(dir: &tempfile::TempDir, config: DuckdbConfig) -> DuckdbDestination {
+        let mut destination = DuckdbDestination::new(DuckdbConfig {
+            path: dir.path().join("cdc.duckdb").to_string_lossy().into_owned(),
+            ..config
+        });
+        destination.connect().await.unwrap();
+        destination
+    }
+
+    fn record(action: &str, row: Value) -> DataRecord {
+        DataRecord::new(
+            row.to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            action.to_string(),
+            None,
+        )
+    }
+
+    fn query<T: duckdb::types::FromSql>(
+        destination: &DuckdbDestination,
+        sql: &str,
+    ) -> Vec<(i64, T)> {
+        let connection = destination.connection.as_ref().unwrap().lock().unwrap();
+        let mut statement = connection.prepare(sql).unwrap();
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<duckdb::Result<_>>()
+            .unwrap()
+    }
+
+    fn names(destination: &DuckdbDestination) -> Vec<(i64, Option<String>)> {
+        query(destination, "SELECT id, name FROM users ORDER BY id")
+    }
+
+    #[tokio::test]
+    async fn test_upserts_and_deletes_by_primary_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a" })),
+                record("insert", json!({ "id": 2, "name": "b" })),
+                record("insert", json!({ "id": 3, "name": "c" })),
+            ])
+            .await
+            .unwrap();
+        destination
+            .write_batch(vec![
+                DataRecord::new(
+                    json!({ "id": 1, "name": "a" }).to_string(),
+                    json!({ "table_name": "users" }).to_string(),
+                    "update".to_string(),
+                    Some(json!({ "name": "z" }).to_string()),
+                ),
+                record("delete", json!({ "id": 2 })),
+                // Replayed insert of an existing key replaces it instead of duplicating it
+                record("insert", json!({ "id": 3, "name": "c" })),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            names(&destination),
+            vec![(1, Some("z".to_string())), (3, Some("c".to_string()))]
+        );
+        assert_eq!(destination.status().records_written, 6);
+    }
+
+    #[tokio::test]
+    async fn test_latest_operation_per_key_wins_within_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a" })),
+                record("delete", json!({ "id": 1 })),
+                record("insert", json!({ "id": 2, "name": "b" })),
+                record("delete", json!({ "id": 2 })),
+                record("insert", json!({ "id": 2, "name": "c" })),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(names(&destination), vec![(2, Some("c".to_string()))]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_leaves_columns_missing_from_batch_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a", "score": 1.5 })),
+                record("insert", json!({ "id": 2, "name": "b", "score": 2.5 })),
+            ])
+            .await
+            .unwrap();
+        destination
+            .write_batch(vec![
+                record("update", json!({ "id": 1, "name": "c" })),
+                record("update", json!({ "id": 2, "score": null })),
+                record("update", json!({ "id": 2, "name": "d" })),
+                record("insert", json!({ "id": 3, "name": "e" })),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            names(&destination),
+            vec![
+                (1, Some("c".to_string())),
+                (2, Some("d".to_string())),
+                (3, Some("e".to_string()))
+            ]
+        );
+        let scores: Vec<(i64, Option<f64>)> =
+            query(&destination, "SELECT id, score FROM users ORDER BY id");
+        assert_eq!(scores, vec![(1, Some(1.5)), (2, None), (3, None)]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_within_batch_resets_missing_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 1, "name": "a", "score": 1.5 }),
+            ))
+            .await
+            .unwrap();
+        destination
+            .write_batch(vec![
+                record("delete", json!({ "id": 1 })),
+                record("insert", json!({ "id": 1, "name": "b" })),
+            ])
+            .await
+            .unwrap();
+
+        let scores: Vec<(i64, Option<f64>)> =
+            query(&destination, "SELECT id, score FROM users ORDER BY id");
+        assert_eq!(scores, vec![(1, None)]);
+        assert_eq!(names(&destination), vec![(1, Some("b".to_string()))]);
+    }
+
+    #[tokio::test]
+    async fn test_creates_typed_tables_and_adds_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 1, "name": null, "score": 1.5, "active": true }),
+            ))
+            .await
+            .unwrap();
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 2, "name": "b", "tags": ["x"] }),
+            ))
+            .await
+            .unwrap();
+
+        let columns: Vec<(String, String)> = {
+            let connection = destination.connection.as_ref().unwrap().lock().unwrap();
+            let mut statement = connection
+                .prepare(
+                    "SELECT column_name, data_type FROM information_schema.columns
+                     WHERE table_name = 'users' ORDER BY column_name",
+                )
+                .unwrap();
+            statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<duckdb::Result<_>>()
+                .unwrap()
+        };
+        let columns: HashMap<String, String> = columns.into_iter().collect();
+        assert_eq!(columns["id"], "BIGINT");
+        assert_eq!(columns["name"], "VARCHAR");
+        assert_eq!(columns["score"], "DOUBLE");
+        assert_eq!(columns["active"], "BOOLEAN");
+        assert_eq!(columns["tags"], "VARCHAR");
+
+        let tags: Vec<(i64, Option<String>)> =
+            query(&destination, "SELECT id, tags FROM users ORDER BY id");
+        assert_eq!(tags, vec![(1, None), (2, Some("[\"x\"]".to_string()))]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir, DuckdbConfig::default()).await;
+        destination
+            .write(record("insert", json!({ "id": 1, "name": "a" })))
+            .await
+            .unwrap();
+
+        // "x" cannot be cast to the BIGINT id column
+        let result = destination
+            .write_batch(vec![
+                record("delete", json!({ "id": 1 })),
+                record("insert", json!({ "id": "x", "name": "c" })),
+            ])
+            .await;
+        assert!(result.is_err());
+        assert_eq!(names(&destination), vec![(1, Some("a".to_string()))]);
+        assert_eq!(destination.status().errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_requires_primary_key_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(
+            &dir,
+            DuckdbConfig {
+                primary_key: vec!["tenant".to_string(), "id".to_string()],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(destination.write(sample_record("users", 1)).await.is_err());
+        destination
+            .write(record(
+                "insert",
+                json!({ "tenant": "acme", "id": 1, "name": "a" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(names(&destination), vec![(1, Some("a".to_string()))]);
+    }
+}
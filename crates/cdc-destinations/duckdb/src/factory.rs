@@ -0,0 +1,61 @@
+use crate::{DuckdbConfig, DuckdbDestination};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct DuckdbDestinationFactory;
+
+impl DestinationFactory for DuckdbDestinationFactory {
+    fn name(&self) -> &str {
+        "duckdb"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: DuckdbConfig = serde_json::from_value(config)?;
+        Ok(Box::new(DuckdbDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(DuckdbConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = DuckdbDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["path"]));
+        assert_eq!(
+            properties["primary_key"]["default"],
+            serde_json::json!(["id"])
+        );
+        assert_eq!(properties["auto_add_columns"]["default"], true);
+    }
+
+    struct DuckdbHarness {
+        dir: tempfile::TempDir,
+        created: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for DuckdbHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            self.created += 1;
+            let path = self.dir.path().join(format!("run-{}.duckdb", self.created));
+            DuckdbDestinationFactory.create(serde_json::json!({ "path": path }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = DuckdbHarness {
+            dir: tempfile::tempdir().unwrap(),
+            created: 0,
+        };
+        cdc_conformance::run_destination_checks(&mut harness).await;
+    }
+}
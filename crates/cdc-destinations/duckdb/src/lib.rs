@@ -0,0 +1,5 @@
+mod duckdb_destination;
+mod factory;
+
+pub use duckdb_destination::{DuckdbConfig, DuckdbDestination};
+pub use factory::DuckdbDestinationFactory;
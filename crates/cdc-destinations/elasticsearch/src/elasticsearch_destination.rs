@@ -0,0 +1,610 @@
+use async_trait::async_trait;
+use base64::Engine;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Separator between primary key values in composite document IDs
+const ID_SEPARATOR: &str = "|";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ElasticsearchConfig {
+    /// Cluster node URLs (comma-separated); requests fail over between them
+    #[serde(default = "default_nodes")]
+    pub nodes: String,
+
+    /// Elastic Cloud deployment ID; replaces `nodes` when set
+    #[serde(default)]
+    pub cloud_id: Option<String>,
+
+    /// User for basic authentication
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Password for basic authentication
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub password: Option<String>,
+
+    /// API key (base64 `id:key`); takes precedence over basic authentication
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub api_key: Option<String>,
+
+    /// Value of `{prefix}` in `index_template`
+    #[serde(default = "default_index_prefix")]
+    pub index_prefix: String,
+
+    /// Index name template; `{prefix}`, `{table}` and `{database}` are replaced
+    #[serde(default = "default_index_template")]
+    pub index_template: String,
+
+    /// Fields forming the document ID; composite keys are joined with `|`
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// HTTP request timeout in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_nodes() -> String {
+    "http://localhost:9200".to_string()
+}
+
+fn default_index_prefix() -> String {
+    "cdc-".to_string()
+}
+
+fn default_index_template() -> String {
+    "{prefix}{table}".to_string()
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for ElasticsearchConfig {
+    fn default() -> Self {
+        Self {
+            nodes: default_nodes(),
+            cloud_id: None,
+            username: None,
+            password: None,
+            api_key: None,
+            index_prefix: default_index_prefix(),
+            index_template: default_index_template(),
+            primary_key: default_primary_key(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+impl ElasticsearchConfig {
+    /// Base URLs to send requests to, from `cloud_id` or `nodes`
+    fn node_urls(&self) -> Result<Vec<String>> {
+        if let Some(cloud_id) = self.cloud_id.as_deref().filter(|id| !id.is_empty()) {
+            return Ok(vec![decode_cloud_id(cloud_id)?]);
+        }
+
+        let nodes: Vec<String> = self
+            .nodes
+            .split(',')
+            .map(|node| node.trim().trim_end_matches('/').to_string())
+            .filter(|node| !node.is_empty())
+            .collect();
+        if nodes.is_empty() {
+            return Err(Error::Configuration(
+                "At least one Elasticsearch node is required".to_string(),
+            ));
+        }
+        Ok(nodes)
+    }
+}
+
+/// Elasticsearch URL of an Elastic Cloud ID (`name:base64(host$es_uuid$kibana_uuid)`)
+fn decode_cloud_id(cloud_id: &str) -> Result<String> {
+    let invalid = || Error::Configuration("Invalid Elastic Cloud ID".to_string());
+
+    let encoded = cloud_id.split_once(':').map_or(cloud_id, |(_, data)| data);
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+
+    let mut parts = decoded.split('$');
+    let host = parts.next().filter(|h| !h.is_empty()).ok_or_else(invalid)?;
+    let es_uuid = parts.next().filter(|u| !u.is_empty()).ok_or_else(invalid)?;
+
+    // The port may be given on the host; the cluster is always served over TLS
+    let (host, port) = match host.rsplit_once(':') {
+        Some((host, port)) if port != "443" => (host, format!(":{}", port)),
+        Some((host, _)) => (host, String::new()),
+        None => (host, String::new()),
+    };
+    Ok(format!("https://{}.{}{}", es_uuid, host, port))
+}
+
+/// One failed item of a `_bulk` response
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BulkFailure {
+    pub index: String,
+    pub id: String,
+    pub reason: String,
+}
+
+/// Failed items of a `_bulk` response; deleting a missing document is not a failure
+pub(crate) fn bulk_failures(response: &Value) -> Vec<BulkFailure> {
+    if response["errors"] != Value::Bool(true) {
+        return Vec::new();
+    }
+
+    let items = response["items"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    items
+        .iter()
+        .filter_map(|item| {
+            let (action, result) = item.as_object()?.iter().next()?;
+            let error = result.get("error")?;
+            if action == "delete" && result["status"] == 404 {
+                return None;
+            }
+            let reason = match error {
+                Value::Object(error) => format!(
+                    "{}: {}",
+                    error.get("type").and_then(Value::as_str).unwrap_or("error"),
+                    error
+                        .get("reason")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                ),
+                other => other.to_string(),
+            };
+            Some(BulkFailure {
+                index: result["_index"].as_str().unwrap_or_default().to_string(),
+                id: result["_id"].as_str().unwrap_or_default().to_string(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+pub struct ElasticsearchDestination {
+    config: ElasticsearchConfig,
+    client: Option<reqwest::Client>,
+    nodes: Vec<String>,
+    /// Node the next request goes to first
+    current_node: usize,
+    status: DestinationStatus,
+}
+
+impl ElasticsearchDestination {
+    pub fn new(config: ElasticsearchConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            nodes: Vec::new(),
+            current_node: 0,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Index name for `record` from the template; Elasticsearch requires lowercase
+    fn index_name(&self, record: &DataRecord) -> Result<String> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+
+        let mut index = self
+            .config
+            .index_template
+            .replace("{prefix}", &self.config.index_prefix)
+            .replace("{table}", &table);
+        if index.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Index template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            index = index.replace("{database}", &database);
+        }
+        Ok(index.to_lowercase())
+    }
+
+    /// Document ID from the primary key fields of `document`
+    fn document_id(&self, document: &Map<String, Value>) -> Result<String> {
+        let values = self
+            .config
+            .primary_key
+            .iter()
+            .map(|key| match document.get(key) {
+                None | Some(Value::Null) => Err(Error::Generic(anyhow::anyhow!(
+                    "Record has no value for primary key field '{}'",
+                    key
+                ))),
+                Some(Value::String(value)) => Ok(value.clone()),
+                Some(value) => Ok(value.to_string()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(values.join(ID_SEPARATOR))
+    }
+
+    /// Action and document lines of `record` in a `_bulk` body
+    fn bulk_lines(&self, record: &DataRecord) -> Result<Vec<Value>> {
+        let mut document: Map<String, Value> = serde_json::from_str(&record.record)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        if operation == Operation::Update {
+            if let Ok(Some(changes)) = record.parse_changes() {
+                document.extend(changes);
+            }
+        }
+
+        let target = json!({
+            "_index": self.index_name(record)?,
+            "_id": self.document_id(&document)?,
+        });
+        Ok(match operation {
+            Operation::Delete => vec![json!({ "delete": target })],
+            _ => vec![json!({ "index": target }), Value::Object(document)],
+        })
+    }
+
+    /// Send a request to the current node, failing over to the others on transport errors
+    async fn send(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<String>,
+    ) -> Result<reqwest::Response> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        let mut last_error = None;
+        for _ in 0..self.nodes.len() {
+            let node = &self.nodes[self.current_node];
+            let mut request = client.request(method.clone(), format!("{}{}", node, path));
+            if let Some(body) = &body {
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+                    .body(body.clone());
+            }
+            if let Some(api_key) = &self.config.api_key {
+                request = request.header(
+                    reqwest::header::AUTHORIZATION,
+                    format!("ApiKey {}", api_key),
+                );
+            } else if let Some(username) = &self.config.username {
+                request = request.basic_auth(username, self.config.password.as_ref());
+            }
+
+            match request.send().await {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    warn!("Request to node {} failed: {}", redact_url(node), e);
+                    last_error = Some(e);
+                    self.current_node = (self.current_node + 1) % self.nodes.len();
+                }
+            }
+        }
+
+        Err(Error::Connection(format!(
+            "All Elasticsearch nodes failed, last error: {}",
+            last_error.map_or_else(|| "no nodes".to_string(), |e| e.to_string())
+        )))
+    }
+
+    /// Send one `_bulk` request and return the items that failed
+    async fn bulk(&mut self, records: &[DataRecord]) -> Result<Vec<BulkFailure>> {
+        // Build every line before sending so a bad record fails the batch up front
+        let mut body = String::new();
+        for record in records {
+            for line in self.bulk_lines(record)? {
+                body.push_str(&line.to_string());
+                body.push('\n');
+            }
+        }
+
+        debug!("Sending bulk request with {} action(s)", records.len());
+        let response = self
+            .send(reqwest::Method::POST, "/_bulk", Some(body))
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Generic(anyhow::anyhow!(
+                "Bulk request returned {}: {}",
+                status,
+                body.trim()
+            )));
+        }
+        let response: Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse bulk response: {}", e)))?;
+        Ok(bulk_failures(&response))
+    }
+
+    fn record_failure(&mut self, failed: u64, message: String) {
+        error!("{}", message);
+        self.status.errors += failed;
+        self.status.consecutive_errors += 1;
+        self.status.last_error = Some(message);
+    }
+}
+
+#[async_trait]
+impl Destination for ElasticsearchDestination {
+    async fn connect(&mut self) -> Result<()> {
+        let nodes = self.config.node_urls()?;
+        info!(
+            "Connecting to Elasticsearch: {}",
+            nodes
+                .iter()
+                .map(|n| redact_url(n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .build()
+            .map_err(|e| Error::Connection(format!("Failed to create HTTP client: {}", e)))?;
+        self.client = Some(client);
+        self.nodes = nodes;
+        self.current_node = 0;
+
+        let result = match self.send(reqwest::Method::GET, "/", None).await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(Error::Connection(format!(
+                "Failed to connect to Elasticsearch: {}",
+                response.status()
+            ))),
+            Err(e) => Err(Error::Connection(format!(
+                "Failed to connect to Elasticsearch: {}",
+                e
+            ))),
+        };
+        if let Err(e) = result {
+            self.client = None;
+            return Err(e);
+        }
+
+        info!("Connected to Elasticsearch successfully");
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from Elasticsearch");
+        self.client = None;
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.client.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let failures = match self.bulk(&records).await {
+            Ok(failures) => failures,
+            Err(e) => {
+                self.record_failure(
+                    1,
+                    format!("Failed to write batch of {} records: {}", records.len(), e),
+                );
+                return Err(e);
+            }
+        };
+
+        // Items are applied independently, so the successful ones are written either way
+        let failed = failures.len() as u64;
+        self.status.records_written += records.len() as u64 - failed;
+        match failures.first() {
+            None => {
+                self.status.consecutive_errors = 0;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Some(first) => {
+                let message = format!(
+                    "{} of {} bulk items failed, first: {}/{}: {}",
+                    failed,
+                    records.len(),
+                    first.index,
+                    first.id,
+                    first.reason
+                );
+                self.record_failure(failed, message.clone());
+                Err(Error::Generic(anyhow::anyhow!(message)))
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+
+    fn destination() -> ElasticsearchDestination {
+        ElasticsearchDestination::new(ElasticsearchConfig::default())
+    }
+
+    #[test]
+    fn test_node_urls() {
+        let config = ElasticsearchConfig {
+            nodes: "http://a:9200/, http://b:9200,".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.node_urls().unwrap(),
+            vec!["http://a:9200", "http://b:9200"]
+        );
+
+        let config = ElasticsearchConfig {
+            nodes: " ".to_string(),
+            ..Default::default()
+        };
+        assert!(config.node_urls().is_err());
+    }
+
+    #[test]
+    fn test_decode_cloud_id() {
+        let encode = |s: &str| base64::engine::general_purpose::STANDARD.encode(s);
+
+        let cloud_id = format!("prod:{}", encode("us-east-1.aws.found.io$abc123$kib456"));
+        assert_eq!(
+            decode_cloud_id(&cloud_id).unwrap(),
+            "https://abc123.us-east-1.aws.found.io"
+        );
+
+        let cloud_id = format!("prod:{}", encode("example.com:9243$abc$kib"));
+        assert_eq!(
+            decode_cloud_id(&cloud_id).unwrap(),
+            "https://abc.example.com:9243"
+        );
+
+        assert!(decode_cloud_id("prod:not-base64!").is_err());
+        assert!(decode_cloud_id(&format!("prod:{}", encode("host-only"))).is_err());
+    }
+
+    #[test]
+    fn test_index_name_from_template() {
+        let record = DataRecord::new(
+            json!({ "id": 1 }).to_string(),
+            json!({ "table_name": "Users", "database_name": "shop" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert_eq!(destination().index_name(&record).unwrap(), "cdc-users");
+        assert_eq!(
+            destination()
+                .index_name(&sample_record("orders", 1))
+                .unwrap(),
+            "cdc-orders"
+        );
+
+        let destination = ElasticsearchDestination::new(ElasticsearchConfig {
+            index_template: "{database}.{table}-v1".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(destination.index_name(&record).unwrap(), "shop.users-v1");
+        let record = DataRecord::new(
+            json!({ "id": 1 }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert!(destination.index_name(&record).is_err());
+    }
+
+    #[test]
+    fn test_bulk_lines() {
+        let destination = destination();
+        let mut record = DataRecord::new(
+            json!({ "id": 7, "name": "a" }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "update".to_string(),
+            Some(json!({ "name": "b" }).to_string()),
+        );
+
+        assert_eq!(
+            destination.bulk_lines(&record).unwrap(),
+            vec![
+                json!({ "index": { "_index": "cdc-users", "_id": "7" } }),
+                json!({ "id": 7, "name": "b" }),
+            ]
+        );
+
+        record.action = "delete".to_string();
+        assert_eq!(
+            destination.bulk_lines(&record).unwrap(),
+            vec![json!({ "delete": { "_index": "cdc-users", "_id": "7" } })]
+        );
+    }
+
+    #[test]
+    fn test_document_id_from_composite_key() {
+        let destination = ElasticsearchDestination::new(ElasticsearchConfig {
+            primary_key: vec!["tenant".to_string(), "id".to_string()],
+            ..Default::default()
+        });
+        let document = json!({ "tenant": "acme", "id": 3 });
+        assert_eq!(
+            destination
+                .document_id(document.as_object().unwrap())
+                .unwrap(),
+            "acme|3"
+        );
+
+        let document = json!({ "id": 3 });
+        assert!(destination
+            .document_id(document.as_object().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_bulk_failures() {
+        let response = json!({
+            "errors": true,
+            "items": [
+                { "index": { "_index": "cdc-users", "_id": "1", "status": 201 } },
+                { "index": { "_index": "cdc-users", "_id": "2", "status": 400,
+                    "error": { "type": "mapper_parsing_exception", "reason": "bad field" } } },
+                { "delete": { "_index": "cdc-users", "_id": "3", "status": 404, "result": "not_found" } },
+                { "delete": { "_index": "cdc-users", "_id": "4", "status": 404,
+                    "error": { "type": "index_not_found_exception", "reason": "no such index" } } },
+                { "delete": { "_index": "cdc-users", "_id": "5", "status": 429,
+                    "error": { "type": "es_rejected_execution_exception", "reason": "queue full" } } }
+            ]
+        });
+
+        assert_eq!(
+            bulk_failures(&response),
+            vec![
+                BulkFailure {
+                    index: "cdc-users".to_string(),
+                    id: "2".to_string(),
+                    reason: "mapper_parsing_exception: bad field".to_string(),
+                },
+                BulkFailure {
+                    index: "cdc-users".to_string(),
+                    id: "5".to_string(),
+                    reason: "es_rejected_execution_exception: queue full".to_string(),
+                },
+            ]
+        );
+        assert!(bulk_failures(&json!({ "errors": false, "items": [] })).is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{ElasticsearchConfig, ElasticsearchDestination};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct ElasticsearchDestinationFactory;
+
+impl DestinationFactory for ElasticsearchDestinationFactory {
+    fn name(&self) -> &str {
+        "elasticsearch"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: ElasticsearchConfig = serde_json::from_value(config)?;
+        Ok(Box::new(ElasticsearchDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(ElasticsearchConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = ElasticsearchDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(
+            properties["password"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(properties["api_key"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["nodes"]["default"], "http://localhost:9200");
+        assert_eq!(properties["index_prefix"]["default"], "cdc-");
+        assert_eq!(properties["index_template"]["default"], "{prefix}{table}");
+    }
+
+    struct ElasticsearchHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for ElasticsearchHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            ElasticsearchDestinationFactory.create(serde_json::json!({ "nodes": self.url }))
+        }
+    }
+
+    /// `CONFORMANCE_ELASTICSEARCH_URL=http://localhost:9200 cargo test -p cdc-elasticsearch-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs an Elasticsearch or OpenSearch cluster in CONFORMANCE_ELASTICSEARCH_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_ELASTICSEARCH_URL")
+            .expect("CONFORMANCE_ELASTICSEARCH_URL is not set");
+        cdc_conformance::run_destination_checks(&mut ElasticsearchHarness { url }).await;
+    }
+}
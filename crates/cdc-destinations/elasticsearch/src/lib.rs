@@ -0,0 +1,5 @@
+mod elasticsearch_destination;
+mod factory;
+
+pub use elasticsearch_destination::{ElasticsearchConfig, ElasticsearchDestination};
+pub use factory::ElasticsearchDestinationFactory;
@@ -0,0 +1,57 @@
+use crate::{FileDestination, FileDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct FileDestinationFactory;
+
+impl DestinationFactory for FileDestinationFactory {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: FileDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(FileDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(FileDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct FileHarness {
+        dir: TempDir,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for FileHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            FileDestinationFactory.create(serde_json::json!({
+                "directory": self.dir.path().to_str().unwrap(),
+            }))
+        }
+    }
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = FileDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["directory"]));
+        assert_eq!(properties["format"]["default"], "jsonl");
+        assert_eq!(properties["partition_format"]["default"], "dt=%Y-%m-%d");
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = FileHarness {
+            dir: TempDir::new().unwrap(),
+        };
+        cdc_conformance::run_destination_checks(&mut harness).await;
+    }
+}
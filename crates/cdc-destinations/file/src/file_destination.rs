@@ -0,0 +1,622 @@
+use crate::part_writer::{Checkpoint, PartWriter};
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Result};
+use chrono::format::{Item, StrftimeItems};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileDestinationConfig {
+    /// Root directory; records go to `{directory}/{table}/{partition}/part-NNNN.{ext}`
+    pub directory: String,
+
+    /// Output format
+    #[serde(default)]
+    pub format: FileFormat,
+
+    /// Partition directory under each table, as a chrono format string applied
+    /// to the record timestamp (e.g., "dt=%Y-%m-%d/hour=%H")
+    #[serde(default = "default_partition_format")]
+    pub partition_format: String,
+
+    /// Start a new part once a file reaches this size, in bytes
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+
+    /// Start a new part once a file has been open this long, in seconds
+    #[serde(default = "default_max_file_age_secs")]
+    pub max_file_age_secs: u64,
+}
+
+fn default_partition_format() -> String {
+    "dt=%Y-%m-%d".to_string()
+}
+
+fn default_max_file_bytes() -> u64 {
+    128 * 1024 * 1024 // 128 MiB
+}
+
+fn default_max_file_age_secs() -> u64 {
+    300 // 5 minutes
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    /// One serialized `DataRecord` per line (readable by the `file` connector)
+    #[default]
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Snappy-compressed Parquet, one row group per batch
+    Parquet,
+}
+
+impl FileFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Jsonl => "jsonl",
+            FileFormat::Csv => "csv",
+            FileFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// A part file being written under a hidden name until it is closed
+struct OpenPart {
+    path: PathBuf,
+    in_progress_path: PathBuf,
+    writer: PartWriter,
+    opened: Instant,
+}
+
+pub struct FileDestination {
+    config: FileDestinationConfig,
+    /// Open part per partition directory
+    parts: HashMap<PathBuf, OpenPart>,
+    /// Next part number per partition directory
+    next_part: HashMap<PathBuf, u32>,
+    status: DestinationStatus,
+}
+
+impl FileDestination {
+    pub fn new(config: FileDestinationConfig) -> Self {
+        Self {
+            config,
+            parts: HashMap::new(),
+            next_part: HashMap::new(),
+            status: DestinationStatus::default(),
+        }
+    }
+
+    fn max_file_age(&self) -> Duration {
+        Duration::from_secs(self.config.max_file_age_secs)
+    }
+
+    /// Directory a record belongs in: `{directory}/{table}/{partition}`
+    fn partition_dir(&self, record: &DataRecord) -> Result<PathBuf> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+        let partition = record
+            .timestamp
+            .format(&self.config.partition_format)
+            .to_string();
+
+        Ok(Path::new(&self.config.directory)
+            .join(sanitize_table_name(&table))
+            .join(partition))
+    }
+
+    /// Next unused part number in `dir`, continuing after files left by earlier runs
+    fn next_part_number(&mut self, dir: &Path) -> Result<u32> {
+        let next = match self.next_part.get(dir) {
+            Some(next) => *next,
+            None => {
+                let mut next = 1;
+                for entry in fs::read_dir(dir)? {
+                    let name = entry?.file_name();
+                    if let Some(number) = part_number(&name.to_string_lossy()) {
+                        next = next.max(number + 1);
+                    }
+                }
+                next
+            }
+        };
+        self.next_part.insert(dir.to_path_buf(), next + 1);
+        Ok(next)
+    }
+
+    fn open_part(&mut self, dir: &Path) -> Result<OpenPart> {
+        fs::create_dir_all(dir)?;
+        let name = format!(
+            "part-{:04}.{}",
+            self.next_part_number(dir)?,
+            self.config.format.extension()
+        );
+        let in_progress_path = dir.join(format!(".{}.inprogress", name));
+        let writer = PartWriter::create(self.config.format, &in_progress_path)?;
+
+        debug!("Opened {}", in_progress_path.display());
+        Ok(OpenPart {
+            path: dir.join(name),
+            in_progress_path,
+            writer,
+            opened: Instant::now(),
+        })
+    }
+
+    /// Finish a part and move it to its final name
+    fn close_part(part: OpenPart) -> Result<()> {
+        part.writer.finish()?;
+        fs::rename(&part.in_progress_path, &part.path)?;
+        info!("Closed {}", part.path.display());
+        Ok(())
+    }
+
+    /// Write records for one partition, opening a part if none is open.
+    ///
+    /// Adds the partition to `touched` with where its part ended before the
+    /// write, or `None` if the part was opened for it.
+    fn write_partition(
+        &mut self,
+        dir: &Path,
+        records: &[&DataRecord],
+        touched: &mut Vec<(PathBuf, Option<Checkpoint>)>,
+    ) -> Result<()> {
+        let checkpoint = match self.parts.get(dir) {
+            Some(part) => Some(part.writer.checkpoint()?),
+            None => {
+                let part = self.open_part(dir)?;
+                self.parts.insert(dir.to_path_buf(), part);
+                None
+            }
+        };
+        touched.push((dir.to_path_buf(), checkpoint));
+
+        let part = self.parts.get_mut(dir).expect("part is open");
+        part.writer.write(records)
+    }
+
+    /// Undo a failed batch in every partition it touched, so a retry writes it only once.
+    ///
+    /// Parts opened by the batch are deleted; older parts are cut back to where
+    /// they ended before it.
+    fn rollback(&mut self, touched: Vec<(PathBuf, Option<Checkpoint>)>) {
+        for (dir, checkpoint) in touched {
+            let Some(part) = self.parts.remove(&dir) else {
+                continue;
+            };
+
+            let Some(checkpoint) = checkpoint else {
+                drop(part.writer);
+                if let Err(e) = fs::remove_file(&part.in_progress_path) {
+                    error!(
+                        "Failed to remove {}: {}",
+                        part.in_progress_path.display(),
+                        e
+                    );
+                }
+                continue;
+            };
+
+            match part.writer.rollback(checkpoint) {
+                Ok(Some(writer)) => {
+                    warn!(
+                        "Rolled {} back to its last complete batch",
+                        part.in_progress_path.display()
+                    );
+                    self.parts.insert(dir, OpenPart { writer, ..part });
+                }
+                // Parquet parts cannot be appended to again, so they are closed as they were
+                Ok(None) => match fs::rename(&part.in_progress_path, &part.path) {
+                    Ok(()) => info!("Closed {} at its last complete batch", part.path.display()),
+                    Err(e) => error!("Failed to close {}: {}", part.in_progress_path.display(), e),
+                },
+                Err(e) => error!(
+                    "Failed to roll back {}, leaving it under its in-progress name: {}",
+                    part.in_progress_path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Close parts that are full or have been open longer than `max_file_age_secs`
+    fn close_due(&mut self) -> Result<()> {
+        let max_age = self.max_file_age();
+        let mut due = Vec::new();
+        for (dir, part) in &self.parts {
+            if part.opened.elapsed() >= max_age || part.writer.size()? >= self.config.max_file_bytes
+            {
+                due.push(dir.clone());
+            }
+        }
+
+        for dir in due {
+            if let Some(part) = self.parts.remove(&dir) {
+                Self::close_part(part)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_records(&mut self, records: &[DataRecord]) -> Result<()> {
+        // Resolve every partition before writing so a bad record fails the batch up front
+        let mut partitions: Vec<(PathBuf, Vec<&DataRecord>)> = Vec::new();
+        let mut index: HashMap<PathBuf, usize> = HashMap::new();
+        for record in records {
+            let dir = self.partition_dir(record)?;
+            match index.get(&dir) {
+                Some(&i) => partitions[i].1.push(record),
+                None => {
+                    index.insert(dir.clone(), partitions.len());
+                    partitions.push((dir, vec![record]));
+                }
+            }
+        }
+
+        // Parts are only closed between batches, while they hold complete batches
+        self.close_due()?;
+
+        let mut touched = Vec::new();
+        for (dir, records) in &partitions {
+            if let Err(e) = self.write_partition(dir, records, &mut touched) {
+                self.rollback(touched);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keep table names from escaping their directory
+fn sanitize_table_name(table: &str) -> String {
+    let name: String = table
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        name.replace('.', "_")
+    } else {
+        name
+    }
+}
+
+/// Part number of a `part-NNNN.ext` file or its in-progress `.part-NNNN.ext.inprogress` name
+fn part_number(file_name: &str) -> Option<u32> {
+    let rest = file_name
+        .strip_prefix("part-")
+        .or_else(|| file_name.strip_prefix(".part-"))?;
+    rest.split('.').next()?.parse().ok()
+}
+
+#[async_trait]
+impl Destination for FileDestination {
+    async fn connect(&mut self) -> Result<()> {
+        if StrftimeItems::new(&self.config.partition_format).any(|item| item == Item::Error) {
+            return Err(Error::Configuration(format!(
+                "Invalid partition_format '{}'",
+                self.config.partition_format
+            )));
+        }
+
+        fs::create_dir_all(&self.config.directory).map_err(|e| {
+            Error::Connection(format!(
+                "Failed to create directory {}: {}",
+                self.config.directory, e
+            ))
+        })?;
+
+        info!(
+            "Writing {:?} files to {}",
+            self.config.format, self.config.directory
+        );
+        self.status.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Closing {} open file(s)", self.parts.len());
+
+        let mut result = Ok(());
+        for (_, part) in self.parts.drain() {
+            if let Err(e) = Self::close_part(part) {
+                error!("Failed to close file: {}", e);
+                result = Err(e);
+            }
+        }
+        self.next_part.clear();
+
+        self.status.connected = false;
+        result
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if !self.status.connected {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        match self.write_records(&records) {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+
+    async fn tick(&mut self) -> Result<()> {
+        self.close_due()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use tempfile::TempDir;
+
+    fn config(dir: &TempDir, format: FileFormat) -> FileDestinationConfig {
+        let mut config: FileDestinationConfig = serde_json::from_value(serde_json::json!({
+            "directory": dir.path().to_str().unwrap(),
+        }))
+        .unwrap();
+        config.format = format;
+        config
+    }
+
+    fn records(table: &str, ids: std::ops::Range<u64>) -> Vec<DataRecord> {
+        ids.map(|id| sample_record(table, id)).collect()
+    }
+
+    /// Visible files under `dir`, relative to it
+    fn files(dir: &Path) -> Vec<String> {
+        let mut found = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(current).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    let relative = path.strip_prefix(dir).unwrap();
+                    found.push(relative.display().to_string());
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+
+    #[tokio::test]
+    async fn test_partitions_by_table_and_renames_on_close() {
+        let dir = TempDir::new().unwrap();
+        let mut destination = FileDestination::new(config(&dir, FileFormat::Jsonl));
+        destination.connect().await.unwrap();
+
+        let mut batch = records("users", 0..2);
+        batch.extend(records("orders", 0..1));
+        destination.write_batch(batch).await.unwrap();
+
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        let in_progress = files(dir.path());
+        assert_eq!(
+            in_progress,
+            vec![
+                format!("orders/{}/.part-0001.jsonl.inprogress", dt),
+                format!("users/{}/.part-0001.jsonl.inprogress", dt),
+            ]
+        );
+
+        destination.disconnect().await.unwrap();
+        assert_eq!(
+            files(dir.path()),
+            vec![
+                format!("orders/{}/part-0001.jsonl", dt),
+                format!("users/{}/part-0001.jsonl", dt),
+            ]
+        );
+
+        let users =
+            fs::read_to_string(dir.path().join(format!("users/{}/part-0001.jsonl", dt))).unwrap();
+        let ids: Vec<u64> = users
+            .lines()
+            .map(|line| {
+                let record: DataRecord = serde_json::from_str(line).unwrap();
+                record.parse_record().unwrap()["id"].as_u64().unwrap()
+            })
+            .collect();
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_by_size_and_continues_numbering_after_restart() {
+        let dir = TempDir::new().unwrap();
+        let mut config = config(&dir, FileFormat::Csv);
+        config.max_file_bytes = 1;
+
+        let mut destination = FileDestination::new(config.clone());
+        destination.connect().await.unwrap();
+        destination
+            .write_batch(records("users", 0..1))
+            .await
+            .unwrap();
+        destination
+            .write_batch(records("users", 1..2))
+            .await
+            .unwrap();
+        destination.disconnect().await.unwrap();
+
+        let mut destination = FileDestination::new(config);
+        destination.connect().await.unwrap();
+        destination
+            .write_batch(records("users", 2..3))
+            .await
+            .unwrap();
+        destination.disconnect().await.unwrap();
+
+        let names: Vec<String> = files(dir.path())
+            .iter()
+            .map(|path| path.rsplit('/').next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["part-0001.csv", "part-0002.csv", "part-0003.csv"]
+        );
+
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        let csv =
+            fs::read_to_string(dir.path().join(format!("users/{}/part-0001.csv", dt))).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("id,timestamp,action,record,metadata,changes")
+        );
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_row_groups_per_batch() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = TempDir::new().unwrap();
+        let mut destination = FileDestination::new(config(&dir, FileFormat::Parquet));
+        destination.connect().await.unwrap();
+        destination
+            .write_batch(records("users", 0..3))
+            .await
+            .unwrap();
+        destination
+            .write_batch(records("users", 3..5))
+            .await
+            .unwrap();
+        destination.disconnect().await.unwrap();
+
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        let file =
+            fs::File::open(dir.path().join(format!("users/{}/part-0001.parquet", dt))).unwrap();
+        let metadata = SerializedFileReader::new(file).unwrap().metadata().clone();
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_record_without_table_fails_whole_batch() {
+        let dir = TempDir::new().unwrap();
+        let mut destination = FileDestination::new(config(&dir, FileFormat::Jsonl));
+        destination.connect().await.unwrap();
+
+        let mut batch = records("users", 0..1);
+        batch.push(DataRecord::new(
+            "{}".to_string(),
+            "{}".to_string(),
+            "insert".to_string(),
+            None,
+        ));
+        assert!(destination.write_batch(batch).await.is_err());
+
+        let status = destination.status();
+        assert_eq!(status.records_written, 0);
+        assert_eq!(status.errors, 1);
+        assert!(files(dir.path()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tick_closes_parts_by_age() {
+        let dir = TempDir::new().unwrap();
+        let mut config = config(&dir, FileFormat::Jsonl);
+        config.max_file_age_secs = 0;
+
+        let mut destination = FileDestination::new(config);
+        destination.connect().await.unwrap();
+        destination
+            .write_batch(records("users", 0..2))
+            .await
+            .unwrap();
+        assert!(files(dir.path())[0].ends_with(".inprogress"));
+
+        // No further batch is needed to publish the part
+        destination.tick().await.unwrap();
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        assert_eq!(
+            files(dir.path()),
+            vec![format!("users/{}/part-0001.jsonl", dt)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_rolled_back_in_every_partition() {
+        let dir = TempDir::new().unwrap();
+        let mut destination = FileDestination::new(config(&dir, FileFormat::Csv));
+        destination.connect().await.unwrap();
+        let first = records("users", 0..2);
+        destination.write_batch(first.clone()).await.unwrap();
+
+        // A file where the table directory belongs makes the second partition fail
+        let blocker = dir.path().join("orders");
+        fs::write(&blocker, "").unwrap();
+        let mut batch = records("users", 2..4);
+        batch.extend(records("orders", 0..1));
+        assert!(destination.write_batch(batch.clone()).await.is_err());
+
+        fs::remove_file(&blocker).unwrap();
+        destination.write_batch(batch.clone()).await.unwrap();
+        destination.disconnect().await.unwrap();
+
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        let csv =
+            fs::read_to_string(dir.path().join(format!("users/{}/part-0001.csv", dt))).unwrap();
+        let ids: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap())
+            .collect();
+        let expected: Vec<String> = first
+            .iter()
+            .chain(&batch[..2])
+            .map(|record| record.id.to_string())
+            .collect();
+        assert_eq!(ids, expected);
+        assert_eq!(destination.status().records_written, 5);
+    }
+
+    #[test]
+    fn test_sanitize_table_name_and_part_number() {
+        assert_eq!(sanitize_table_name("public.users"), "public.users");
+        assert_eq!(sanitize_table_name("../etc"), ".._etc");
+        assert_eq!(sanitize_table_name(".."), "__");
+        assert_eq!(part_number("part-0012.parquet"), Some(12));
+        assert_eq!(part_number(".part-0003.csv.inprogress"), Some(3));
+        assert_eq!(part_number("_SUCCESS"), None);
+    }
+}
@@ -0,0 +1,6 @@
+mod factory;
+mod file_destination;
+mod part_writer;
+
+pub use factory::FileDestinationFactory;
+pub use file_destination::{FileDestination, FileDestinationConfig, FileFormat};
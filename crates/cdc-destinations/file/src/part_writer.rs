@@ -0,0 +1,269 @@
+use crate::FileFormat;
+use cdc_core::{DataRecord, Error, Result};
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::metadata::{FileMetaData, ParquetMetaData, ParquetMetaDataWriter};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Columns of CSV and Parquet files, in order
+const COLUMNS: [&str; 6] = ["id", "timestamp", "action", "record", "metadata", "changes"];
+
+const PARQUET_SCHEMA: &str = "
+message data_record {
+    REQUIRED BYTE_ARRAY id (STRING);
+    REQUIRED INT64 timestamp (TIMESTAMP(MICROS, true));
+    REQUIRED BYTE_ARRAY action (STRING);
+    REQUIRED BYTE_ARRAY record (STRING);
+    REQUIRED BYTE_ARRAY metadata (STRING);
+    OPTIONAL BYTE_ARRAY changes (STRING);
+}
+";
+
+/// End of the last complete batch in a part file
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Checkpoint {
+    len: u64,
+    row_groups: usize,
+}
+
+/// Writes records to one part file in the configured format
+pub(crate) enum PartWriter {
+    Jsonl(BufWriter<File>),
+    Csv(csv::Writer<File>),
+    Parquet(SerializedFileWriter<File>),
+}
+
+impl PartWriter {
+    pub(crate) fn create(format: FileFormat, path: &Path) -> Result<Self> {
+        let file = File::create(path)?;
+        match format {
+            FileFormat::Jsonl => Ok(Self::Jsonl(BufWriter::new(file))),
+            FileFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(file);
+                writer.write_record(COLUMNS).map_err(io::Error::from)?;
+                writer.flush()?;
+                Ok(Self::Csv(writer))
+            }
+            FileFormat::Parquet => {
+                let schema = parse_message_type(PARQUET_SCHEMA).map_err(parquet_error)?;
+                let properties = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                let writer =
+                    SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+                        .map_err(parquet_error)?;
+                Ok(Self::Parquet(writer))
+            }
+        }
+    }
+
+    /// Append `records` and hand them to the OS; Parquet writes them as one row group
+    pub(crate) fn write(&mut self, records: &[&DataRecord]) -> Result<()> {
+        match self {
+            Self::Jsonl(writer) => {
+                for record in records {
+                    serde_json::to_writer(&mut *writer, record)?;
+                    writer.write_all(b"\n")?;
+                }
+                writer.flush()?;
+            }
+            Self::Csv(writer) => {
+                for record in records {
+                    writer
+                        .write_record([
+                            record.id.to_string().as_str(),
+                            record.timestamp.to_rfc3339().as_str(),
+                            &record.action,
+                            &record.record,
+                            &record.metadata,
+                            record.changes.as_deref().unwrap_or_default(),
+                        ])
+                        .map_err(io::Error::from)?;
+                }
+                writer.flush()?;
+            }
+            Self::Parquet(writer) => {
+                write_row_group(writer, records).map_err(parquet_error)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Bytes written to the file so far
+    pub(crate) fn size(&self) -> Result<u64> {
+        Ok(match self {
+            Self::Jsonl(writer) => writer.get_ref().metadata()?.len(),
+            Self::Csv(writer) => writer.get_ref().metadata()?.len(),
+            Self::Parquet(writer) => writer.bytes_written() as u64,
+        })
+    }
+
+    /// Where the file ends after the batches written so far
+    pub(crate) fn checkpoint(&self) -> Result<Checkpoint> {
+        let row_groups = match self {
+            Self::Parquet(writer) => writer.flushed_row_groups().len(),
+            _ => 0,
+        };
+        Ok(Checkpoint {
+            len: self.size()?,
+            row_groups,
+        })
+    }
+
+    /// Discard everything written after `checkpoint`.
+    ///
+    /// JSONL and CSV writers keep appending from there. A Parquet writer cannot
+    /// continue after a failed row group, so the file is completed with the row
+    /// groups before `checkpoint` and `None` is returned.
+    pub(crate) fn rollback(self, checkpoint: Checkpoint) -> Result<Option<Self>> {
+        match self {
+            Self::Jsonl(writer) => {
+                let file = writer.get_ref().try_clone()?;
+                let file = truncate(file, writer, checkpoint.len)?;
+                Ok(Some(Self::Jsonl(BufWriter::new(file))))
+            }
+            Self::Csv(writer) => {
+                let file = writer.get_ref().try_clone()?;
+                let file = truncate(file, writer, checkpoint.len)?;
+                Ok(Some(Self::Csv(csv::Writer::from_writer(file))))
+            }
+            Self::Parquet(writer) => {
+                let row_groups = writer.flushed_row_groups()[..checkpoint.row_groups].to_vec();
+                let num_rows = row_groups.iter().map(|rg| rg.num_rows()).sum();
+                let file_metadata = FileMetaData::new(
+                    writer.properties().writer_version().as_num(),
+                    num_rows,
+                    Some(writer.properties().created_by().to_string()),
+                    None,
+                    Arc::new(writer.schema_descr().clone()),
+                    None,
+                );
+                let metadata = ParquetMetaData::new(file_metadata, row_groups);
+
+                let file = writer.inner().try_clone()?;
+                let mut file = truncate(file, writer, checkpoint.len)?;
+                ParquetMetaDataWriter::new(&mut file, &metadata)
+                    .finish()
+                    .map_err(parquet_error)?;
+                file.sync_all()?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Complete the file (Parquet footer included) and sync it to disk
+    pub(crate) fn finish(self) -> Result<()> {
+        let file = match self {
+            Self::Jsonl(writer) => writer.into_inner().map_err(|e| e.into_error())?,
+            Self::Csv(writer) => writer
+                .into_inner()
+                .map_err(|e| io::Error::other(e.to_string()))?,
+            Self::Parquet(writer) => writer.into_inner().map_err(parquet_error)?,
+        };
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+fn write_row_group(
+    writer: &mut SerializedFileWriter<File>,
+    records: &[&DataRecord],
+) -> parquet::errors::Result<()> {
+    let text = |value: fn(&DataRecord) -> String| -> Vec<ByteArray> {
+        records
+            .iter()
+            .map(|r| ByteArray::from(value(r).into_bytes()))
+            .collect()
+    };
+
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match COLUMNS[index] {
+            "timestamp" => {
+                let values: Vec<i64> = records
+                    .iter()
+                    .map(|r| r.timestamp.timestamp_micros())
+                    .collect();
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&values, None, None)?;
+            }
+            "changes" => {
+                let values: Vec<ByteArray> = records
+                    .iter()
+                    .filter_map(|r| r.changes.as_deref().map(ByteArray::from))
+                    .collect();
+                let levels: Vec<i16> = records
+                    .iter()
+                    .map(|r| i16::from(r.changes.is_some()))
+                    .collect();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            name => {
+                let values = match name {
+                    "id" => text(|r| r.id.to_string()),
+                    "action" => text(|r| r.action.clone()),
+                    "record" => text(|r| r.record.clone()),
+                    _ => text(|r| r.metadata.clone()),
+                };
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+        }
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    Ok(())
+}
+
+/// Cut `file` back to `len` once `writer` is gone, ready to append from there
+fn truncate<W>(mut file: File, writer: W, len: u64) -> Result<File> {
+    // Dropping the writer may flush buffered bytes, so truncate afterwards
+    drop(writer);
+    file.set_len(len)?;
+    file.seek(SeekFrom::Start(len))?;
+    Ok(file)
+}
+
+fn parquet_error(e: parquet::errors::ParquetError) -> Error {
+    Error::Generic(anyhow::anyhow!("Parquet error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parquet_rollback_keeps_complete_row_groups() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("part.parquet");
+        let records: Vec<DataRecord> = (0..3).map(|id| sample_record("users", id)).collect();
+        let refs: Vec<&DataRecord> = records.iter().collect();
+
+        let mut writer = PartWriter::create(FileFormat::Parquet, &path).unwrap();
+        writer.write(&refs[..2]).unwrap();
+        let checkpoint = writer.checkpoint().unwrap();
+        writer.write(&refs[2..]).unwrap();
+        assert!(writer.rollback(checkpoint).unwrap().is_none());
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 1);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(reader.get_row_iter(None).unwrap().count(), 2);
+    }
+}
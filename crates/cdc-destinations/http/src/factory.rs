@@ -0,0 +1,61 @@
+use crate::{HttpDestination, HttpDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct HttpDestinationFactory;
+
+impl DestinationFactory for HttpDestinationFactory {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: HttpDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(HttpDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(HttpDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = HttpDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(
+            properties["bearer_token"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(
+            properties["hmac_secret"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(properties["mode"]["default"], "batch");
+        assert_eq!(properties["max_retries"]["default"], 3);
+        assert_eq!(schema["required"], serde_json::json!(["url"]));
+    }
+
+    struct HttpHarness {
+        server: MockServer,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for HttpHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            HttpDestinationFactory.create(serde_json::json!({ "url": self.server.url() }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let server = MockServer::start().await;
+        cdc_conformance::run_destination_checks(&mut HttpHarness { server }).await;
+    }
+}
@@ -0,0 +1,442 @@
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Result};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::StatusCode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Longest response body kept in error messages
+const MAX_ERROR_BODY: usize = 512;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpDestinationConfig {
+    /// URL the records are POSTed to
+    pub url: String,
+
+    /// Send one request per batch or per record
+    #[serde(default)]
+    pub mode: SendMode,
+
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Token sent as `Authorization: Bearer <token>`
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub bearer_token: Option<String>,
+
+    /// Key for signing request bodies with HMAC-SHA256
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub hmac_secret: Option<String>,
+
+    /// Header carrying the signature (`sha256=<hex>`)
+    #[serde(default = "default_signature_header")]
+    pub signature_header: String,
+
+    /// Timeout of a whole request in seconds
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Timeout for establishing a connection in seconds
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// Retries after a 5xx or 429 response or a failed connection
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before the first retry in milliseconds; doubled for each further retry
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_signature_header() -> String {
+    "X-Signature-256".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SendMode {
+    /// One request per batch with a JSON array of records
+    #[default]
+    Batch,
+    /// One request per record with a JSON object
+    Record,
+}
+
+/// Why a request failed, and whether sending it again may help
+struct RequestError {
+    message: String,
+    retryable: bool,
+}
+
+pub struct HttpDestination {
+    config: HttpDestinationConfig,
+    client: Option<reqwest::Client>,
+    status: DestinationStatus,
+}
+
+impl HttpDestination {
+    pub fn new(config: HttpDestinationConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Headers sent with every request: JSON content type, custom headers and bearer token
+    fn default_headers(&self) -> Result<HeaderMap> {
+        let invalid =
+            |e: &dyn std::fmt::Display| Error::Configuration(format!("Invalid header: {}", e));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in &self.config.headers {
+            let name = HeaderName::try_from(name.as_str()).map_err(|e| invalid(&e))?;
+            let value = HeaderValue::try_from(value.as_str()).map_err(|e| invalid(&e))?;
+            headers.insert(name, value);
+        }
+        if let Some(token) = &self.config.bearer_token {
+            let mut value =
+                HeaderValue::try_from(format!("Bearer {}", token)).map_err(|e| invalid(&e))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
+    /// `sha256=<hex>` HMAC of `body`, when a secret is configured
+    fn signature(&self, body: &[u8]) -> Option<String> {
+        let secret = self.config.hmac_secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        Some(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    /// Send `body` once
+    async fn send(&self, body: &[u8]) -> std::result::Result<(), RequestError> {
+        let client = self.client.as_ref().ok_or_else(|| RequestError {
+            message: "Not connected".to_string(),
+            retryable: false,
+        })?;
+
+        let mut request = client.post(&self.config.url).body(body.to_vec());
+        if let Some(signature) = self.signature(body) {
+            request = request.header(&self.config.signature_header, signature);
+        }
+
+        let response = request.send().await.map_err(|e| RequestError {
+            message: format!("Request failed: {}", e),
+            // A timed out request may have been processed; receivers dedupe by record ID
+            retryable: e.is_connect() || e.is_timeout(),
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let mut body = response.text().await.unwrap_or_default();
+        if body.len() > MAX_ERROR_BODY {
+            let mut end = MAX_ERROR_BODY;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            body.truncate(end);
+        }
+        Err(RequestError {
+            message: format!("HTTP {}: {}", status, body.trim()),
+            retryable: status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        })
+    }
+
+    /// Send `body`, retrying with exponential backoff while the failure is retryable
+    async fn send_with_retries(&self, body: &[u8]) -> Result<()> {
+        let mut backoff = Duration::from_millis(self.config.retry_backoff_ms);
+        let mut attempt = 0;
+        loop {
+            match self.send(body).await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.retryable && attempt < self.config.max_retries => {
+                    attempt += 1;
+                    warn!(
+                        "{} (retry {}/{} in {:?})",
+                        e.message, attempt, self.config.max_retries, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => return Err(Error::Connection(e.message)),
+            }
+        }
+    }
+
+    fn record_failure(&mut self, e: &Error) {
+        self.status.errors += 1;
+        self.status.consecutive_errors += 1;
+        self.status.last_error = Some(e.to_string());
+    }
+}
+
+#[async_trait]
+impl Destination for HttpDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting HTTP destination: {}",
+            redact_url(&self.config.url)
+        );
+
+        reqwest::Url::parse(&self.config.url)
+            .map_err(|e| Error::Configuration(format!("Invalid URL: {}", e)))?;
+        let client = reqwest::Client::builder()
+            .default_headers(self.default_headers()?)
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .connect_timeout(Duration::from_secs(self.config.connect_timeout_secs))
+            .build()
+            .map_err(|e| Error::Connection(format!("Failed to create HTTP client: {}", e)))?;
+
+        // Webhook endpoints usually only accept POSTs, so there is nothing to probe
+        self.client = Some(client);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting HTTP destination");
+        self.client = None;
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.client.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        match self.config.mode {
+            SendMode::Batch => {
+                let body = serde_json::to_vec(&records)?;
+                debug!("Sending batch of {} records", records.len());
+                if let Err(e) = self.send_with_retries(&body).await {
+                    error!("Failed to send batch of {} records: {}", records.len(), e);
+                    self.record_failure(&e);
+                    return Err(e);
+                }
+                self.status.records_written += records.len() as u64;
+            }
+            SendMode::Record => {
+                // Records sent before a failure stay delivered and counted
+                for record in &records {
+                    let body = serde_json::to_vec(record)?;
+                    if let Err(e) = self.send_with_retries(&body).await {
+                        error!("Failed to send record {}: {}", record.id, e);
+                        self.record_failure(&e);
+                        return Err(e);
+                    }
+                    self.status.records_written += 1;
+                }
+            }
+        }
+
+        self.status.consecutive_errors = 0;
+        info!("Successfully sent {} records", records.len());
+        Ok(())
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+    use cdc_core::testing::sample_record;
+
+    fn config(server: &MockServer) -> HttpDestinationConfig {
+        serde_json::from_value(serde_json::json!({
+            "url": server.url(),
+            "retry_backoff_ms": 1,
+        }))
+        .unwrap()
+    }
+
+    async fn connected(config: HttpDestinationConfig) -> HttpDestination {
+        let mut destination = HttpDestination::new(config);
+        destination.connect().await.unwrap();
+        destination
+    }
+
+    #[tokio::test]
+    async fn test_batch_sends_records_with_headers() {
+        let server = MockServer::start().await;
+        let mut config = config(&server);
+        config
+            .headers
+            .insert("X-Team".to_string(), "search".to_string());
+        config.bearer_token = Some("token".to_string());
+        let mut destination = connected(config).await;
+
+        let records = vec![sample_record("users", 1), sample_record("users", 2)];
+        destination.write_batch(records.clone()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        let headers = &requests[0].headers;
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(headers["x-team"], "search");
+        assert_eq!(headers["authorization"], "Bearer token");
+        assert!(headers.get("x-signature-256").is_none());
+
+        let sent: Vec<DataRecord> = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].id, records[1].id);
+        assert_eq!(destination.status().records_written, 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_mode_sends_one_request_per_record() {
+        let server = MockServer::start().await;
+        let mut config = config(&server);
+        config.mode = SendMode::Record;
+        let mut destination = connected(config).await;
+
+        let records = vec![sample_record("users", 1), sample_record("users", 2)];
+        destination.write_batch(records).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].json()["record"], "{\"id\":2}");
+    }
+
+    #[tokio::test]
+    async fn test_hmac_signature() {
+        let server = MockServer::start().await;
+        let mut config = config(&server);
+        config.hmac_secret = Some("secret".to_string());
+        let mut destination = connected(config).await;
+
+        destination.write(sample_record("users", 1)).await.unwrap();
+
+        let request = &server.requests()[0];
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(&request.body);
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert_eq!(request.headers["x-signature-256"], expected.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let server = MockServer::start().await;
+        server.respond_with([503, 500]);
+        let mut destination = connected(config(&server)).await;
+
+        destination.write(sample_record("users", 1)).await.unwrap();
+
+        assert_eq!(server.requests().len(), 3);
+        let status = destination.status();
+        assert_eq!(status.records_written, 1);
+        assert_eq!(status.errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        server.respond_with([500, 500, 500]);
+        let mut config = config(&server);
+        config.max_retries = 2;
+        let mut destination = connected(config).await;
+
+        assert!(destination.write(sample_record("users", 1)).await.is_err());
+        assert_eq!(server.requests().len(), 3);
+
+        let status = destination.status();
+        assert_eq!(status.errors, 1);
+        assert_eq!(status.consecutive_errors, 1);
+        assert!(status
+            .last_error
+            .unwrap()
+            .contains("HTTP 500 Internal Server Error: mock failure"));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        server.respond_with([400]);
+        let mut destination = connected(config(&server)).await;
+
+        let result = destination
+            .write_batch(vec![sample_record("users", 1)])
+            .await;
+        assert!(result.is_err());
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(destination.status().records_written, 0);
+        assert!(destination
+            .status()
+            .last_error
+            .unwrap()
+            .contains("HTTP 400 Bad Request"));
+
+        // The next successful batch resets the consecutive error count
+        destination
+            .write_batch(vec![sample_record("users", 2)])
+            .await
+            .unwrap();
+        let status = destination.status();
+        assert_eq!(status.consecutive_errors, 0);
+        assert_eq!(status.errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_header_fails_connect() {
+        let server = MockServer::start().await;
+        let mut config = config(&server);
+        config
+            .headers
+            .insert("Bad Header".to_string(), "x".to_string());
+
+        let mut destination = HttpDestination::new(config);
+        assert!(destination.connect().await.is_err());
+        assert!(!destination.is_connected());
+    }
+}
@@ -0,0 +1,7 @@
+mod factory;
+mod http_destination;
+#[cfg(test)]
+mod mock_server;
+
+pub use factory::HttpDestinationFactory;
+pub use http_destination::{HttpDestination, HttpDestinationConfig, SendMode};
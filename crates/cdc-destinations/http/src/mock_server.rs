@@ -0,0 +1,84 @@
+//! Local HTTP server recording webhook requests, for tests
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl ReceivedRequest {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("Request body is not JSON")
+    }
+}
+
+#[derive(Default)]
+struct ServerState {
+    requests: Vec<ReceivedRequest>,
+    /// Statuses to answer with, in order; 200 once exhausted
+    responses: VecDeque<StatusCode>,
+}
+
+/// Server answering every POST with the queued statuses, then 200
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<ServerState>>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let app = Router::new()
+            .route("/", post(handle))
+            .with_state(Arc::clone(&state));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock server");
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Self { addr, state }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Answer the next requests with `statuses`
+    pub fn respond_with(&self, statuses: impl IntoIterator<Item = u16>) {
+        let mut state = self.state.lock().unwrap();
+        state.responses.extend(
+            statuses
+                .into_iter()
+                .map(|status| StatusCode::from_u16(status).unwrap()),
+        );
+    }
+
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+async fn handle(
+    State(state): State<Arc<Mutex<ServerState>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, &'static str) {
+    let mut state = state.lock().unwrap();
+    state.requests.push(ReceivedRequest { headers, body });
+    match state.responses.pop_front() {
+        Some(status) => (status, "mock failure"),
+        None => (StatusCode::OK, "ok"),
+    }
+}
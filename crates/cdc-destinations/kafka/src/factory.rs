@@ -0,0 +1,70 @@
+use crate::{KafkaDestination, KafkaDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct KafkaDestinationFactory;
+
+impl DestinationFactory for KafkaDestinationFactory {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: KafkaDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(KafkaDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(KafkaDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = KafkaDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(
+            properties["sasl_password"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(
+            properties["ssl_key_password"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(
+            properties["topic_template"]["default"],
+            "cdc.{database}.{table}"
+        );
+        assert_eq!(properties["enable_idempotence"]["default"], true);
+        assert_eq!(properties["acks"]["default"], "all");
+    }
+
+    struct KafkaHarness {
+        brokers: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for KafkaHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            // Relies on the broker creating topics on first use
+            KafkaDestinationFactory.create(serde_json::json!({
+                "brokers": [self.brokers],
+                "topic_template": "cdc-conformance-{table}",
+            }))
+        }
+    }
+
+    /// `CONFORMANCE_KAFKA_BROKERS=localhost:9092 cargo test -p cdc-kafka-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a Kafka broker in CONFORMANCE_KAFKA_BROKERS"]
+    async fn test_conformance() {
+        let brokers = std::env::var("CONFORMANCE_KAFKA_BROKERS")
+            .expect("CONFORMANCE_KAFKA_BROKERS is not set");
+        cdc_conformance::run_destination_checks(&mut KafkaHarness { brokers }).await;
+    }
+}
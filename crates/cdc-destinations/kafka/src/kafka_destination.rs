@@ -0,0 +1,544 @@
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Result};
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::types::RDKafkaErrorCode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// How long `connect` waits for cluster metadata before giving up
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `disconnect` waits for queued messages to be delivered
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause before retrying a send while the producer queue is full
+const QUEUE_FULL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Separator between primary key values in composite message keys
+const KEY_SEPARATOR: &str = "|";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KafkaDestinationConfig {
+    /// Kafka brokers (host:port)
+    #[serde(default = "default_brokers")]
+    pub brokers: Vec<String>,
+
+    /// Topic template; `{database}` and `{table}` are replaced
+    #[serde(default = "default_topic_template")]
+    pub topic_template: String,
+
+    /// Fields forming the message key; composite keys are joined with `|`
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// Use the idempotent producer (no duplicates or reordering on retries)
+    #[serde(default = "default_enable_idempotence")]
+    pub enable_idempotence: bool,
+
+    /// Broker acknowledgements required for a write; must be `all` with idempotence
+    #[serde(default)]
+    pub acks: Acks,
+
+    /// How long the producer waits to fill a batch, in milliseconds
+    #[serde(default = "default_linger_ms")]
+    pub linger_ms: u64,
+
+    /// How long a message may take to be delivered, retries included, in milliseconds
+    #[serde(default = "default_delivery_timeout_ms")]
+    pub delivery_timeout_ms: u64,
+
+    /// Protocol used to talk to the brokers
+    #[serde(default)]
+    pub security_protocol: SecurityProtocol,
+
+    /// SASL mechanism (PLAIN, SCRAM-SHA-256 or SCRAM-SHA-512)
+    #[serde(default)]
+    pub sasl_mechanism: Option<String>,
+
+    /// SASL username
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+
+    /// SASL password
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub sasl_password: Option<String>,
+
+    /// CA certificate (PEM file) used to verify the brokers
+    #[serde(default)]
+    pub ssl_ca_location: Option<String>,
+
+    /// Client certificate (PEM file) for mutual TLS
+    #[serde(default)]
+    pub ssl_certificate_location: Option<String>,
+
+    /// Client private key (PEM file) for mutual TLS
+    #[serde(default)]
+    pub ssl_key_location: Option<String>,
+
+    /// Password of the client private key
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub ssl_key_password: Option<String>,
+}
+
+fn default_brokers() -> Vec<String> {
+    vec!["localhost:9092".to_string()]
+}
+
+fn default_topic_template() -> String {
+    "cdc.{database}.{table}".to_string()
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+fn default_enable_idempotence() -> bool {
+    true
+}
+
+fn default_linger_ms() -> u64 {
+    5
+}
+
+fn default_delivery_timeout_ms() -> u64 {
+    30000 // 30 seconds
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Acks {
+    /// Wait for all in-sync replicas
+    #[default]
+    All,
+    /// Wait for the partition leader only
+    Leader,
+    /// Do not wait for the broker
+    None,
+}
+
+impl Acks {
+    fn as_str(self) -> &'static str {
+        match self {
+            Acks::All => "all",
+            Acks::Leader => "1",
+            Acks::None => "0",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityProtocol {
+    #[default]
+    Plaintext,
+    Ssl,
+    SaslPlaintext,
+    SaslSsl,
+}
+
+impl SecurityProtocol {
+    fn as_str(self) -> &'static str {
+        match self {
+            SecurityProtocol::Plaintext => "plaintext",
+            SecurityProtocol::Ssl => "ssl",
+            SecurityProtocol::SaslPlaintext => "sasl_plaintext",
+            SecurityProtocol::SaslSsl => "sasl_ssl",
+        }
+    }
+}
+
+impl Default for KafkaDestinationConfig {
+    fn default() -> Self {
+        Self {
+            brokers: default_brokers(),
+            topic_template: default_topic_template(),
+            primary_key: default_primary_key(),
+            enable_idempotence: true,
+            acks: Acks::All,
+            linger_ms: 5,
+            delivery_timeout_ms: 30000,
+            security_protocol: SecurityProtocol::Plaintext,
+            sasl_mechanism: None,
+            sasl_username: None,
+            sasl_password: None,
+            ssl_ca_location: None,
+            ssl_certificate_location: None,
+            ssl_key_location: None,
+            ssl_key_password: None,
+        }
+    }
+}
+
+impl KafkaDestinationConfig {
+    /// librdkafka settings for the producer
+    pub(crate) fn client_config(&self) -> Result<ClientConfig> {
+        if self.enable_idempotence && self.acks != Acks::All {
+            return Err(Error::Configuration(
+                "The idempotent producer requires acks to be 'all'".to_string(),
+            ));
+        }
+
+        let mut client = ClientConfig::new();
+        client
+            .set("bootstrap.servers", self.brokers.join(","))
+            .set("security.protocol", self.security_protocol.as_str())
+            .set("enable.idempotence", self.enable_idempotence.to_string())
+            .set("acks", self.acks.as_str())
+            .set("linger.ms", self.linger_ms.to_string())
+            .set("message.timeout.ms", self.delivery_timeout_ms.to_string());
+
+        let optional = [
+            ("sasl.mechanism", &self.sasl_mechanism),
+            ("sasl.username", &self.sasl_username),
+            ("sasl.password", &self.sasl_password),
+            ("ssl.ca.location", &self.ssl_ca_location),
+            ("ssl.certificate.location", &self.ssl_certificate_location),
+            ("ssl.key.location", &self.ssl_key_location),
+            ("ssl.key.password", &self.ssl_key_password),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                client.set(key, value);
+            }
+        }
+        Ok(client)
+    }
+}
+
+/// A record ready to be produced
+#[derive(Debug)]
+struct Message {
+    topic: String,
+    key: String,
+    payload: String,
+    timestamp: i64,
+}
+
+pub struct KafkaDestination {
+    config: KafkaDestinationConfig,
+    producer: Option<Arc<FutureProducer>>,
+    status: DestinationStatus,
+}
+
+impl KafkaDestination {
+    pub fn new(config: KafkaDestinationConfig) -> Self {
+        Self {
+            config,
+            producer: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Topic name part; Kafka only allows ASCII letters, digits, `.`, `_` and `-`
+    fn topic_part(name: &str) -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    /// Topic for `record` from the template
+    fn topic(&self, record: &DataRecord) -> Result<String> {
+        let template = &self.config.topic_template;
+        let mut topic = template.clone();
+
+        if template.contains("{table}") {
+            let table = record
+                .table_name()
+                .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+            topic = topic.replace("{table}", &Self::topic_part(&table));
+        }
+        if template.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Topic template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            topic = topic.replace("{database}", &Self::topic_part(&database));
+        }
+        Ok(topic)
+    }
+
+    /// Message key from the primary key fields, so all changes of a row share a partition
+    fn key(&self, record: &DataRecord) -> Result<String> {
+        let row: Map<String, Value> = serde_json::from_str(&record.record)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let values = self
+            .config
+            .primary_key
+            .iter()
+            .map(|key| match row.get(key) {
+                None | Some(Value::Null) => Err(Error::Generic(anyhow::anyhow!(
+                    "Record has no value for primary key field '{}'",
+                    key
+                ))),
+                Some(Value::String(value)) => Ok(value.clone()),
+                Some(value) => Ok(value.to_string()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(values.join(KEY_SEPARATOR))
+    }
+
+    fn message(&self, record: &DataRecord) -> Result<Message> {
+        Ok(Message {
+            topic: self.topic(record)?,
+            key: self.key(record)?,
+            payload: serde_json::to_string(record)?,
+            timestamp: record.timestamp.timestamp_millis(),
+        })
+    }
+
+    /// Produce every record and wait until each one is acknowledged
+    async fn produce(&self, records: &[DataRecord]) -> Result<()> {
+        // Build every message before sending so a bad record fails the batch up front
+        let messages = records
+            .iter()
+            .map(|record| self.message(record))
+            .collect::<Result<Vec<_>>>()?;
+
+        let producer = self
+            .producer
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        let mut deliveries = Vec::with_capacity(messages.len());
+        for message in &messages {
+            let mut record = FutureRecord::to(&message.topic)
+                .key(&message.key)
+                .payload(&message.payload)
+                .timestamp(message.timestamp);
+            loop {
+                match producer.send_result(record) {
+                    Ok(delivery) => {
+                        deliveries.push(delivery);
+                        break;
+                    }
+                    Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), rejected)) => {
+                        record = rejected;
+                        tokio::time::sleep(QUEUE_FULL_BACKOFF).await;
+                    }
+                    Err((e, _)) => {
+                        return Err(Error::Connection(format!(
+                            "Failed to enqueue message for topic '{}': {}",
+                            message.topic, e
+                        )));
+                    }
+                }
+            }
+        }
+
+        debug!("Waiting for {} deliveries", deliveries.len());
+        let results = futures::future::join_all(deliveries).await;
+        let mut failed = 0;
+        let mut first_error = None;
+        for result in results {
+            let error = match result {
+                Ok(Ok(_)) => continue,
+                Ok(Err((e, _))) => e.to_string(),
+                Err(_) => "delivery was cancelled".to_string(),
+            };
+            failed += 1;
+            first_error.get_or_insert(error);
+        }
+        match first_error {
+            None => Ok(()),
+            Some(error) => Err(Error::Connection(format!(
+                "{} of {} messages were not delivered, first: {}",
+                failed,
+                messages.len(),
+                error
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Destination for KafkaDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting to Kafka brokers: {:?} (topics={})",
+            self.config.brokers, self.config.topic_template
+        );
+
+        let producer: FutureProducer =
+            self.config.client_config()?.create().map_err(|e| {
+                Error::Connection(format!("Failed to create Kafka producer: {}", e))
+            })?;
+        let producer = Arc::new(producer);
+
+        // Fetching metadata fails fast on unreachable brokers or bad credentials
+        let probe = Arc::clone(&producer);
+        tokio::task::spawn_blocking(move || probe.client().fetch_metadata(None, METADATA_TIMEOUT))
+            .await
+            .map_err(|e| Error::Connection(format!("Kafka metadata task failed: {}", e)))?
+            .map_err(|e| Error::Connection(format!("Failed to connect to Kafka: {}", e)))?;
+
+        info!("Connected to Kafka successfully");
+        self.producer = Some(producer);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from Kafka");
+        self.status.connected = false;
+
+        let Some(producer) = self.producer.take() else {
+            return Ok(());
+        };
+
+        // write_batch waits for its deliveries, so this only covers interrupted batches
+        let result = tokio::task::spawn_blocking(move || producer.flush(FLUSH_TIMEOUT))
+            .await
+            .map_err(|e| Error::Connection(format!("Kafka disconnect task failed: {}", e)))?;
+        if let Err(e) = result {
+            warn!("Failed to flush Kafka producer: {}", e);
+        }
+
+        info!("Disconnected from Kafka");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.producer.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        match self.produce(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully produced batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to produce batch of {} records: {}",
+                    records.len(),
+                    e
+                );
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    #[test]
+    fn test_client_config() {
+        let config = KafkaDestinationConfig {
+            brokers: vec!["a:9092".to_string(), "b:9092".to_string()],
+            security_protocol: SecurityProtocol::SaslSsl,
+            sasl_password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let client = config.client_config().unwrap();
+
+        assert_eq!(client.get("bootstrap.servers"), Some("a:9092,b:9092"));
+        assert_eq!(client.get("security.protocol"), Some("sasl_ssl"));
+        assert_eq!(client.get("enable.idempotence"), Some("true"));
+        assert_eq!(client.get("acks"), Some("all"));
+        assert_eq!(client.get("sasl.password"), Some("secret"));
+        assert_eq!(client.get("sasl.username"), None);
+    }
+
+    #[test]
+    fn test_idempotence_requires_all_acks() {
+        let config = KafkaDestinationConfig {
+            acks: Acks::Leader,
+            ..Default::default()
+        };
+        assert!(config.client_config().is_err());
+
+        let config = KafkaDestinationConfig {
+            enable_idempotence: false,
+            ..config
+        };
+        assert_eq!(config.client_config().unwrap().get("acks"), Some("1"));
+    }
+
+    #[test]
+    fn test_message_topic_key_and_payload() {
+        let destination = KafkaDestination::new(KafkaDestinationConfig::default());
+        let record = sample_record("users", 7);
+
+        let message = destination.message(&record).unwrap();
+        assert_eq!(message.topic, "cdc.test.users");
+        assert_eq!(message.key, "7");
+        assert_eq!(message.timestamp, record.timestamp.timestamp_millis());
+
+        let produced: DataRecord = serde_json::from_str(&message.payload).unwrap();
+        assert_eq!(produced.id, record.id);
+        assert_eq!(produced.record, record.record);
+    }
+
+    #[test]
+    fn test_topic_sanitizes_names() {
+        let destination = KafkaDestination::new(KafkaDestinationConfig {
+            topic_template: "cdc-{table}".to_string(),
+            ..Default::default()
+        });
+        let record = DataRecord::new(
+            json!({ "id": 1 }).to_string(),
+            json!({ "table_name": "public.order items" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert_eq!(
+            destination.topic(&record).unwrap(),
+            "cdc-public.order_items"
+        );
+
+        let destination = KafkaDestination::new(KafkaDestinationConfig::default());
+        assert!(destination.topic(&record).is_err());
+    }
+
+    #[test]
+    fn test_composite_key() {
+        let destination = KafkaDestination::new(KafkaDestinationConfig {
+            primary_key: vec!["tenant".to_string(), "id".to_string()],
+            ..Default::default()
+        });
+        let record = DataRecord::new(
+            json!({ "tenant": "acme", "id": 3 }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert_eq!(destination.key(&record).unwrap(), "acme|3");
+        assert!(destination.key(&sample_record("users", 1)).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod factory;
+mod kafka_destination;
+
+pub use factory::KafkaDestinationFactory;
+pub use kafka_destination::{Acks, KafkaDestination, KafkaDestinationConfig, SecurityProtocol};
@@ -0,0 +1,60 @@
+use crate::{MongodbDestination, MongodbDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct MongodbDestinationFactory;
+
+impl DestinationFactory for MongodbDestinationFactory {
+    fn name(&self) -> &str {
+        "mongodb"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: MongodbDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(MongodbDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(MongodbDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = MongodbDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["url"]["default"], "mongodb://localhost:27017");
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["database"]["default"], "cdc");
+        assert_eq!(properties["collection_template"]["default"], "{table}");
+        assert_eq!(
+            properties["primary_key"]["default"],
+            serde_json::json!(["id"])
+        );
+    }
+
+    struct MongodbHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for MongodbHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            MongodbDestinationFactory.create(serde_json::json!({ "url": self.url }))
+        }
+    }
+
+    /// `CONFORMANCE_MONGODB_URL=mongodb://localhost:27017 cargo test -p cdc-mongodb-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a MongoDB server in CONFORMANCE_MONGODB_URL"]
+    async fn test_conformance() {
+        let url =
+            std::env::var("CONFORMANCE_MONGODB_URL").expect("CONFORMANCE_MONGODB_URL is not set");
+        cdc_conformance::run_destination_checks(&mut MongodbHarness { url }).await;
+    }
+}
@@ -0,0 +1,7 @@
+mod factory;
+mod mongodb_destination;
+
+pub use factory::MongodbDestinationFactory;
+pub use mongodb_destination::{
+    MongodbDestination, MongodbDestinationConfig, WriteAcknowledgment, WriteConcernConfig,
+};
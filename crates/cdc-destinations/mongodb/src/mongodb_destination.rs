@@ -0,0 +1,702 @@
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::{
+    Acknowledgment, ClientOptions, DeleteOneModel, ReplaceOneModel, UpdateModifications,
+    UpdateOneModel, WriteConcern, WriteModel,
+};
+use mongodb::{Client, Namespace};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MongodbDestinationConfig {
+    /// MongoDB connection string (e.g., mongodb://localhost:27017)
+    #[serde(default = "default_url")]
+    #[schemars(extend("x-secret" = true))]
+    pub url: String,
+
+    /// Database the collections are written to
+    #[serde(default = "default_database")]
+    pub database: String,
+
+    /// Collection name template; `{table}` and `{database}` are replaced
+    #[serde(default = "default_collection_template")]
+    pub collection_template: String,
+
+    /// Fields forming the document `_id`; composite keys become an embedded document
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// Write concern for each batch; the server default is used when unset
+    #[serde(default)]
+    pub write_concern: WriteConcernConfig,
+
+    /// Send each batch as one client-level `bulkWrite`, which needs MongoDB 8.0+.
+    /// Otherwise batches are sent as `update` and `delete` commands per collection
+    #[serde(default)]
+    pub bulk_write: bool,
+}
+
+fn default_url() -> String {
+    "mongodb://localhost:27017".to_string()
+}
+
+fn default_database() -> String {
+    "cdc".to_string()
+}
+
+fn default_collection_template() -> String {
+    "{table}".to_string()
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WriteConcernConfig {
+    /// Acknowledgment required: a number of nodes, `majority` or a custom tag set name
+    #[serde(default)]
+    pub w: Option<WriteAcknowledgment>,
+
+    /// Wait for the write to reach the on-disk journal
+    #[serde(default)]
+    pub journal: Option<bool>,
+
+    /// Fail the batch if the acknowledgment is not reached within this time
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum WriteAcknowledgment {
+    /// Number of nodes
+    Nodes(u32),
+    /// `majority` or a custom tag set name
+    Tag(String),
+}
+
+impl Default for MongodbDestinationConfig {
+    fn default() -> Self {
+        Self {
+            url: default_url(),
+            database: default_database(),
+            collection_template: default_collection_template(),
+            primary_key: default_primary_key(),
+            write_concern: WriteConcernConfig::default(),
+            bulk_write: false,
+        }
+    }
+}
+
+/// Most statements sent in one `update` or `delete` command
+const MAX_COMMAND_STATEMENTS: usize = 1000;
+
+/// Most statement bytes sent in one command, well under the 16MB message limit
+const MAX_COMMAND_BYTES: usize = 8 * 1024 * 1024;
+
+/// Collection-level write command a model is sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommandKind {
+    Update,
+    Delete,
+}
+
+impl CommandKind {
+    fn name(self) -> &'static str {
+        match self {
+            CommandKind::Update => "update",
+            CommandKind::Delete => "delete",
+        }
+    }
+
+    /// Field of the command holding its statements
+    fn statements_field(self) -> &'static str {
+        match self {
+            CommandKind::Update => "updates",
+            CommandKind::Delete => "deletes",
+        }
+    }
+}
+
+/// One `update` or `delete` command against a collection
+#[derive(Debug, PartialEq)]
+struct WriteCommand {
+    namespace: Namespace,
+    kind: CommandKind,
+    statements: Vec<Document>,
+    /// Encoded size of the statements
+    bytes: usize,
+}
+
+impl WriteCommand {
+    fn into_document(self, write_concern: Option<&WriteConcern>) -> Result<Document> {
+        let mut command = doc! {
+            self.kind.name(): self.namespace.coll,
+            self.kind.statements_field(): self.statements,
+            "ordered": true,
+        };
+        if let Some(write_concern) = write_concern {
+            let write_concern = mongodb::bson::to_document(write_concern).map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to encode write concern: {}", e))
+            })?;
+            command.insert("writeConcern", write_concern);
+        }
+        Ok(command)
+    }
+}
+
+/// Command statement equivalent to a write model
+fn statement(model: WriteModel) -> (Namespace, CommandKind, Document) {
+    match model {
+        WriteModel::ReplaceOne(model) => (
+            model.namespace,
+            CommandKind::Update,
+            doc! {
+                "q": model.filter,
+                "u": model.replacement,
+                "upsert": model.upsert.unwrap_or(false),
+            },
+        ),
+        WriteModel::UpdateOne(model) => {
+            let UpdateModifications::Document(update) = model.update else {
+                unreachable!("only update documents are built")
+            };
+            (
+                model.namespace,
+                CommandKind::Update,
+                doc! {
+                    "q": model.filter,
+                    "u": update,
+                    "upsert": model.upsert.unwrap_or(false),
+                },
+            )
+        }
+        WriteModel::DeleteOne(model) => (
+            model.namespace,
+            CommandKind::Delete,
+            doc! { "q": model.filter, "limit": 1 },
+        ),
+        _ => unreachable!("only replaceOne, updateOne and deleteOne models are built"),
+    }
+}
+
+/// Group models into per-collection commands. Each collection's models keep their
+/// order: a command only holds a run of same-kind statements, so an update and a
+/// later delete of the same document are never reordered
+fn write_commands(models: Vec<WriteModel>) -> Vec<WriteCommand> {
+    let mut collections: Vec<(Namespace, Vec<WriteCommand>)> = Vec::new();
+    for model in models {
+        let (namespace, kind, statement) = statement(model);
+        let size = mongodb::bson::to_vec(&statement).map_or(0, |bytes| bytes.len());
+
+        let index = match collections.iter().position(|(ns, _)| *ns == namespace) {
+            Some(index) => index,
+            None => {
+                collections.push((namespace.clone(), Vec::new()));
+                collections.len() - 1
+            }
+        };
+        let commands = &mut collections[index].1;
+
+        match commands.last_mut() {
+            Some(command)
+                if command.kind == kind
+                    && command.statements.len() < MAX_COMMAND_STATEMENTS
+                    && command.bytes + size <= MAX_COMMAND_BYTES =>
+            {
+                command.statements.push(statement);
+                command.bytes += size;
+            }
+            _ => commands.push(WriteCommand {
+                namespace,
+                kind,
+                statements: vec![statement],
+                bytes: size,
+            }),
+        }
+    }
+
+    collections
+        .into_iter()
+        .flat_map(|(_, commands)| commands)
+        .collect()
+}
+
+/// Fail on write errors, which the server reports in an otherwise successful reply
+fn check_reply(reply: &Document) -> Result<()> {
+    if let Ok(errors) = reply.get_array("writeErrors") {
+        if let Some(Bson::Document(first)) = errors.first() {
+            return Err(Error::Connection(format!(
+                "Write failed for {} statement(s): {}",
+                errors.len(),
+                first.get_str("errmsg").unwrap_or("unknown error")
+            )));
+        }
+    }
+    if let Ok(error) = reply.get_document("writeConcernError") {
+        return Err(Error::Connection(format!(
+            "Write concern failed: {}",
+            error.get_str("errmsg").unwrap_or("unknown error")
+        )));
+    }
+    Ok(())
+}
+
+pub struct MongodbDestination {
+    config: MongodbDestinationConfig,
+    client: Option<Client>,
+    status: DestinationStatus,
+}
+
+impl MongodbDestination {
+    pub fn new(config: MongodbDestinationConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Namespace of the record's collection from the template
+    fn namespace(&self, record: &DataRecord) -> Result<Namespace> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+
+        let mut collection = self.config.collection_template.replace("{table}", &table);
+        if collection.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Collection template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            collection = collection.replace("{database}", &database);
+        }
+        Ok(Namespace::new(&self.config.database, collection))
+    }
+
+    fn to_bson(value: Value) -> Result<Bson> {
+        Bson::try_from(value)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to convert to BSON: {}", e)))
+    }
+
+    /// Document `_id` from the primary key fields
+    fn document_id(&self, row: &Map<String, Value>) -> Result<Bson> {
+        let mut key = Document::new();
+        for field in &self.config.primary_key {
+            match row.get(field) {
+                None | Some(Value::Null) => {
+                    return Err(Error::Generic(anyhow::anyhow!(
+                        "Record has no value for primary key field '{}'",
+                        field
+                    )))
+                }
+                Some(value) => {
+                    key.insert(field.clone(), Self::to_bson(value.clone())?);
+                }
+            }
+        }
+
+        // A single key is used as-is so `_id` matches the source column
+        if key.len() == 1 {
+            Ok(key.into_iter().next().map(|(_, value)| value).unwrap())
+        } else {
+            Ok(Bson::Document(key))
+        }
+    }
+
+    /// Write model applying `record`: inserts replace the document, updates `$set` the row's
+    /// fields so fields added outside the pipeline are kept, deletes remove it
+    fn model(&self, record: &DataRecord) -> Result<WriteModel> {
+        let namespace = self.namespace(record)?;
+        let mut row: Map<String, Value> = serde_json::from_str(&record.record)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        if operation == Operation::Update {
+            if let Ok(Some(changes)) = record.parse_changes() {
+                row.extend(changes);
+            }
+        }
+
+        let filter = doc! { "_id": self.document_id(&row)? };
+        let model = match operation {
+            Operation::Delete => DeleteOneModel::builder()
+                .namespace(namespace)
+                .filter(filter)
+                .build()
+                .into(),
+            Operation::Update => {
+                row.remove("_id");
+                UpdateOneModel::builder()
+                    .namespace(namespace)
+                    .filter(filter)
+                    .update(doc! { "$set": Self::to_bson(Value::Object(row))? })
+                    .upsert(true)
+                    .build()
+                    .into()
+            }
+            Operation::Insert | Operation::Snapshot => {
+                let Bson::Document(mut replacement) = Self::to_bson(Value::Object(row))? else {
+                    unreachable!("a JSON object converts to a BSON document")
+                };
+                replacement.insert("_id", filter.get("_id").cloned().unwrap());
+                ReplaceOneModel::builder()
+                    .namespace(namespace)
+                    .filter(filter)
+                    .replacement(replacement)
+                    .upsert(true)
+                    .build()
+                    .into()
+            }
+        };
+        Ok(model)
+    }
+
+    fn write_concern(&self) -> Option<WriteConcern> {
+        let config = &self.config.write_concern;
+        if config.w.is_none() && config.journal.is_none() && config.timeout_ms.is_none() {
+            return None;
+        }
+
+        let w = config.w.clone().map(|w| match w {
+            WriteAcknowledgment::Nodes(n) => Acknowledgment::Nodes(n),
+            WriteAcknowledgment::Tag(tag) => Acknowledgment::from(tag),
+        });
+        Some(
+            WriteConcern::builder()
+                .w(w)
+                .journal(config.journal)
+                .w_timeout(config.timeout_ms.map(Duration::from_millis))
+                .build(),
+        )
+    }
+
+    async fn write_records(&self, records: &[DataRecord]) -> Result<()> {
+        // Build every model before sending so a bad record fails the batch up front
+        let models = records
+            .iter()
+            .map(|record| self.model(record))
+            .collect::<Result<Vec<_>>>()?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        if self.config.bulk_write {
+            debug!("Sending bulk write of {} model(s)", models.len());
+            let mut bulk = client.bulk_write(models).ordered(true);
+            if let Some(write_concern) = self.write_concern() {
+                bulk = bulk.write_concern(write_concern);
+            }
+            bulk.await
+                .map_err(|e| Error::Connection(format!("Bulk write failed: {}", e)))?;
+            return Ok(());
+        }
+
+        let write_concern = self.write_concern();
+        for command in write_commands(models) {
+            let database = client.database(&command.namespace.db);
+            debug!(
+                "Sending {} of {} statement(s) to {}",
+                command.kind.name(),
+                command.statements.len(),
+                command.namespace
+            );
+            let reply = database
+                .run_command(command.into_document(write_concern.as_ref())?)
+                .await
+                .map_err(|e| Error::Connection(format!("Write failed: {}", e)))?;
+            check_reply(&reply)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Destination for MongodbDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting to MongoDB: {} (database={})",
+            redact_url(&self.config.url),
+            self.config.database
+        );
+
+        let options = ClientOptions::parse(&self.config.url)
+            .await
+            .map_err(|e| Error::Connection(format!("Invalid MongoDB URL: {}", e)))?;
+        let client = Client::with_options(options)
+            .map_err(|e| Error::Connection(format!("Failed to create MongoDB client: {}", e)))?;
+
+        client
+            .database("admin")
+            .run_command(doc! { "ping": 1 })
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to connect to MongoDB: {}", e)))?;
+
+        info!("Connected to MongoDB successfully");
+        self.client = Some(client);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from MongoDB");
+
+        self.status.connected = false;
+        if let Some(client) = self.client.take() {
+            client.shutdown().await;
+        }
+
+        info!("Disconnected from MongoDB");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.client.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        match self.write_records(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    fn destination(config: MongodbDestinationConfig) -> MongodbDestination {
+        MongodbDestination::new(config)
+    }
+
+    #[test]
+    fn test_namespace_from_template() {
+        let record = sample_record("users", 1);
+        assert_eq!(
+            destination(MongodbDestinationConfig::default())
+                .namespace(&record)
+                .unwrap(),
+            Namespace::new("cdc", "users")
+        );
+
+        let destination = destination(MongodbDestinationConfig {
+            database: "mirror".to_string(),
+            collection_template: "{database}_{table}".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            destination.namespace(&record).unwrap(),
+            Namespace::new("mirror", "test_users")
+        );
+    }
+
+    #[test]
+    fn test_insert_replaces_by_primary_key() {
+        let destination = destination(MongodbDestinationConfig::default());
+        let record = DataRecord::new(
+            json!({ "id": 7, "name": "a" }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+
+        let WriteModel::ReplaceOne(model) = destination.model(&record).unwrap() else {
+            panic!("expected replaceOne");
+        };
+        assert_eq!(model.namespace, Namespace::new("cdc", "users"));
+        assert_eq!(model.filter, doc! { "_id": 7 });
+        assert_eq!(model.replacement, doc! { "id": 7, "name": "a", "_id": 7 });
+        assert_eq!(model.upsert, Some(true));
+    }
+
+    #[test]
+    fn test_update_sets_merged_fields() {
+        let destination = destination(MongodbDestinationConfig {
+            primary_key: vec!["tenant".to_string(), "id".to_string()],
+            ..Default::default()
+        });
+        let record = DataRecord::new(
+            json!({ "tenant": "acme", "id": 7, "name": "a" }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "update".to_string(),
+            Some(json!({ "name": "b" }).to_string()),
+        );
+
+        let WriteModel::UpdateOne(model) = destination.model(&record).unwrap() else {
+            panic!("expected updateOne");
+        };
+        assert_eq!(model.filter, doc! { "_id": { "tenant": "acme", "id": 7 } });
+        let mongodb::options::UpdateModifications::Document(update) = model.update else {
+            panic!("expected an update document");
+        };
+        assert_eq!(
+            update,
+            doc! { "$set": { "tenant": "acme", "id": 7, "name": "b" } }
+        );
+        assert_eq!(model.upsert, Some(true));
+    }
+
+    #[test]
+    fn test_delete_by_primary_key() {
+        let destination = destination(MongodbDestinationConfig::default());
+        let mut record = sample_record("users", 3);
+        record.action = "delete".to_string();
+
+        let WriteModel::DeleteOne(model) = destination.model(&record).unwrap() else {
+            panic!("expected deleteOne");
+        };
+        assert_eq!(model.filter, doc! { "_id": 3 });
+
+        let record = DataRecord::new(
+            json!({ "name": "a" }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "delete".to_string(),
+            None,
+        );
+        assert!(destination.model(&record).is_err());
+    }
+
+    #[test]
+    fn test_write_commands_per_collection_in_order() {
+        let destination = destination(MongodbDestinationConfig::default());
+        let mut deleted = sample_record("users", 1);
+        deleted.action = "delete".to_string();
+        let records = [
+            sample_record("users", 1),
+            sample_record("orders", 2),
+            sample_record("users", 3),
+            deleted,
+            sample_record("users", 1),
+        ];
+        let models = records
+            .iter()
+            .map(|record| destination.model(record).unwrap())
+            .collect();
+
+        let commands = write_commands(models);
+        let summary: Vec<_> = commands
+            .iter()
+            .map(|command| {
+                (
+                    command.namespace.coll.as_str(),
+                    command.kind,
+                    command.statements.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("users", CommandKind::Update, 2),
+                ("users", CommandKind::Delete, 1),
+                ("users", CommandKind::Update, 1),
+                ("orders", CommandKind::Update, 1),
+            ]
+        );
+        assert_eq!(
+            commands[1].statements[0],
+            doc! { "q": { "_id": 1 }, "limit": 1 }
+        );
+        assert_eq!(commands[0].statements[0].get_bool("upsert"), Ok(true));
+    }
+
+    #[test]
+    fn test_write_command_document() {
+        let destination = destination(MongodbDestinationConfig::default());
+        let mut record = sample_record("users", 1);
+        record.action = "delete".to_string();
+        let mut commands = write_commands(vec![destination.model(&record).unwrap()]);
+
+        let write_concern = WriteConcern::builder().w(Acknowledgment::Majority).build();
+        let command = commands
+            .remove(0)
+            .into_document(Some(&write_concern))
+            .unwrap();
+        assert_eq!(
+            command,
+            doc! {
+                "delete": "users",
+                "deletes": [{ "q": { "_id": 1 }, "limit": 1 }],
+                "ordered": true,
+                "writeConcern": { "w": "majority" },
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_reply_reports_write_errors() {
+        assert!(check_reply(&doc! { "ok": 1, "n": 2 }).is_ok());
+
+        let reply = doc! {
+            "ok": 1,
+            "n": 0,
+            "writeErrors": [{ "index": 0, "code": 11000, "errmsg": "duplicate key" }],
+        };
+        let error = check_reply(&reply).unwrap_err().to_string();
+        assert!(error.contains("duplicate key"), "{}", error);
+
+        let reply = doc! { "ok": 1, "writeConcernError": { "code": 64, "errmsg": "timed out" } };
+        assert!(check_reply(&reply).is_err());
+    }
+
+    #[test]
+    fn test_write_concern() {
+        assert!(destination(MongodbDestinationConfig::default())
+            .write_concern()
+            .is_none());
+
+        let config: MongodbDestinationConfig = serde_json::from_value(json!({
+            "write_concern": { "w": "majority", "journal": true, "timeout_ms": 500 }
+        }))
+        .unwrap();
+        let write_concern = destination(config).write_concern().unwrap();
+        assert_eq!(write_concern.w, Some(Acknowledgment::Majority));
+        assert_eq!(write_concern.journal, Some(true));
+        assert_eq!(write_concern.w_timeout, Some(Duration::from_millis(500)));
+
+        let config: MongodbDestinationConfig =
+            serde_json::from_value(json!({ "write_concern": { "w": 2 } })).unwrap();
+        assert_eq!(
+            destination(config).write_concern().unwrap().w,
+            Some(Acknowledgment::Nodes(2))
+        );
+    }
+}
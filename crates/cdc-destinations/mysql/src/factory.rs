@@ -13,4 +13,54 @@ impl DestinationFactory for MysqlDestinationFactory {
         let config: MysqlConfig = serde_json::from_value(config)?;
         Ok(Box::new(MysqlDestination::new(config)))
     }
+
+    fn config_schema(&self) -> Value {
+        MysqlConfig::config_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = MysqlDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["password"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["max_connections"]["default"], 10);
+        assert_eq!(properties["conflict_resolution"]["default"], "upsert");
+    }
+
+    struct MysqlHarness {
+        url: String,
+        database: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for MysqlHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            MysqlDestinationFactory.create(serde_json::json!({
+                "url": self.url,
+                "database": self.database,
+            }))
+        }
+    }
+
+    /// `CONFORMANCE_MYSQL_URL=mysql://root@localhost:3306/cdc cargo test -p cdc-mysql-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a MySQL server in CONFORMANCE_MYSQL_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_MYSQL_URL")
+            .expect("CONFORMANCE_MYSQL_URL is not set");
+        let database = url
+            .rsplit('/')
+            .next()
+            .and_then(|path| path.split('?').next())
+            .unwrap_or_default()
+            .to_string();
+        cdc_conformance::run_destination_checks(&mut MysqlHarness { url, database }).await;
+    }
 }
@@ -1,18 +1,142 @@
 use async_trait::async_trait;
-use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Result};
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info};
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+use tracing::{debug, error, info, warn};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MysqlConfig {
-    /// MySQL connection URL
+    /// MySQL connection URL (built from individual fields or provided directly)
     pub url: String,
-    
+
     /// Maximum number of connections in the pool
+    #[serde(default = "default_max_connections")]
     pub max_connections: u32,
-    
+
     /// Target database name
     pub database: String,
+
+    /// Conflict resolution strategy
+    #[serde(default)]
+    pub conflict_resolution: ConflictResolution,
+
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    pub auto_create_tables: bool,
+
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    pub auto_add_columns: bool,
+}
+
+fn default_max_connections() -> u32 {
+    10
+}
+
+fn default_auto_create_tables() -> bool {
+    true
+}
+
+fn default_auto_add_columns() -> bool {
+    true
+}
+
+/// Accepted input format for `MysqlConfig`: either a direct `url` or the
+/// individual connection fields
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "MysqlConfig")]
+struct MysqlConfigHelper {
+    /// MySQL connection URL (takes precedence over the individual fields)
+    #[schemars(extend("x-secret" = true))]
+    url: Option<String>,
+
+    /// Database host (used with `username` when `url` is not set)
+    host: Option<String>,
+    /// Database port (default 3306)
+    port: Option<u16>,
+    /// Database user
+    username: Option<String>,
+    /// Database password
+    #[schemars(extend("x-secret" = true))]
+    password: Option<String>,
+
+    /// Target database name
+    database: String,
+    /// Maximum number of connections in the pool
+    #[serde(default = "default_max_connections")]
+    max_connections: u32,
+    /// Conflict resolution strategy
+    #[serde(default)]
+    conflict_resolution: ConflictResolution,
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    auto_create_tables: bool,
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    auto_add_columns: bool,
+}
+
+impl MysqlConfig {
+    /// JSON Schema of the accepted configuration input
+    pub fn config_schema() -> serde_json::Value {
+        schemars::schema_for!(MysqlConfigHelper).to_value()
+    }
+}
+
+impl<'de> Deserialize<'de> for MysqlConfig {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let helper = MysqlConfigHelper::deserialize(deserializer)?;
+
+        // Build URL from either direct URL or individual fields
+        let url = if let Some(url) = helper.url {
+            // Direct URL provided
+            url
+        } else if let (Some(host), Some(username)) = (helper.host, helper.username) {
+            // Build URL from individual fields
+            let port = helper.port.unwrap_or(3306);
+            let password = helper.password.unwrap_or_default();
+
+            if password.is_empty() {
+                format!("mysql://{}@{}:{}/{}", username, host, port, helper.database)
+            } else {
+                format!(
+                    "mysql://{}:{}@{}:{}/{}",
+                    username, password, host, port, helper.database
+                )
+            }
+        } else {
+            return Err(D::Error::custom(
+                "Either 'url' or both 'host' and 'username' must be provided",
+            ));
+        };
+
+        Ok(MysqlConfig {
+            url,
+            max_connections: helper.max_connections,
+            database: helper.database,
+            conflict_resolution: helper.conflict_resolution,
+            auto_create_tables: helper.auto_create_tables,
+            auto_add_columns: helper.auto_add_columns,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// Use INSERT ... ON DUPLICATE KEY UPDATE (upsert)
+    #[default]
+    Upsert,
+    /// Replace existing records (REPLACE INTO)
+    Replace,
+    /// Ignore conflicts (INSERT IGNORE)
+    Ignore,
 }
 
 impl Default for MysqlConfig {
@@ -21,13 +145,16 @@ impl Default for MysqlConfig {
             url: "mysql://localhost/cdc".to_string(),
             max_connections: 10,
             database: "cdc".to_string(),
+            conflict_resolution: ConflictResolution::Upsert,
+            auto_create_tables: true,
+            auto_add_columns: true,
         }
     }
 }
 
 pub struct MysqlDestination {
     config: MysqlConfig,
-    // pool: Option<MySqlPool>,  // Uncomment when using sqlx
+    pool: Option<MySqlPool>,
     status: DestinationStatus,
 }
 
@@ -35,66 +162,638 @@ impl MysqlDestination {
     pub fn new(config: MysqlConfig) -> Self {
         Self {
             config,
+            pool: None,
             status: DestinationStatus::default(),
         }
     }
+
+    /// Quote identifier with backticks, doubling any backtick inside it
+    fn quote_identifier(identifier: &str) -> String {
+        format!("`{}`", identifier.replace('`', "``"))
+    }
+
+    /// Infer MySQL type from JSON value
+    fn infer_mysql_type(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Null => "TEXT".to_string(),
+            serde_json::Value::Bool(_) => "BOOLEAN".to_string(),
+            serde_json::Value::Number(n) => {
+                if n.is_i64() || n.is_u64() {
+                    "BIGINT".to_string()
+                } else {
+                    "DOUBLE".to_string()
+                }
+            }
+            serde_json::Value::String(s) => {
+                let trimmed = s.trim();
+
+                // Check if it's a UUID (format: xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)
+                if trimmed.len() == 36
+                    && trimmed.chars().filter(|c| *c == '-').count() == 4
+                    && trimmed.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+                {
+                    return "CHAR(36)".to_string();
+                }
+
+                // Check if string content is JSON (starts with { or [)
+                if (trimmed.starts_with('{') && trimmed.ends_with('}'))
+                    || (trimmed.starts_with('[') && trimmed.ends_with(']'))
+                {
+                    // Verify it's valid JSON by attempting to parse
+                    if serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
+                        return "JSON".to_string();
+                    }
+                }
+                "TEXT".to_string()
+            }
+            serde_json::Value::Array(_) => "JSON".to_string(),
+            serde_json::Value::Object(_) => "JSON".to_string(),
+        }
+    }
+
+    /// Ensure the schema metadata table exists
+    async fn ensure_schema_metadata_table(&self, pool: &MySqlPool) -> Result<()> {
+        let database = Self::quote_identifier(&self.config.database);
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {}.`_cdc_schema_metadata` (
+                schema_name VARCHAR(64) NOT NULL,
+                table_name VARCHAR(64) NOT NULL,
+                column_name VARCHAR(64) NOT NULL,
+                data_type VARCHAR(64) NOT NULL,
+                last_updated TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (schema_name, table_name, column_name)
+            )",
+            database
+        );
+
+        sqlx::query(&query).execute(pool).await.map_err(|e| {
+            Error::Generic(anyhow::anyhow!(
+                "Failed to create schema metadata table: {}",
+                e
+            ))
+        })?;
+
+        info!("Schema metadata table ensured");
+        Ok(())
+    }
+
+    /// Check if a table exists
+    async fn table_exists(&self, pool: &MySqlPool, table: &str) -> Result<bool> {
+        let query = "SELECT COUNT(*) FROM information_schema.tables
+                     WHERE table_schema = ? AND table_name = ?";
+
+        let count: (i64,) = sqlx::query_as(query)
+            .bind(&self.config.database)
+            .bind(table)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to check table existence: {}", e))
+            })?;
+
+        Ok(count.0 > 0)
+    }
+
+    /// Get current table schema from information_schema
+    async fn get_table_schema(
+        &self,
+        pool: &MySqlPool,
+        table: &str,
+    ) -> Result<std::collections::HashMap<String, String>> {
+        // information_schema columns may be binary strings; cast so they decode as text
+        let query = "SELECT CAST(column_name AS CHAR), CAST(column_type AS CHAR)
+                     FROM information_schema.columns
+                     WHERE table_schema = ? AND table_name = ?
+                     ORDER BY ordinal_position";
+
+        let rows: Vec<(String, String)> = sqlx::query_as(query)
+            .bind(&self.config.database)
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to get table schema: {}", e)))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Update schema metadata cache
+    async fn update_schema_metadata(
+        &self,
+        pool: &MySqlPool,
+        table: &str,
+        columns: &std::collections::HashMap<String, String>,
+    ) -> Result<()> {
+        let database = Self::quote_identifier(&self.config.database);
+
+        // Delete existing metadata for this table
+        let delete_query = format!(
+            "DELETE FROM {}.`_cdc_schema_metadata`
+             WHERE schema_name = ? AND table_name = ?",
+            database
+        );
+        sqlx::query(&delete_query)
+            .bind(&self.config.database)
+            .bind(table)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                Error::Generic(anyhow::anyhow!(
+                    "Failed to delete old schema metadata: {}",
+                    e
+                ))
+            })?;
+
+        // Insert new metadata
+        for (column_name, data_type) in columns {
+            let insert_query = format!(
+                "INSERT INTO {}.`_cdc_schema_metadata`
+                 (schema_name, table_name, column_name, data_type, last_updated)
+                 VALUES (?, ?, ?, ?, NOW())",
+                database
+            );
+            sqlx::query(&insert_query)
+                .bind(&self.config.database)
+                .bind(table)
+                .bind(column_name)
+                .bind(data_type)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    Error::Generic(anyhow::anyhow!("Failed to insert schema metadata: {}", e))
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a new table with columns inferred from data
+    async fn create_table(
+        &self,
+        pool: &MySqlPool,
+        table: &str,
+        data: &std::collections::HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let database = Self::quote_identifier(&self.config.database);
+        let table_quoted = Self::quote_identifier(table);
+
+        // Build column definitions
+        let mut column_defs = Vec::new();
+        let mut column_types = std::collections::HashMap::new();
+
+        for (col_name, col_value) in data {
+            let mut col_type = Self::infer_mysql_type(col_value);
+            let col_quoted = Self::quote_identifier(col_name);
+
+            // Check for id column (case-insensitive) to set as PRIMARY KEY
+            if col_name.to_lowercase() == "id" {
+                // TEXT and JSON columns can't be keys without a prefix length
+                if col_type == "TEXT" || col_type == "JSON" {
+                    col_type = "VARCHAR(255)".to_string();
+                }
+                column_defs.push(format!("{} {} PRIMARY KEY", col_quoted, col_type));
+            } else {
+                column_defs.push(format!("{} {}", col_quoted, col_type));
+            }
+            column_types.insert(col_name.clone(), col_type);
+        }
+
+        let columns_str = column_defs.join(", ");
+        let query = format!(
+            "CREATE TABLE {}.{} ({})",
+            database, table_quoted, columns_str
+        );
+
+        info!("Creating table: {}", query);
+        sqlx::query(&query)
+            .execute(pool)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to create table: {}", e)))?;
+
+        // Update metadata
+        self.update_schema_metadata(pool, table, &column_types)
+            .await?;
+
+        info!("Table {} created successfully", table);
+        Ok(())
+    }
+
+    /// Add new columns to an existing table
+    async fn add_columns(
+        &self,
+        pool: &MySqlPool,
+        table: &str,
+        new_columns: Vec<(String, String)>,
+    ) -> Result<()> {
+        if new_columns.is_empty() {
+            return Ok(());
+        }
+
+        let database = Self::quote_identifier(&self.config.database);
+        let table_quoted = Self::quote_identifier(table);
+
+        for (col_name, col_type) in &new_columns {
+            let col_quoted = Self::quote_identifier(col_name);
+            let query = format!(
+                "ALTER TABLE {}.{} ADD COLUMN {} {}",
+                database, table_quoted, col_quoted, col_type
+            );
+
+            info!("Adding column: {}", query);
+            sqlx::query(&query).execute(pool).await.map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to add column {}: {}", col_name, e))
+            })?;
+        }
+
+        // Update metadata with new columns
+        let mut current_schema = self.get_table_schema(pool, table).await?;
+        for (col_name, col_type) in new_columns {
+            current_schema.insert(col_name, col_type);
+        }
+        self.update_schema_metadata(pool, table, &current_schema)
+            .await?;
+
+        info!(
+            "Table {} now has {} column(s)",
+            table,
+            current_schema.len()
+        );
+        Ok(())
+    }
+
+    /// Ensure table exists and has all required columns
+    async fn ensure_table_exists(&self, pool: &MySqlPool, record: &DataRecord) -> Result<()> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+
+        // Parse record data
+        let data = record
+            .parse_record()
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        // Check if table exists
+        let exists = self.table_exists(pool, &table).await?;
+
+        if !exists {
+            // Table doesn't exist
+            if self.config.auto_create_tables {
+                info!("Table {} does not exist, creating it", table);
+                self.create_table(pool, &table, &data).await?;
+            } else {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} does not exist and auto_create_tables is disabled",
+                    table
+                )));
+            }
+        } else {
+            // Table exists, check for missing columns
+            if self.config.auto_add_columns {
+                let current_schema = self.get_table_schema(pool, &table).await?;
+                let mut missing_columns = Vec::new();
+
+                for (col_name, col_value) in &data {
+                    // MySQL column names are case-insensitive
+                    let known = current_schema
+                        .keys()
+                        .any(|existing| existing.eq_ignore_ascii_case(col_name));
+                    if !known {
+                        let col_type = Self::infer_mysql_type(col_value);
+                        missing_columns.push((col_name.clone(), col_type));
+                    }
+                }
+
+                if !missing_columns.is_empty() {
+                    info!(
+                        "Detected {} missing column(s) in table {}",
+                        missing_columns.len(),
+                        table
+                    );
+                    self.add_columns(pool, &table, missing_columns).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn insert_record<'e, E>(&self, executor: E, record: &DataRecord) -> Result<()>
+    where
+        E: sqlx::MySqlExecutor<'e>,
+    {
+        let database = Self::quote_identifier(&self.config.database);
+        let table_name_str = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+        let table = Self::quote_identifier(&table_name_str);
+        let table_name = format!("{}.{}", database, table);
+
+        // Parse record data
+        let data = record
+            .parse_record()
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        info!("Executing operation {:?} on {}", operation, table_name);
+
+        match operation {
+            Operation::Insert | Operation::Snapshot | Operation::Update => {
+                // For all operations, insert and resolve duplicates by primary key
+                // For UPDATE, we'll merge changes into the full record
+
+                let mut final_data = data.clone();
+
+                // If it's an UPDATE, merge changes
+                if matches!(operation, Operation::Update) {
+                    if let Ok(Some(changes)) = record.parse_changes() {
+                        info!("Merging {} changed fields for UPDATE", changes.len());
+                        for (key, value) in changes {
+                            final_data.insert(key, value);
+                        }
+                    }
+                }
+
+                // Extract column names and values
+                let mut columns = Vec::new();
+                let mut placeholders = Vec::new();
+                let mut values: Vec<&serde_json::Value> = Vec::new();
+                let mut update_sets = Vec::new();
+
+                for (key, value) in final_data.iter() {
+                    let quoted = Self::quote_identifier(key);
+                    columns.push(quoted.clone());
+                    placeholders.push("?");
+                    values.push(value);
+
+                    if key.to_lowercase() != "id" {
+                        update_sets.push(format!("{} = new.{}", quoted, quoted));
+                    }
+                }
+
+                let columns_str = columns.join(", ");
+                let placeholders_str = placeholders.join(", ");
+
+                let query = match self.config.conflict_resolution {
+                    // Nothing besides the key to update
+                    ConflictResolution::Upsert if update_sets.is_empty() => {
+                        format!(
+                            "INSERT IGNORE INTO {} ({}) VALUES ({})",
+                            table_name, columns_str, placeholders_str
+                        )
+                    }
+                    ConflictResolution::Upsert => {
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({}) AS new ON DUPLICATE KEY UPDATE {}",
+                            table_name,
+                            columns_str,
+                            placeholders_str,
+                            update_sets.join(", ")
+                        )
+                    }
+                    ConflictResolution::Ignore => {
+                        format!(
+                            "INSERT IGNORE INTO {} ({}) VALUES ({})",
+                            table_name, columns_str, placeholders_str
+                        )
+                    }
+                    ConflictResolution::Replace => {
+                        format!(
+                            "REPLACE INTO {} ({}) VALUES ({})",
+                            table_name, columns_str, placeholders_str
+                        )
+                    }
+                };
+
+                info!("Executing upsert query: {}", query);
+                self.execute_query(executor, &query, &values).await?;
+            }
+            Operation::Delete => {
+                // For DELETE, extract ID and delete
+                let pk_value = data.get("id").or_else(|| data.get("Id"));
+
+                if let Some(val) = pk_value {
+                    let pk_column = Self::quote_identifier("id");
+                    let query = format!("DELETE FROM {} WHERE {} = ?", table_name, pk_column);
+                    debug!("Executing delete query: {}", query);
+                    self.execute_query(executor, &query, &[val]).await?;
+                } else {
+                    warn!(
+                        "Cannot delete record without ID column in table {}",
+                        table_name
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn execute_query<'e, E>(
+        &self,
+        executor: E,
+        query: &str,
+        values: &[&serde_json::Value],
+    ) -> Result<()>
+    where
+        E: sqlx::MySqlExecutor<'e>,
+    {
+        let mut query_builder = sqlx::query(query);
+
+        for value in values {
+            debug!("Binding value: {}", value);
+            match *value {
+                serde_json::Value::Number(n) => {
+                    if let Some(i) = n.as_i64() {
+                        query_builder = query_builder.bind(i);
+                    } else if let Some(u) = n.as_u64() {
+                        query_builder = query_builder.bind(u);
+                    } else if let Some(f) = n.as_f64() {
+                        query_builder = query_builder.bind(f);
+                    } else {
+                        query_builder = query_builder.bind((*value).clone());
+                    }
+                }
+                // MySQL parses UUID and JSON columns from their text form
+                serde_json::Value::String(s) => {
+                    query_builder = query_builder.bind(s.clone());
+                }
+                serde_json::Value::Bool(b) => {
+                    query_builder = query_builder.bind(*b);
+                }
+                serde_json::Value::Null => {
+                    query_builder = query_builder.bind(None::<String>);
+                }
+                _ => {
+                    query_builder = query_builder.bind((*value).clone());
+                }
+            }
+        }
+
+        query_builder
+            .execute(executor)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Database error: {}", e)))?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Destination for MysqlDestination {
     async fn connect(&mut self) -> Result<()> {
-        info!("Connecting to MySQL: {}", self.config.url);
-        
-        // TODO: Implement actual MySQL connection
-        // Example with sqlx:
-        // let pool = MySqlPoolOptions::new()
-        //     .max_connections(self.config.max_connections)
-        //     .connect(&self.config.url)
-        //     .await?;
-        // 
-        // self.pool = Some(pool);
-        
+        info!("Connecting to MySQL: {}", redact_url(&self.config.url));
+
+        let pool = MySqlPoolOptions::new()
+            .max_connections(self.config.max_connections)
+            .connect(&self.config.url)
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to connect to MySQL: {}", e)))?;
+
+        info!("Connected to MySQL successfully");
+
+        // Ensure schema metadata table exists
+        self.ensure_schema_metadata_table(&pool).await?;
+
+        self.pool = Some(pool);
         self.status.connected = true;
-        info!("Connected to MySQL successfully (stub implementation)");
-        
+
         Ok(())
     }
-    
+
     async fn disconnect(&mut self) -> Result<()> {
         info!("Disconnecting from MySQL");
+
+        if let Some(pool) = self.pool.take() {
+            pool.close().await;
+        }
+
         self.status.connected = false;
+        info!("Disconnected from MySQL");
+
         Ok(())
     }
-    
+
     fn is_connected(&self) -> bool {
         self.status.connected
     }
-    
+
     async fn write(&mut self, record: DataRecord) -> Result<()> {
-        // TODO: Implement single record write
-        debug!("Writing record to MySQL: {:?}", record.id);
-        self.status.records_written += 1;
-        Ok(())
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        // Ensure table exists and has all required columns
+        self.ensure_table_exists(pool, &record).await?;
+
+        match self.insert_record(pool, &record).await {
+            Ok(_) => {
+                self.status.records_written += 1;
+                Ok(())
+            }
+            Err(e) => {
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                error!("Failed to write record: {}", e);
+                Err(e)
+            }
+        }
     }
-    
+
     async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
-        // TODO: Implement batch write with transaction
-        // Example with sqlx:
-        // let mut tx = self.pool.as_ref().unwrap().begin().await?;
-        // 
-        // for record in &records {
-        //     let query = format!("INSERT INTO {} ...", record.table);
-        //     sqlx::query(&query).execute(&mut *tx).await?;
-        // }
-        // 
-        // tx.commit().await?;
-        
-        info!("Wrote batch of {} records to MySQL (stub)", records.len());
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        // Ensure all unique tables exist before processing batch (DDL commits implicitly)
+        let mut processed_tables = std::collections::HashSet::new();
+        for record in &records {
+            if let Some(table_name) = record.table_name() {
+                if !processed_tables.contains(&table_name) {
+                    self.ensure_table_exists(pool, record).await?;
+                    processed_tables.insert(table_name);
+                }
+            }
+        }
+
+        let mut transaction = pool
+            .begin()
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to begin transaction: {}", e)))?;
+
+        for record in &records {
+            if let Err(e) = self.insert_record(&mut *transaction, record).await {
+                let table_name = record.table_name().unwrap_or_else(|| "unknown".to_string());
+                let operation = record.operation();
+                error!(
+                    "Failed to write record to table '{}' (operation: {:?}): {}",
+                    table_name, operation, e
+                );
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+
+                transaction
+                    .rollback()
+                    .await
+                    .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to rollback: {}", e)))?;
+
+                return Err(e);
+            }
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
         self.status.records_written += records.len() as u64;
+        info!("Successfully wrote batch of {} records", records.len());
+
         Ok(())
     }
-    
+
     fn status(&self) -> DestinationStatus {
         self.status.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier_escapes_backticks() {
+        assert_eq!(MysqlDestination::quote_identifier("users"), "`users`");
+        assert_eq!(MysqlDestination::quote_identifier("we`ird"), "`we``ird`");
+    }
+
+    #[test]
+    fn test_infer_mysql_type() {
+        let infer = |value| MysqlDestination::infer_mysql_type(&value);
+        assert_eq!(infer(serde_json::json!(1)), "BIGINT");
+        assert_eq!(infer(serde_json::json!(1.5)), "DOUBLE");
+        assert_eq!(infer(serde_json::json!(true)), "BOOLEAN");
+        assert_eq!(
+            infer(serde_json::json!("550e8400-e29b-41d4-a716-446655440000")),
+            "CHAR(36)"
+        );
+        assert_eq!(infer(serde_json::json!("{\"a\":1}")), "JSON");
+        assert_eq!(infer(serde_json::json!({ "a": 1 })), "JSON");
+        assert_eq!(infer(serde_json::json!("hello")), "TEXT");
+    }
+
+    #[test]
+    fn test_config_from_fields() {
+        let config: MysqlConfig = serde_json::from_value(serde_json::json!({
+            "host": "db",
+            "username": "cdc",
+            "password": "secret",
+            "database": "app",
+        }))
+        .unwrap();
+
+        assert_eq!(config.url, "mysql://cdc:secret@db:3306/app");
+        assert_eq!(config.database, "app");
+        assert_eq!(config.max_connections, 10);
+    }
+}
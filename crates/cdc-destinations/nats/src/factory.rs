@@ -0,0 +1,61 @@
+use crate::{NatsDestination, NatsDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct NatsDestinationFactory;
+
+impl DestinationFactory for NatsDestinationFactory {
+    fn name(&self) -> &str {
+        "nats"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: NatsDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(NatsDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(NatsDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = NatsDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(
+            properties["password"][cdc_core::SECRET_SCHEMA_KEYWORD],
+            true
+        );
+        assert_eq!(properties["token"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(
+            properties["subject_template"]["default"],
+            "cdc.{database}.{table}.{op}"
+        );
+        assert_eq!(properties["use_jetstream"]["default"], false);
+    }
+
+    struct NatsHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for NatsHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            NatsDestinationFactory.create(serde_json::json!({ "servers": [self.url] }))
+        }
+    }
+
+    /// `CONFORMANCE_NATS_URL=nats://localhost:4222 cargo test -p cdc-nats-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a NATS server in CONFORMANCE_NATS_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_NATS_URL").expect("CONFORMANCE_NATS_URL is not set");
+        cdc_conformance::run_destination_checks(&mut NatsHarness { url }).await;
+    }
+}
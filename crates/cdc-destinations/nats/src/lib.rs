@@ -0,0 +1,5 @@
+mod factory;
+mod nats_destination;
+
+pub use factory::NatsDestinationFactory;
+pub use nats_destination::{NatsDestination, NatsDestinationConfig};
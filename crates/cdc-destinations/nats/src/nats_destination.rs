@@ -0,0 +1,336 @@
+use async_nats::header::NATS_MESSAGE_ID;
+use async_nats::{jetstream, Client, HeaderMap};
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::future::IntoFuture;
+use tracing::{debug, error, info};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NatsDestinationConfig {
+    /// NATS server URL(s)
+    #[serde(default = "default_servers")]
+    pub servers: Vec<String>,
+
+    /// Subject template; `{database}`, `{table}` and `{op}` are replaced
+    #[serde(default = "default_subject_template")]
+    pub subject_template: String,
+
+    /// Publish through JetStream and wait for the stream's acks
+    #[serde(default)]
+    pub use_jetstream: bool,
+
+    /// Optional username for authentication
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Optional password for authentication
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub password: Option<String>,
+
+    /// Optional token for authentication
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub token: Option<String>,
+}
+
+fn default_servers() -> Vec<String> {
+    vec!["nats://localhost:4222".to_string()]
+}
+
+fn default_subject_template() -> String {
+    "cdc.{database}.{table}.{op}".to_string()
+}
+
+impl Default for NatsDestinationConfig {
+    fn default() -> Self {
+        Self {
+            servers: default_servers(),
+            subject_template: default_subject_template(),
+            use_jetstream: false,
+            username: None,
+            password: None,
+            token: None,
+        }
+    }
+}
+
+pub struct NatsDestination {
+    config: NatsDestinationConfig,
+    client: Option<Client>,
+    jetstream: Option<jetstream::Context>,
+    status: DestinationStatus,
+}
+
+impl NatsDestination {
+    pub fn new(config: NatsDestinationConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            jetstream: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Subject token for a name; `.`, wildcards and whitespace would change the subject's shape
+    fn subject_token(name: &str) -> String {
+        name.chars()
+            .map(|c| match c {
+                '.' | '*' | '>' => '_',
+                c if c.is_whitespace() => '_',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Subject for `record` from the template
+    fn subject(&self, record: &DataRecord) -> Result<String> {
+        let template = &self.config.subject_template;
+        let mut subject = template.clone();
+
+        if template.contains("{table}") {
+            let table = record
+                .table_name()
+                .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+            subject = subject.replace("{table}", &Self::subject_token(&table));
+        }
+        if template.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Subject template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            subject = subject.replace("{database}", &Self::subject_token(&database));
+        }
+        let op = match record.operation() {
+            Operation::Insert => "insert",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+            Operation::Snapshot => "snapshot",
+        };
+        Ok(subject.replace("{op}", op))
+    }
+
+    /// Subject, headers and payload of every record, built before anything is published
+    fn messages(&self, records: &[DataRecord]) -> Result<Vec<(String, HeaderMap, Vec<u8>)>> {
+        records
+            .iter()
+            .map(|record| {
+                let subject = self.subject(record)?;
+                // Lets JetStream drop duplicates when a batch is published again
+                let mut headers = HeaderMap::new();
+                headers.insert(NATS_MESSAGE_ID, record.id.to_string().as_str());
+                Ok((subject, headers, serde_json::to_vec(record)?))
+            })
+            .collect()
+    }
+
+    async fn publish(&self, records: &[DataRecord]) -> Result<()> {
+        let messages = self.messages(records)?;
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        if let Some(jetstream) = &self.jetstream {
+            // Publish everything first, then wait for the acks together
+            let mut acks = Vec::with_capacity(messages.len());
+            for (subject, headers, payload) in messages {
+                let ack = jetstream
+                    .publish_with_headers(subject, headers, payload.into())
+                    .await
+                    .map_err(|e| Error::Connection(format!("Failed to publish: {}", e)))?;
+                acks.push(ack.into_future());
+            }
+            futures::future::try_join_all(acks)
+                .await
+                .map_err(|e| Error::Connection(format!("JetStream publish not acked: {}", e)))?;
+        } else {
+            for (subject, headers, payload) in messages {
+                debug!("Publishing to subject '{}'", subject);
+                client
+                    .publish_with_headers(subject, headers, payload.into())
+                    .await
+                    .map_err(|e| Error::Connection(format!("Failed to publish: {}", e)))?;
+            }
+            client
+                .flush()
+                .await
+                .map_err(|e| Error::Connection(format!("Failed to flush: {}", e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Destination for NatsDestination {
+    async fn connect(&mut self) -> Result<()> {
+        let servers: Vec<_> = self.config.servers.iter().map(|s| redact_url(s)).collect();
+        info!("Connecting to NATS servers: {:?}", servers);
+
+        // Build connection options with authentication if provided
+        let mut opts = async_nats::ConnectOptions::new();
+
+        if let Some(ref username) = self.config.username {
+            if let Some(ref password) = self.config.password {
+                info!("Using username/password authentication");
+                opts = opts.user_and_password(username.clone(), password.clone());
+            }
+        } else if let Some(ref token) = self.config.token {
+            info!("Using token authentication");
+            opts = opts.token(token.clone());
+        }
+
+        let client = opts
+            .connect(&self.config.servers)
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to connect to NATS: {}", e)))?;
+
+        info!("Connected to NATS successfully");
+
+        if self.config.use_jetstream {
+            self.jetstream = Some(jetstream::new(client.clone()));
+        }
+        self.client = Some(client);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from NATS");
+
+        self.jetstream = None;
+        self.status.connected = false;
+        if let Some(client) = self.client.take() {
+            client
+                .flush()
+                .await
+                .map_err(|e| Error::Connection(format!("Failed to flush: {}", e)))?;
+        }
+
+        info!("Disconnected from NATS");
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.client.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        match self.publish(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully published batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to publish batch of {} records: {}",
+                    records.len(),
+                    e
+                );
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    fn destination(subject_template: &str) -> NatsDestination {
+        NatsDestination::new(NatsDestinationConfig {
+            subject_template: subject_template.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_subject_from_template() {
+        let destination = destination("cdc.{database}.{table}.{op}");
+        let mut record = sample_record("users", 1);
+        assert_eq!(
+            destination.subject(&record).unwrap(),
+            "cdc.test.users.insert"
+        );
+
+        record.action = "DELETE".to_string();
+        assert_eq!(
+            destination.subject(&record).unwrap(),
+            "cdc.test.users.delete"
+        );
+    }
+
+    #[test]
+    fn test_subject_escapes_names() {
+        let destination = destination("cdc.{database}.{table}");
+        let record = DataRecord::new(
+            json!({ "id": 1 }).to_string(),
+            json!({ "table_name": "public.order items", "database_name": "shop*" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert_eq!(
+            destination.subject(&record).unwrap(),
+            "cdc.shop_.public_order_items"
+        );
+    }
+
+    #[test]
+    fn test_subject_requires_used_metadata() {
+        let record = DataRecord::new(
+            json!({ "id": 1 }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "insert".to_string(),
+            None,
+        );
+        assert!(destination("cdc.{database}.{table}")
+            .subject(&record)
+            .is_err());
+        assert_eq!(
+            destination("cdc.{table}").subject(&record).unwrap(),
+            "cdc.users"
+        );
+        assert_eq!(destination("cdc.all").subject(&record).unwrap(), "cdc.all");
+    }
+
+    #[test]
+    fn test_messages_carry_record_and_id() {
+        let destination = destination("cdc.{table}");
+        let record = sample_record("users", 1);
+
+        let messages = destination.messages(std::slice::from_ref(&record)).unwrap();
+        let (subject, headers, payload) = &messages[0];
+        assert_eq!(subject, "cdc.users");
+        assert_eq!(
+            headers.get(NATS_MESSAGE_ID).unwrap().as_str(),
+            record.id.to_string()
+        );
+
+        let published: DataRecord = serde_json::from_slice(payload).unwrap();
+        assert_eq!(published.id, record.id);
+        assert_eq!(published.record, record.record);
+        assert_eq!(published.metadata, record.metadata);
+    }
+}
@@ -13,4 +13,44 @@ impl DestinationFactory for PostgresDestinationFactory {
         let config: PostgresConfig = serde_json::from_value(config)?;
         Ok(Box::new(PostgresDestination::new(config)))
     }
+
+    fn config_schema(&self) -> Value {
+        PostgresConfig::config_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_marks_secrets_and_defaults() {
+        let schema = PostgresDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["password"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["max_connections"]["default"], 10);
+        assert_eq!(properties["conflict_resolution"]["default"], "upsert");
+    }
+
+    struct PostgresHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for PostgresHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            PostgresDestinationFactory.create(serde_json::json!({ "url": self.url }))
+        }
+    }
+
+    /// `CONFORMANCE_POSTGRES_URL=postgresql://... cargo test -p cdc-postgres-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a PostgreSQL server in CONFORMANCE_POSTGRES_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_POSTGRES_URL")
+            .expect("CONFORMANCE_POSTGRES_URL is not set");
+        cdc_conformance::run_destination_checks(&mut PostgresHarness { url }).await;
+    }
 }
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use tracing::{debug, error, info, warn};
@@ -46,6 +47,51 @@ fn default_auto_add_columns() -> bool {
     true
 }
 
+/// Accepted input format for `PostgresConfig`: either a direct `url` or the
+/// individual connection fields
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "PostgresConfig")]
+struct PostgresConfigHelper {
+    /// PostgreSQL connection URL (takes precedence over the individual fields)
+    #[schemars(extend("x-secret" = true))]
+    url: Option<String>,
+
+    /// Database host (used with `username` when `url` is not set)
+    host: Option<String>,
+    /// Database port (default 5432)
+    port: Option<u16>,
+    /// Database user
+    username: Option<String>,
+    /// Database password
+    #[schemars(extend("x-secret" = true))]
+    password: Option<String>,
+    /// Database name (default "postgres")
+    database: Option<String>,
+
+    /// Maximum number of connections in the pool
+    #[serde(default = "default_max_connections")]
+    max_connections: u32,
+    /// Target schema name
+    #[serde(default = "default_schema")]
+    schema: String,
+    /// Conflict resolution strategy
+    #[serde(default)]
+    conflict_resolution: ConflictResolution,
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    auto_create_tables: bool,
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    auto_add_columns: bool,
+}
+
+impl PostgresConfig {
+    /// JSON Schema of the accepted configuration input
+    pub fn config_schema() -> serde_json::Value {
+        schemars::schema_for!(PostgresConfigHelper).to_value()
+    }
+}
+
 impl<'de> Deserialize<'de> for PostgresConfig {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -53,31 +99,6 @@ impl<'de> Deserialize<'de> for PostgresConfig {
     {
         use serde::de::Error;
 
-        #[derive(Deserialize)]
-        struct PostgresConfigHelper {
-            // Direct URL format
-            url: Option<String>,
-
-            // Individual fields format
-            host: Option<String>,
-            port: Option<u16>,
-            username: Option<String>,
-            password: Option<String>,
-            database: Option<String>,
-
-            // Optional configuration
-            #[serde(default = "default_max_connections")]
-            max_connections: u32,
-            #[serde(default = "default_schema")]
-            schema: String,
-            #[serde(default)]
-            conflict_resolution: ConflictResolution,
-            #[serde(default = "default_auto_create_tables")]
-            auto_create_tables: bool,
-            #[serde(default = "default_auto_add_columns")]
-            auto_add_columns: bool,
-        }
-
         let helper = PostgresConfigHelper::deserialize(deserializer)?;
 
         // Build URL from either direct URL or individual fields
@@ -115,7 +136,7 @@ impl<'de> Deserialize<'de> for PostgresConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ConflictResolution {
     /// Use INSERT ... ON CONFLICT DO UPDATE (upsert)
@@ -270,7 +291,7 @@ impl PostgresDestination {
         Ok(rows.into_iter().collect())
     }
 
-    /// Get cached schema from metadata table
+    // Get cached schema from metadata table
 
     /// Update schema metadata cache
     async fn update_schema_metadata(
@@ -522,6 +543,13 @@ impl PostgresDestination {
                 let placeholders_str = placeholders.join(", ");
 
                 let query = match self.config.conflict_resolution {
+                    // Nothing besides the key to update
+                    ConflictResolution::Upsert if update_sets.is_empty() => {
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO NOTHING",
+                            table_name, columns_str, placeholders_str, pk_column
+                        )
+                    }
                     ConflictResolution::Upsert => {
                         format!(
                             "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO UPDATE SET {}",
@@ -641,7 +669,7 @@ impl PostgresDestination {
 #[async_trait]
 impl Destination for PostgresDestination {
     async fn connect(&mut self) -> Result<()> {
-        info!("Connecting to PostgreSQL: {}", self.config.url);
+        info!("Connecting to PostgreSQL: {}", redact_url(&self.config.url));
 
         let pool = PgPoolOptions::new()
             .max_connections(self.config.max_connections)
@@ -0,0 +1,56 @@
+use crate::{RedisDestination, RedisDestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct RedisDestinationFactory;
+
+impl DestinationFactory for RedisDestinationFactory {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: RedisDestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(RedisDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(RedisDestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = RedisDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["url"]["default"], "redis://localhost:6379");
+        assert_eq!(properties["url"][cdc_core::SECRET_SCHEMA_KEYWORD], true);
+        assert_eq!(properties["database"]["default"], 0);
+        assert_eq!(properties["key_template"]["default"], "{table}:{id}");
+        assert_eq!(properties["format"]["default"], "hash");
+    }
+
+    struct RedisHarness {
+        url: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for RedisHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            RedisDestinationFactory.create(serde_json::json!({ "url": self.url }))
+        }
+    }
+
+    /// `CONFORMANCE_REDIS_URL=redis://localhost:6379 cargo test -p cdc-redis-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a Redis server in CONFORMANCE_REDIS_URL"]
+    async fn test_conformance() {
+        let url = std::env::var("CONFORMANCE_REDIS_URL").expect("CONFORMANCE_REDIS_URL is not set");
+        cdc_conformance::run_destination_checks(&mut RedisHarness { url }).await;
+    }
+}
@@ -0,0 +1,5 @@
+mod factory;
+mod redis_destination;
+
+pub use factory::RedisDestinationFactory;
+pub use redis_destination::{RedisDestination, RedisDestinationConfig, ValueFormat};
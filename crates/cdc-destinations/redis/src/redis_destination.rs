@@ -0,0 +1,389 @@
+use async_trait::async_trait;
+use cdc_core::{redact_url, DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use redis::{aio::MultiplexedConnection, Client, Cmd, ConnectionInfo, Pipeline};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::{debug, error, info};
+
+/// Separator between primary key values in `{id}`
+const ID_SEPARATOR: &str = ":";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RedisDestinationConfig {
+    /// Redis connection URL (e.g., redis://localhost:6379)
+    #[serde(default = "default_url")]
+    #[schemars(extend("x-secret" = true))]
+    pub url: String,
+
+    /// Redis database number
+    #[serde(default = "default_database")]
+    pub database: i64,
+
+    /// Key template; `{table}`, `{database}` and `{id}` are replaced
+    #[serde(default = "default_key_template")]
+    pub key_template: String,
+
+    /// Fields forming `{id}`; composite keys are joined with `:`
+    #[serde(default = "default_primary_key")]
+    pub primary_key: Vec<String>,
+
+    /// How rows are stored
+    #[serde(default)]
+    pub format: ValueFormat,
+
+    /// Expire keys this many seconds after their last write
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+fn default_url() -> String {
+    "redis://localhost:6379".to_string()
+}
+
+fn default_database() -> i64 {
+    0
+}
+
+fn default_key_template() -> String {
+    "{table}:{id}".to_string()
+}
+
+fn default_primary_key() -> Vec<String> {
+    vec!["id".to_string()]
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueFormat {
+    /// A hash with one field per column
+    #[default]
+    Hash,
+    /// A string holding the row as JSON
+    Json,
+}
+
+impl Default for RedisDestinationConfig {
+    fn default() -> Self {
+        Self {
+            url: "redis://localhost:6379".to_string(),
+            database: 0,
+            key_template: default_key_template(),
+            primary_key: default_primary_key(),
+            format: ValueFormat::Hash,
+            ttl_secs: None,
+        }
+    }
+}
+
+pub struct RedisDestination {
+    config: RedisDestinationConfig,
+    connection: Option<MultiplexedConnection>,
+    status: DestinationStatus,
+}
+
+impl RedisDestination {
+    pub fn new(config: RedisDestinationConfig) -> Self {
+        Self {
+            config,
+            connection: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Key of the row from the key template
+    fn key(&self, record: &DataRecord, row: &Map<String, Value>) -> Result<String> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+
+        let id = self
+            .config
+            .primary_key
+            .iter()
+            .map(|key| match row.get(key) {
+                None | Some(Value::Null) => Err(Error::Generic(anyhow::anyhow!(
+                    "Record has no value for primary key field '{}'",
+                    key
+                ))),
+                Some(value) => Ok(Self::field_value(value)),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(ID_SEPARATOR);
+
+        let mut key = self
+            .config
+            .key_template
+            .replace("{table}", &table)
+            .replace("{id}", &id);
+        if key.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Key template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            key = key.replace("{database}", &database);
+        }
+        Ok(key)
+    }
+
+    /// Hash field value; strings are stored as-is, everything else as JSON text
+    fn field_value(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Commands materializing `record`
+    fn commands(&self, record: &DataRecord) -> Result<Vec<Cmd>> {
+        let mut row: Map<String, Value> = serde_json::from_str(&record.record)
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        if operation == Operation::Update {
+            if let Ok(Some(changes)) = record.parse_changes() {
+                row.extend(changes);
+            }
+        }
+
+        let key = self.key(record, &row)?;
+        if operation == Operation::Delete {
+            return Ok(vec![redis::cmd("DEL").arg(&key).clone()]);
+        }
+
+        let mut commands = Vec::new();
+        match self.config.format {
+            ValueFormat::Hash => {
+                // Replace the whole hash so columns that became NULL disappear
+                commands.push(redis::cmd("DEL").arg(&key).clone());
+                let fields: Vec<(&String, String)> = row
+                    .iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(name, value)| (name, Self::field_value(value)))
+                    .collect();
+                if !fields.is_empty() {
+                    commands.push(redis::cmd("HSET").arg(&key).arg(fields).clone());
+                }
+                if let Some(ttl) = self.config.ttl_secs {
+                    commands.push(redis::cmd("EXPIRE").arg(&key).arg(ttl).clone());
+                }
+            }
+            ValueFormat::Json => {
+                let mut set = redis::cmd("SET");
+                set.arg(&key).arg(Value::Object(row).to_string());
+                if let Some(ttl) = self.config.ttl_secs {
+                    set.arg("EX").arg(ttl);
+                }
+                commands.push(set);
+            }
+        }
+        Ok(commands)
+    }
+
+    async fn write_records(&mut self, records: &[DataRecord]) -> Result<()> {
+        // Build every command before sending so a bad record fails the batch up front
+        let mut pipeline = Pipeline::new();
+        pipeline.atomic();
+        for record in records {
+            for command in self.commands(record)? {
+                pipeline.add_command(command).ignore();
+            }
+        }
+
+        let conn = self
+            .connection
+            .as_mut()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        debug!("Sending pipeline for {} record(s)", records.len());
+        pipeline
+            .query_async::<_, ()>(conn)
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to write to Redis: {}", e)))
+    }
+}
+
+#[async_trait]
+impl Destination for RedisDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!(
+            "Connecting to Redis: {} (db={})",
+            redact_url(&self.config.url),
+            self.config.database
+        );
+
+        let mut connection_info: ConnectionInfo = Client::open(self.config.url.as_str())
+            .map_err(|e| Error::Connection(format!("Invalid Redis URL: {}", e)))?
+            .get_connection_info()
+            .clone();
+
+        // Override database if specified
+        connection_info.redis.db = self.config.database;
+
+        let client = Client::open(connection_info)
+            .map_err(|e| Error::Connection(format!("Failed to create Redis client: {}", e)))?;
+
+        let mut conn = client
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to connect to Redis: {}", e)))?;
+
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut conn)
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to connect to Redis: {}", e)))?;
+
+        info!("Connected to Redis successfully");
+        self.connection = Some(conn);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Disconnecting from Redis");
+        self.connection = None;
+        self.status.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if self.connection.is_none() {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        match self.write_records(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully wrote batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    fn destination(config: RedisDestinationConfig) -> RedisDestination {
+        RedisDestination::new(config)
+    }
+
+    fn packed(commands: &[Cmd]) -> Vec<Vec<u8>> {
+        commands.iter().map(Cmd::get_packed_command).collect()
+    }
+
+    #[test]
+    fn test_key_from_template() {
+        let record = sample_record("users", 7);
+        let row = serde_json::from_str(&record.record).unwrap();
+        assert_eq!(
+            destination(RedisDestinationConfig::default())
+                .key(&record, &row)
+                .unwrap(),
+            "users:7"
+        );
+
+        let destination = destination(RedisDestinationConfig {
+            key_template: "cache:{database}:{table}:{id}".to_string(),
+            primary_key: vec!["tenant".to_string(), "id".to_string()],
+            ..Default::default()
+        });
+        let row = json!({ "tenant": "acme", "id": 7 });
+        assert_eq!(
+            destination.key(&record, row.as_object().unwrap()).unwrap(),
+            "cache:test:users:acme:7"
+        );
+
+        let row = json!({ "id": 7 });
+        assert!(destination.key(&record, row.as_object().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_hash_commands() {
+        let destination = destination(RedisDestinationConfig {
+            ttl_secs: Some(60),
+            ..Default::default()
+        });
+        let record = DataRecord::new(
+            json!({ "id": 1, "name": "a", "tags": ["x"], "note": null }).to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            "update".to_string(),
+            Some(json!({ "name": "b" }).to_string()),
+        );
+
+        let mut hset = redis::cmd("HSET");
+        hset.arg("users:1")
+            .arg("id")
+            .arg("1")
+            .arg("name")
+            .arg("b")
+            .arg("tags")
+            .arg("[\"x\"]");
+        assert_eq!(
+            packed(&destination.commands(&record).unwrap()),
+            packed(&[
+                redis::cmd("DEL").arg("users:1").clone(),
+                hset,
+                redis::cmd("EXPIRE").arg("users:1").arg(60).clone(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_commands() {
+        let destination = destination(RedisDestinationConfig {
+            format: ValueFormat::Json,
+            ttl_secs: Some(60),
+            ..Default::default()
+        });
+        let record = sample_record("users", 1);
+
+        assert_eq!(
+            packed(&destination.commands(&record).unwrap()),
+            packed(&[redis::cmd("SET")
+                .arg("users:1")
+                .arg("{\"id\":1}")
+                .arg("EX")
+                .arg(60)
+                .clone()])
+        );
+    }
+
+    #[test]
+    fn test_delete_removes_key() {
+        let destination = destination(RedisDestinationConfig::default());
+        let mut record = sample_record("users", 3);
+        record.action = "delete".to_string();
+
+        assert_eq!(
+            packed(&destination.commands(&record).unwrap()),
+            packed(&[redis::cmd("DEL").arg("users:3").clone()])
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{S3Destination, S3DestinationConfig};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct S3DestinationFactory;
+
+impl DestinationFactory for S3DestinationFactory {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: S3DestinationConfig = serde_json::from_value(config)?;
+        Ok(Box::new(S3Destination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(S3DestinationConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = S3DestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["bucket"]));
+        assert_eq!(properties["format"]["default"], "jsonl");
+        assert_eq!(properties["compression"]["default"], "gzip");
+        assert_eq!(
+            properties["key_template"]["default"],
+            "{table}/{partition}/{id}.{ext}"
+        );
+        assert_eq!(properties["secret_access_key"]["x-secret"], true);
+    }
+
+    struct InMemoryHarness;
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for InMemoryHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            let config = serde_json::from_value(serde_json::json!({ "bucket": "lake" }))?;
+            Ok(Box::new(S3Destination::with_store(
+                config,
+                Arc::new(InMemory::new()),
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        cdc_conformance::run_destination_checks(&mut InMemoryHarness).await;
+    }
+
+    struct S3Harness {
+        endpoint: String,
+        bucket: String,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for S3Harness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            S3DestinationFactory.create(serde_json::json!({
+                "endpoint": self.endpoint,
+                "bucket": self.bucket,
+            }))
+        }
+    }
+
+    /// `CONFORMANCE_S3_ENDPOINT=http://localhost:9000 CONFORMANCE_S3_BUCKET=cdc \
+    /// AWS_ACCESS_KEY_ID=minioadmin AWS_SECRET_ACCESS_KEY=minioadmin \
+    /// cargo test -p cdc-s3-destination -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs an S3-compatible endpoint in CONFORMANCE_S3_ENDPOINT"]
+    async fn test_conformance_s3() {
+        let endpoint =
+            std::env::var("CONFORMANCE_S3_ENDPOINT").expect("CONFORMANCE_S3_ENDPOINT is not set");
+        let bucket =
+            std::env::var("CONFORMANCE_S3_BUCKET").expect("CONFORMANCE_S3_BUCKET is not set");
+        cdc_conformance::run_destination_checks(&mut S3Harness { endpoint, bucket }).await;
+    }
+}
@@ -0,0 +1,6 @@
+mod factory;
+mod object_writer;
+mod s3_destination;
+
+pub use factory::S3DestinationFactory;
+pub use s3_destination::{ObjectCompression, ObjectFormat, S3Destination, S3DestinationConfig};
@@ -0,0 +1,171 @@
+use crate::{ObjectCompression, ObjectFormat};
+use cdc_core::{DataRecord, Error, Result};
+use flate2::write::GzEncoder;
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::io::Write;
+use std::mem;
+use std::sync::Arc;
+
+/// Columns of Parquet objects, in order
+const COLUMNS: [&str; 6] = ["id", "timestamp", "action", "record", "metadata", "changes"];
+
+const PARQUET_SCHEMA: &str = "
+message data_record {
+    REQUIRED BYTE_ARRAY id (STRING);
+    REQUIRED INT64 timestamp (TIMESTAMP(MICROS, true));
+    REQUIRED BYTE_ARRAY action (STRING);
+    REQUIRED BYTE_ARRAY record (STRING);
+    REQUIRED BYTE_ARRAY metadata (STRING);
+    OPTIONAL BYTE_ARRAY changes (STRING);
+}
+";
+
+/// Encodes records of one object into memory; encoded bytes are taken out as they are
+/// produced so they can be uploaded as parts
+pub(crate) enum ObjectWriter {
+    Jsonl(Vec<u8>),
+    JsonlGzip(GzEncoder<Vec<u8>>),
+    JsonlZstd(zstd::Encoder<'static, Vec<u8>>),
+    Parquet(SerializedFileWriter<Vec<u8>>),
+}
+
+impl ObjectWriter {
+    pub(crate) fn create(format: ObjectFormat, compression: ObjectCompression) -> Result<Self> {
+        match (format, compression) {
+            (ObjectFormat::Jsonl, ObjectCompression::None) => Ok(Self::Jsonl(Vec::new())),
+            (ObjectFormat::Jsonl, ObjectCompression::Gzip) => Ok(Self::JsonlGzip(GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::default(),
+            ))),
+            (ObjectFormat::Jsonl, ObjectCompression::Zstd) => Ok(Self::JsonlZstd(
+                zstd::Encoder::new(Vec::new(), zstd::DEFAULT_COMPRESSION_LEVEL)?,
+            )),
+            (ObjectFormat::Parquet, compression) => {
+                let compression = match compression {
+                    ObjectCompression::None => Compression::UNCOMPRESSED,
+                    ObjectCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+                    ObjectCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+                };
+                let schema = parse_message_type(PARQUET_SCHEMA).map_err(parquet_error)?;
+                let properties = WriterProperties::builder()
+                    .set_compression(compression)
+                    .build();
+                let writer =
+                    SerializedFileWriter::new(Vec::new(), Arc::new(schema), Arc::new(properties))
+                        .map_err(parquet_error)?;
+                Ok(Self::Parquet(writer))
+            }
+        }
+    }
+
+    /// Encode `records`; Parquet writes them as one row group
+    pub(crate) fn write(&mut self, records: &[&DataRecord]) -> Result<()> {
+        match self {
+            Self::Jsonl(buffer) => write_lines(buffer, records)?,
+            Self::JsonlGzip(encoder) => write_lines(encoder, records)?,
+            Self::JsonlZstd(encoder) => write_lines(encoder, records)?,
+            Self::Parquet(writer) => {
+                write_row_group(writer, records).map_err(parquet_error)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete the encoding (compression trailer, Parquet footer); the remaining bytes are
+    /// then returned by `take_output`
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        match self {
+            Self::Jsonl(_) => {}
+            Self::JsonlGzip(encoder) => encoder.try_finish()?,
+            Self::JsonlZstd(encoder) => encoder.do_finish()?,
+            Self::Parquet(writer) => {
+                writer.finish().map_err(parquet_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encoded bytes produced since the last call
+    pub(crate) fn take_output(&mut self) -> Vec<u8> {
+        let output = match self {
+            Self::Jsonl(buffer) => buffer,
+            Self::JsonlGzip(encoder) => encoder.get_mut(),
+            Self::JsonlZstd(encoder) => encoder.get_mut(),
+            Self::Parquet(writer) => writer.inner_mut(),
+        };
+        mem::take(output)
+    }
+}
+
+fn write_lines(writer: &mut impl Write, records: &[&DataRecord]) -> Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut *writer, record)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn write_row_group(
+    writer: &mut SerializedFileWriter<Vec<u8>>,
+    records: &[&DataRecord],
+) -> parquet::errors::Result<()> {
+    let text = |value: fn(&DataRecord) -> String| -> Vec<ByteArray> {
+        records
+            .iter()
+            .map(|r| ByteArray::from(value(r).into_bytes()))
+            .collect()
+    };
+
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match COLUMNS[index] {
+            "timestamp" => {
+                let values: Vec<i64> = records
+                    .iter()
+                    .map(|r| r.timestamp.timestamp_micros())
+                    .collect();
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&values, None, None)?;
+            }
+            "changes" => {
+                let values: Vec<ByteArray> = records
+                    .iter()
+                    .filter_map(|r| r.changes.as_deref().map(ByteArray::from))
+                    .collect();
+                let levels: Vec<i16> = records
+                    .iter()
+                    .map(|r| i16::from(r.changes.is_some()))
+                    .collect();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            name => {
+                let values = match name {
+                    "id" => text(|r| r.id.to_string()),
+                    "action" => text(|r| r.action.clone()),
+                    "record" => text(|r| r.record.clone()),
+                    _ => text(|r| r.metadata.clone()),
+                };
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+        }
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    Ok(())
+}
+
+fn parquet_error(e: parquet::errors::ParquetError) -> Error {
+    Error::Generic(anyhow::anyhow!("Parquet error: {}", e))
+}
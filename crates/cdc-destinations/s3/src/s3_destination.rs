@@ -0,0 +1,793 @@
+use crate::object_writer::ObjectWriter;
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Result};
+use chrono::format::{Item, StrftimeItems};
+use object_store::aws::AmazonS3Builder;
+use object_store::multipart::{MultipartStore, PartId};
+use object_store::path::Path;
+use object_store::MultipartId;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// Smallest part S3 accepts, except for the last part of an object
+const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct S3DestinationConfig {
+    /// Bucket the objects are written to
+    pub bucket: String,
+
+    /// Endpoint of an S3-compatible service (e.g., http://localhost:9000 for MinIO);
+    /// AWS S3 is used when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Bucket region
+    #[serde(default = "default_region")]
+    pub region: String,
+
+    /// Access key ID; taken from the `AWS_*` environment variables when unset
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+
+    /// Secret access key
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub secret_access_key: Option<String>,
+
+    /// Session token for temporary credentials
+    #[serde(default)]
+    #[schemars(extend("x-secret" = true))]
+    pub session_token: Option<String>,
+
+    /// Object format
+    #[serde(default)]
+    pub format: ObjectFormat,
+
+    /// Compression of the object (JSONL) or of its pages (Parquet)
+    #[serde(default)]
+    pub compression: ObjectCompression,
+
+    /// Object key; `{table}`, `{database}`, `{partition}`, `{id}` and `{ext}` are replaced.
+    /// `{id}` is unique per object and must be present
+    #[serde(default = "default_key_template")]
+    pub key_template: String,
+
+    /// `{partition}` as a chrono format string applied to the record timestamp
+    /// (e.g., "dt=%Y-%m-%d/hour=%H")
+    #[serde(default = "default_partition_format")]
+    pub partition_format: String,
+
+    /// Complete an object once it reaches this size, in bytes
+    #[serde(default = "default_max_object_bytes")]
+    pub max_object_bytes: u64,
+
+    /// Complete the open objects once the oldest has been open this long, in seconds.
+    /// Records are only acknowledged to the source once their object is complete
+    #[serde(default = "default_max_object_age_secs")]
+    pub max_object_age_secs: u64,
+
+    /// Size of each multipart upload part, in bytes (at least 5 MiB)
+    #[serde(default = "default_part_size_bytes")]
+    pub part_size_bytes: u64,
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_key_template() -> String {
+    "{table}/{partition}/{id}.{ext}".to_string()
+}
+
+fn default_partition_format() -> String {
+    "dt=%Y-%m-%d".to_string()
+}
+
+fn default_max_object_bytes() -> u64 {
+    128 * 1024 * 1024 // 128 MiB
+}
+
+fn default_max_object_age_secs() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_part_size_bytes() -> u64 {
+    8 * 1024 * 1024 // 8 MiB
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectFormat {
+    /// One serialized `DataRecord` per line
+    #[default]
+    Jsonl,
+    /// Parquet, one row group per batch
+    Parquet,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectCompression {
+    /// Uncompressed
+    None,
+    /// Gzip (`.jsonl.gz`)
+    #[default]
+    Gzip,
+    /// Zstandard (`.jsonl.zst`)
+    Zstd,
+}
+
+impl S3DestinationConfig {
+    /// `{ext}` of the objects
+    fn extension(&self) -> &'static str {
+        match (self.format, self.compression) {
+            (ObjectFormat::Jsonl, ObjectCompression::None) => "jsonl",
+            (ObjectFormat::Jsonl, ObjectCompression::Gzip) => "jsonl.gz",
+            (ObjectFormat::Jsonl, ObjectCompression::Zstd) => "jsonl.zst",
+            (ObjectFormat::Parquet, _) => "parquet",
+        }
+    }
+}
+
+/// An object being encoded and uploaded part by part; it only becomes visible once
+/// its multipart upload is completed
+struct OpenObject {
+    path: Path,
+    writer: ObjectWriter,
+    /// Encoded bytes not uploaded yet
+    pending: Vec<u8>,
+    upload_id: Option<MultipartId>,
+    parts: Vec<PartId>,
+    /// Bytes encoded so far, uploaded or not
+    size: u64,
+    opened: Instant,
+    /// The encoding is complete and only the upload remains
+    finished: bool,
+}
+
+pub struct S3Destination {
+    config: S3DestinationConfig,
+    store: Option<Arc<dyn MultipartStore>>,
+    /// Open object per rendered key template (with `{id}` left in)
+    objects: HashMap<String, OpenObject>,
+    status: DestinationStatus,
+}
+
+impl S3Destination {
+    pub fn new(config: S3DestinationConfig) -> Self {
+        Self {
+            config,
+            store: None,
+            objects: HashMap::new(),
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Destination writing to `store` instead of the configured bucket
+    #[cfg(test)]
+    pub(crate) fn with_store(config: S3DestinationConfig, store: Arc<dyn MultipartStore>) -> Self {
+        Self {
+            store: Some(store),
+            ..Self::new(config)
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.config.key_template.contains("{id}") {
+            return Err(Error::Configuration(format!(
+                "key_template '{}' must contain {{id}}, otherwise objects overwrite each other",
+                self.config.key_template
+            )));
+        }
+        if StrftimeItems::new(&self.config.partition_format).any(|item| item == Item::Error) {
+            return Err(Error::Configuration(format!(
+                "Invalid partition_format '{}'",
+                self.config.partition_format
+            )));
+        }
+        Ok(())
+    }
+
+    fn build_store(&self) -> Result<Arc<dyn MultipartStore>> {
+        if self.config.part_size_bytes < MIN_PART_SIZE {
+            return Err(Error::Configuration(format!(
+                "part_size_bytes must be at least {} (5 MiB)",
+                MIN_PART_SIZE
+            )));
+        }
+
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(&self.config.bucket)
+            .with_region(&self.config.region);
+        if let Some(endpoint) = &self.config.endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        if let Some(access_key_id) = &self.config.access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &self.config.secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        if let Some(session_token) = &self.config.session_token {
+            builder = builder.with_token(session_token);
+        }
+
+        let store = builder
+            .build()
+            .map_err(|e| Error::Configuration(format!("Invalid S3 configuration: {}", e)))?;
+        Ok(Arc::new(store))
+    }
+
+    /// Key template rendered for `record`, with `{id}` left for the object to fill in
+    fn object_group(&self, record: &DataRecord) -> Result<String> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+        let partition = record
+            .timestamp
+            .format(&self.config.partition_format)
+            .to_string();
+
+        let mut key = self
+            .config
+            .key_template
+            .replace("{table}", &sanitize_key_segment(&table))
+            .replace("{partition}", &partition)
+            .replace("{ext}", self.config.extension());
+        if key.contains("{database}") {
+            let database = record.database_name().ok_or_else(|| {
+                Error::Generic(anyhow::anyhow!(
+                    "Key template uses {{database}} but there is no database_name in metadata"
+                ))
+            })?;
+            key = key.replace("{database}", &sanitize_key_segment(&database));
+        }
+        Ok(key)
+    }
+
+    /// Complete objects that are full and upload the parts that are ready; age is left
+    /// to `tick`, which completes all objects together
+    async fn upload_due(&mut self) -> Result<()> {
+        let Some(store) = self.store.clone() else {
+            return Err(Error::Connection("Not connected".to_string()));
+        };
+
+        let groups: Vec<String> = self.objects.keys().cloned().collect();
+        for group in groups {
+            let object = self.objects.get_mut(&group).expect("object is open");
+            if object.finished || object.size >= self.config.max_object_bytes {
+                Self::complete(store.as_ref(), object, self.config.part_size_bytes).await?;
+                self.objects.remove(&group);
+            } else {
+                Self::upload_parts(store.as_ref(), object, self.config.part_size_bytes, false)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete every open object. Objects that fail stay open, so a later call
+    /// retries their upload
+    async fn complete_all(&mut self) -> Result<()> {
+        let Some(store) = self.store.clone() else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        let groups: Vec<String> = self.objects.keys().cloned().collect();
+        for group in groups {
+            let object = self.objects.get_mut(&group).expect("object is open");
+            match Self::complete(store.as_ref(), object, self.config.part_size_bytes).await {
+                Ok(()) => {
+                    self.objects.remove(&group);
+                }
+                Err(e) => {
+                    error!("Failed to complete object: {}", e);
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Upload full parts of the pending bytes, or all of them for the last part
+    async fn upload_parts(
+        store: &dyn MultipartStore,
+        object: &mut OpenObject,
+        part_size: u64,
+        last: bool,
+    ) -> Result<()> {
+        let part_size = part_size as usize;
+        while object.pending.len() >= part_size || (last && !object.pending.is_empty()) {
+            let upload_id = match &object.upload_id {
+                Some(id) => id.clone(),
+                None => {
+                    let id = store
+                        .create_multipart(&object.path)
+                        .await
+                        .map_err(|e| upload_error(&object.path, e))?;
+                    object.upload_id = Some(id.clone());
+                    id
+                }
+            };
+
+            // Parts keep their index, so a part that failed is sent again in its place
+            let length = object.pending.len().min(part_size);
+            let data = object.pending[..length].to_vec();
+            let part = store
+                .put_part(&object.path, &upload_id, object.parts.len(), data.into())
+                .await
+                .map_err(|e| upload_error(&object.path, e))?;
+            object.parts.push(part);
+            object.pending.drain(..length);
+            debug!("Uploaded part {} of {}", object.parts.len(), object.path);
+        }
+        Ok(())
+    }
+
+    /// Finish the encoding, upload what is left and complete the object
+    async fn complete(
+        store: &dyn MultipartStore,
+        object: &mut OpenObject,
+        part_size: u64,
+    ) -> Result<()> {
+        if !object.finished {
+            object.writer.finish()?;
+            let output = object.writer.take_output();
+            object.size += output.len() as u64;
+            object.pending.extend(output);
+            object.finished = true;
+        }
+
+        Self::upload_parts(store, object, part_size, true).await?;
+        if let Some(upload_id) = &object.upload_id {
+            store
+                .complete_multipart(&object.path, upload_id, object.parts.clone())
+                .await
+                .map_err(|e| upload_error(&object.path, e))?;
+        }
+
+        info!("Completed {} ({} bytes)", object.path, object.size);
+        Ok(())
+    }
+
+    fn open_object(&self, group: &str) -> Result<OpenObject> {
+        let key = group.replace("{id}", &uuid::Uuid::now_v7().to_string());
+        debug!("Opened {}", key);
+        Ok(OpenObject {
+            path: Path::from(key),
+            writer: ObjectWriter::create(self.config.format, self.config.compression)?,
+            pending: Vec::new(),
+            upload_id: None,
+            parts: Vec::new(),
+            size: 0,
+            opened: Instant::now(),
+            finished: false,
+        })
+    }
+
+    async fn write_records(&mut self, records: &[DataRecord]) -> Result<()> {
+        // Resolve every object before writing so a bad record fails the batch up front
+        let mut groups: Vec<(String, Vec<&DataRecord>)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for record in records {
+            let group = self.object_group(record)?;
+            match index.get(&group) {
+                Some(&i) => groups[i].1.push(record),
+                None => {
+                    index.insert(group.clone(), groups.len());
+                    groups.push((group, vec![record]));
+                }
+            }
+        }
+
+        // Upload before buffering, so a batch that fails on an upload has buffered
+        // nothing and is not written twice when it is sent again
+        self.upload_due().await?;
+
+        for (group, records) in &groups {
+            if !self.objects.contains_key(group) {
+                let object = self.open_object(group)?;
+                self.objects.insert(group.clone(), object);
+            }
+
+            let object = self.objects.get_mut(group).expect("object is open");
+            if let Err(e) = object.writer.write(records) {
+                // The encoding may end in a partial write; drop the object
+                let object = self.objects.remove(group).expect("object is open");
+                warn!("Abandoning {} after a failed write", object.path);
+                if let (Some(store), Some(upload_id)) = (&self.store, &object.upload_id) {
+                    if let Err(e) = store.abort_multipart(&object.path, upload_id).await {
+                        warn!("Failed to abort upload of {}: {}", object.path, e);
+                    }
+                }
+                return Err(e);
+            }
+            let output = object.writer.take_output();
+            object.size += output.len() as u64;
+            object.pending.extend(output);
+        }
+        Ok(())
+    }
+}
+
+fn upload_error(path: &Path, e: object_store::Error) -> Error {
+    Error::Connection(format!("Failed to upload {}: {}", path, e))
+}
+
+/// Keep table and database names to a single, portable key segment
+fn sanitize_key_segment(name: &str) -> String {
+    let segment: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if segment.chars().all(|c| c == '.') {
+        segment.replace('.', "_")
+    } else {
+        segment
+    }
+}
+
+#[async_trait]
+impl Destination for S3Destination {
+    async fn connect(&mut self) -> Result<()> {
+        self.validate()?;
+        if self.store.is_none() {
+            self.store = Some(self.build_store()?);
+        }
+
+        info!(
+            "Writing {:?} objects to bucket {}{}",
+            self.config.format,
+            self.config.bucket,
+            self.config
+                .endpoint
+                .as_deref()
+                .map(|endpoint| format!(" at {}", endpoint))
+                .unwrap_or_default()
+        );
+        self.status.connected = true;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Completing {} open object(s)", self.objects.len());
+
+        // Objects that fail are kept open so a later connect() and write retries them
+        let result = self.complete_all().await;
+        self.status.connected = false;
+        result
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        self.write_batch(vec![record]).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        if !self.status.connected {
+            return Err(Error::Connection("Not connected".to_string()));
+        }
+
+        match self.write_records(&records).await {
+            Ok(()) => {
+                self.status.records_written += records.len() as u64;
+                info!("Successfully buffered batch of {} records", records.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write batch of {} records: {}", records.len(), e);
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.objects.is_empty()
+    }
+
+    fn pending_due(&self) -> bool {
+        let max_age = Duration::from_secs(self.config.max_object_age_secs);
+        self.objects
+            .values()
+            .any(|object| object.opened.elapsed() >= max_age)
+    }
+
+    async fn tick(&mut self) -> Result<()> {
+        if !self.pending_due() {
+            return Ok(());
+        }
+
+        // Completing every object, not only the old one, leaves nothing held back so the
+        // flow can commit the source
+        info!("Completing {} open object(s) by age", self.objects.len());
+        self.complete_all().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use futures::StreamExt;
+    use object_store::memory::InMemory;
+    use object_store::{ObjectStore, PutPayload};
+    use std::io::Read;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn config(overrides: serde_json::Value) -> S3DestinationConfig {
+        let mut config = serde_json::json!({ "bucket": "lake" });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(overrides.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    fn records(table: &str, ids: std::ops::Range<u64>) -> Vec<DataRecord> {
+        ids.map(|id| sample_record(table, id)).collect()
+    }
+
+    async fn connected(
+        config: S3DestinationConfig,
+        store: Arc<dyn MultipartStore>,
+    ) -> S3Destination {
+        let mut destination = S3Destination::with_store(config, store);
+        destination.connect().await.unwrap();
+        destination
+    }
+
+    /// Completed objects in `store`, sorted by key
+    async fn objects(store: &InMemory) -> Vec<(String, Vec<u8>)> {
+        let mut found = Vec::new();
+        for meta in store.list(None).collect::<Vec<_>>().await {
+            let path = meta.unwrap().location;
+            let bytes = store.get(&path).await.unwrap().bytes().await.unwrap();
+            found.push((path.to_string(), bytes.to_vec()));
+        }
+        found.sort();
+        found
+    }
+
+    fn ids(jsonl: &[u8]) -> Vec<u64> {
+        String::from_utf8(jsonl.to_vec())
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let record: DataRecord = serde_json::from_str(line).unwrap();
+                record.parse_record().unwrap()["id"].as_u64().unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_gzip_objects_per_table_and_partition() {
+        let store = Arc::new(InMemory::new());
+        let mut destination = connected(config(serde_json::json!({})), store.clone()).await;
+
+        let mut batch = records("users", 0..2);
+        batch.extend(records("orders", 0..1));
+        destination.write_batch(batch).await.unwrap();
+        destination
+            .write_batch(records("users", 2..3))
+            .await
+            .unwrap();
+        assert!(objects(&store).await.is_empty());
+
+        destination.disconnect().await.unwrap();
+        let objects = objects(&store).await;
+        let dt = chrono::Utc::now().format("dt=%Y-%m-%d").to_string();
+        assert_eq!(objects.len(), 2);
+        assert!(objects[0].0.starts_with(&format!("orders/{}/", dt)));
+        assert!(objects[1].0.starts_with(&format!("users/{}/", dt)));
+        assert!(objects[1].0.ends_with(".jsonl.gz"));
+
+        let mut jsonl = Vec::new();
+        flate2::read::GzDecoder::new(objects[1].1.as_slice())
+            .read_to_end(&mut jsonl)
+            .unwrap();
+        assert_eq!(ids(&jsonl), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_by_size_in_parts() {
+        let store = Arc::new(InMemory::new());
+        let config = config(serde_json::json!({
+            "compression": "none",
+            "key_template": "{database}/{table}/{id}.{ext}",
+            "part_size_bytes": 64,
+            "max_object_bytes": 400,
+        }));
+        let mut destination = connected(config, store.clone()).await;
+
+        for id in 0..6 {
+            destination
+                .write_batch(records("users", id..id + 1))
+                .await
+                .unwrap();
+        }
+        destination.disconnect().await.unwrap();
+
+        let objects = objects(&store).await;
+        assert!(objects.len() > 1);
+        assert!(objects
+            .iter()
+            .all(|(key, _)| key.starts_with("test/users/")));
+
+        // Objects are named by time-ordered IDs, so key order is write order
+        let all: Vec<u64> = objects.iter().flat_map(|(_, bytes)| ids(bytes)).collect();
+        assert_eq!(all, (0..6).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_tick_completes_all_objects_once_one_is_old() {
+        let store = Arc::new(InMemory::new());
+        let config = config(serde_json::json!({ "max_object_age_secs": 60 }));
+        let mut destination = connected(config, store.clone()).await;
+
+        let mut batch = records("users", 0..1);
+        batch.extend(records("orders", 0..1));
+        destination.write_batch(batch).await.unwrap();
+        destination.tick().await.unwrap();
+        assert!(destination.has_pending());
+        assert!(!destination.pending_due());
+        assert!(objects(&store).await.is_empty());
+
+        // Age one of the objects; the other one is completed along with it
+        let object = destination.objects.values_mut().next().unwrap();
+        object.opened -= Duration::from_secs(60);
+        assert!(destination.pending_due());
+        destination.tick().await.unwrap();
+
+        assert!(!destination.has_pending());
+        assert_eq!(objects(&store).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_row_groups_per_batch() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let store = Arc::new(InMemory::new());
+        let config = config(serde_json::json!({ "format": "parquet", "compression": "zstd" }));
+        let mut destination = connected(config, store.clone()).await;
+        destination
+            .write_batch(records("users", 0..3))
+            .await
+            .unwrap();
+        destination
+            .write_batch(records("users", 3..5))
+            .await
+            .unwrap();
+        destination.disconnect().await.unwrap();
+
+        let objects = objects(&store).await;
+        assert!(objects[0].0.ends_with(".parquet"));
+        let bytes = bytes::Bytes::from(objects[0].1.clone());
+        let metadata = SerializedFileReader::new(bytes).unwrap().metadata().clone();
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+    }
+
+    /// Store whose next part upload fails
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: Arc<InMemory>,
+        fail_next_part: AtomicBool,
+    }
+
+    #[async_trait]
+    impl MultipartStore for FlakyStore {
+        async fn create_multipart(&self, path: &Path) -> object_store::Result<MultipartId> {
+            self.inner.create_multipart(path).await
+        }
+
+        async fn put_part(
+            &self,
+            path: &Path,
+            id: &MultipartId,
+            part_idx: usize,
+            data: PutPayload,
+        ) -> object_store::Result<PartId> {
+            if self.fail_next_part.swap(false, Ordering::SeqCst) {
+                return Err(object_store::Error::Generic {
+                    store: "flaky",
+                    source: "connection reset".into(),
+                });
+            }
+            self.inner.put_part(path, id, part_idx, data).await
+        }
+
+        async fn complete_multipart(
+            &self,
+            path: &Path,
+            id: &MultipartId,
+            parts: Vec<PartId>,
+        ) -> object_store::Result<object_store::PutResult> {
+            self.inner.complete_multipart(path, id, parts).await
+        }
+
+        async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> object_store::Result<()> {
+            self.inner.abort_multipart(path, id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_upload_fails_batch_without_buffering_it() {
+        let inner = Arc::new(InMemory::new());
+        let store = Arc::new(FlakyStore {
+            inner: inner.clone(),
+            fail_next_part: AtomicBool::new(false),
+        });
+        let config = config(serde_json::json!({ "compression": "none", "part_size_bytes": 1 }));
+        let mut destination = connected(config, store.clone()).await;
+
+        destination
+            .write_batch(records("users", 0..1))
+            .await
+            .unwrap();
+
+        // The upload of the first batch's part fails, so the second batch is not buffered
+        store.fail_next_part.store(true, Ordering::SeqCst);
+        let batch = records("users", 1..2);
+        assert!(destination.write_batch(batch.clone()).await.is_err());
+        assert_eq!(destination.status().errors, 1);
+
+        // Sending it again uploads both batches exactly once
+        destination.write_batch(batch).await.unwrap();
+        destination.disconnect().await.unwrap();
+
+        let objects = objects(&inner).await;
+        assert_eq!(objects.len(), 1);
+        assert_eq!(ids(&objects[0].1), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_validates_key_template_and_part_size() {
+        let mut destination = S3Destination::with_store(
+            config(serde_json::json!({ "key_template": "{table}/data.{ext}" })),
+            Arc::new(InMemory::new()),
+        );
+        assert!(matches!(
+            destination.connect().await,
+            Err(Error::Configuration(_))
+        ));
+
+        let mut destination = S3Destination::new(config(serde_json::json!({
+            "endpoint": "http://localhost:9000",
+            "part_size_bytes": 1024,
+        })));
+        assert!(matches!(
+            destination.connect().await,
+            Err(Error::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_key_segment() {
+        assert_eq!(sanitize_key_segment("public.users"), "public.users");
+        assert_eq!(sanitize_key_segment("a/b c"), "a_b_c");
+        assert_eq!(sanitize_key_segment(".."), "__");
+    }
+}
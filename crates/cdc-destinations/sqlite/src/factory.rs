@@ -0,0 +1,58 @@
+use crate::{SqliteConfig, SqliteDestination};
+use cdc_core::{Destination, DestinationFactory, Result};
+use serde_json::Value;
+
+pub struct SqliteDestinationFactory;
+
+impl DestinationFactory for SqliteDestinationFactory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn create(&self, config: Value) -> Result<Box<dyn Destination>> {
+        let config: SqliteConfig = serde_json::from_value(config)?;
+        Ok(Box::new(SqliteDestination::new(config)))
+    }
+
+    fn config_schema(&self) -> Value {
+        schemars::schema_for!(SqliteConfig).to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defaults() {
+        let schema = SqliteDestinationFactory.config_schema();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], serde_json::json!(["path"]));
+        assert_eq!(properties["conflict_resolution"]["default"], "upsert");
+        assert_eq!(properties["busy_timeout_ms"]["default"], 5000);
+    }
+
+    struct SqliteHarness {
+        dir: tempfile::TempDir,
+        created: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl cdc_conformance::DestinationHarness for SqliteHarness {
+        async fn create(&mut self) -> Result<Box<dyn Destination>> {
+            self.created += 1;
+            let path = self.dir.path().join(format!("run-{}.db", self.created));
+            SqliteDestinationFactory.create(serde_json::json!({ "path": path }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let mut harness = SqliteHarness {
+            dir: tempfile::tempdir().unwrap(),
+            created: 0,
+        };
+        cdc_conformance::run_destination_checks(&mut harness).await;
+    }
+}
@@ -0,0 +1,5 @@
+mod factory;
+mod sqlite_destination;
+
+pub use factory::SqliteDestinationFactory;
+pub use sqlite_destination::{ConflictResolution, SqliteConfig, SqliteDestination};
@@ -0,0 +1,757 @@
+use async_trait::async_trait;
+use cdc_core::{DataRecord, Destination, DestinationStatus, Error, Operation, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqliteConfig {
+    /// Path of the database file; created if it does not exist
+    pub path: String,
+
+    /// Maximum number of connections in the pool
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+
+    /// How long a write waits for another connection's lock before failing
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+
+    /// Conflict resolution strategy
+    #[serde(default)]
+    pub conflict_resolution: ConflictResolution,
+
+    /// Automatically create tables if they don't exist
+    #[serde(default = "default_auto_create_tables")]
+    pub auto_create_tables: bool,
+
+    /// Automatically add columns if they don't exist
+    #[serde(default = "default_auto_add_columns")]
+    pub auto_add_columns: bool,
+}
+
+fn default_max_connections() -> u32 {
+    4
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_auto_create_tables() -> bool {
+    true
+}
+
+fn default_auto_add_columns() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// Use INSERT ... ON CONFLICT DO UPDATE (upsert)
+    #[default]
+    Upsert,
+    /// Replace existing records (INSERT OR REPLACE)
+    Replace,
+    /// Ignore conflicts
+    Ignore,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            path: "cdc.db".to_string(),
+            max_connections: 4,
+            busy_timeout_ms: 5000,
+            conflict_resolution: ConflictResolution::Upsert,
+            auto_create_tables: true,
+            auto_add_columns: true,
+        }
+    }
+}
+
+pub struct SqliteDestination {
+    config: SqliteConfig,
+    pool: Option<SqlitePool>,
+    status: DestinationStatus,
+}
+
+impl SqliteDestination {
+    pub fn new(config: SqliteConfig) -> Self {
+        Self {
+            config,
+            pool: None,
+            status: DestinationStatus::default(),
+        }
+    }
+
+    /// Quote identifier, doubling any embedded quotes
+    fn quote_identifier(identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    /// Infer SQLite column type from JSON value; JSON documents are stored as TEXT
+    fn infer_sqlite_type(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Bool(_) => "BOOLEAN".to_string(),
+            serde_json::Value::Number(n) => {
+                if n.is_i64() {
+                    "INTEGER".to_string()
+                } else {
+                    "REAL".to_string()
+                }
+            }
+            _ => "TEXT".to_string(),
+        }
+    }
+
+    /// Ensure the schema metadata table exists
+    async fn ensure_schema_metadata_table(&self, pool: &SqlitePool) -> Result<()> {
+        let query = "CREATE TABLE IF NOT EXISTS \"_cdc_schema_metadata\" (
+                table_name TEXT NOT NULL,
+                column_name TEXT NOT NULL,
+                data_type TEXT NOT NULL,
+                last_updated TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (table_name, column_name)
+            )";
+
+        sqlx::query(query).execute(pool).await.map_err(|e| {
+            Error::Generic(anyhow::anyhow!(
+                "Failed to create schema metadata table: {}",
+                e
+            ))
+        })?;
+
+        info!("Schema metadata table ensured");
+        Ok(())
+    }
+
+    /// Check if a table exists
+    async fn table_exists(&self, pool: &SqlitePool, table: &str) -> Result<bool> {
+        let query = "SELECT EXISTS (
+            SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?
+        )";
+
+        let exists: (bool,) = sqlx::query_as(query)
+            .bind(table)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to check table existence: {}", e))
+            })?;
+
+        Ok(exists.0)
+    }
+
+    /// Get current table schema; column names are lowercased as SQLite compares them
+    /// case-insensitively
+    async fn get_table_schema(
+        &self,
+        pool: &SqlitePool,
+        table: &str,
+    ) -> Result<HashMap<String, String>> {
+        let query = "SELECT name, type FROM pragma_table_info(?) ORDER BY cid";
+
+        let rows = sqlx::query_as::<_, (String, String)>(query)
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to get table schema: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, data_type)| (name.to_lowercase(), data_type))
+            .collect())
+    }
+
+    /// Update schema metadata cache
+    async fn update_schema_metadata(
+        &self,
+        pool: &SqlitePool,
+        table: &str,
+        columns: &HashMap<String, String>,
+    ) -> Result<()> {
+        // Delete existing metadata for this table
+        sqlx::query("DELETE FROM \"_cdc_schema_metadata\" WHERE table_name = ?")
+            .bind(table)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                Error::Generic(anyhow::anyhow!(
+                    "Failed to delete old schema metadata: {}",
+                    e
+                ))
+            })?;
+
+        // Insert new metadata
+        for (column_name, data_type) in columns {
+            sqlx::query(
+                "INSERT INTO \"_cdc_schema_metadata\"
+                 (table_name, column_name, data_type, last_updated)
+                 VALUES (?, ?, ?, CURRENT_TIMESTAMP)",
+            )
+            .bind(table)
+            .bind(column_name)
+            .bind(data_type)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to insert schema metadata: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a new table with columns inferred from data
+    async fn create_table(
+        &self,
+        pool: &SqlitePool,
+        table: &str,
+        data: &HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        let table_quoted = Self::quote_identifier(table);
+
+        // Build column definitions
+        let mut column_defs = Vec::new();
+        let mut column_types = HashMap::new();
+
+        for (col_name, col_value) in data {
+            let col_type = Self::infer_sqlite_type(col_value);
+            let col_quoted = Self::quote_identifier(col_name);
+
+            // Check for id column (case-insensitive) to set as PRIMARY KEY
+            if col_name.to_lowercase() == "id" {
+                column_defs.push(format!("{} {} PRIMARY KEY", col_quoted, col_type));
+            } else {
+                column_defs.push(format!("{} {}", col_quoted, col_type));
+            }
+            column_types.insert(col_name.clone(), col_type);
+        }
+
+        let columns_str = column_defs.join(", ");
+        let query = format!("CREATE TABLE {} ({})", table_quoted, columns_str);
+
+        info!("Creating table: {}", query);
+        sqlx::query(&query)
+            .execute(pool)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to create table: {}", e)))?;
+
+        // Update metadata
+        self.update_schema_metadata(pool, table, &column_types)
+            .await?;
+
+        info!("Table {} created successfully", table);
+        Ok(())
+    }
+
+    /// Add new columns to an existing table
+    async fn add_columns(
+        &self,
+        pool: &SqlitePool,
+        table: &str,
+        new_columns: Vec<(String, String)>,
+    ) -> Result<()> {
+        if new_columns.is_empty() {
+            return Ok(());
+        }
+
+        let table_quoted = Self::quote_identifier(table);
+
+        for (col_name, col_type) in &new_columns {
+            let col_quoted = Self::quote_identifier(col_name);
+            let query = format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table_quoted, col_quoted, col_type
+            );
+
+            info!("Adding column: {}", query);
+            sqlx::query(&query).execute(pool).await.map_err(|e| {
+                Error::Generic(anyhow::anyhow!("Failed to add column {}: {}", col_name, e))
+            })?;
+        }
+
+        // Update metadata with new columns
+        let mut current_schema = self.get_table_schema(pool, table).await?;
+        let added = new_columns.len();
+        for (col_name, col_type) in new_columns {
+            current_schema.insert(col_name.to_lowercase(), col_type);
+        }
+        self.update_schema_metadata(pool, table, &current_schema)
+            .await?;
+
+        info!("Added {} new column(s) to table {}", added, table);
+        Ok(())
+    }
+
+    /// Ensure table exists and has all required columns
+    async fn ensure_table_exists(&self, pool: &SqlitePool, record: &DataRecord) -> Result<()> {
+        let table = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+
+        // Parse record data
+        let data = record
+            .parse_record()
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        // Check if table exists
+        let exists = self.table_exists(pool, &table).await?;
+
+        if !exists {
+            // Table doesn't exist
+            if self.config.auto_create_tables {
+                info!("Table {} does not exist, creating it", table);
+                self.create_table(pool, &table, &data).await?;
+            } else {
+                return Err(Error::Generic(anyhow::anyhow!(
+                    "Table {} does not exist and auto_create_tables is disabled",
+                    table
+                )));
+            }
+        } else {
+            // Table exists, check for missing columns
+            if self.config.auto_add_columns {
+                let current_schema = self.get_table_schema(pool, &table).await?;
+                let mut missing_columns = Vec::new();
+
+                for (col_name, col_value) in &data {
+                    if !current_schema.contains_key(&col_name.to_lowercase()) {
+                        let col_type = Self::infer_sqlite_type(col_value);
+                        missing_columns.push((col_name.clone(), col_type));
+                    }
+                }
+
+                if !missing_columns.is_empty() {
+                    info!(
+                        "Detected {} missing column(s) in table {}",
+                        missing_columns.len(),
+                        table
+                    );
+                    self.add_columns(pool, &table, missing_columns).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn insert_record<'e, E>(&self, executor: E, record: &DataRecord) -> Result<()>
+    where
+        E: sqlx::SqliteExecutor<'e>,
+    {
+        let table_name_str = record
+            .table_name()
+            .ok_or_else(|| Error::Generic(anyhow::anyhow!("No table_name in metadata")))?;
+        let table_name = Self::quote_identifier(&table_name_str);
+
+        // Parse record data
+        let data = record
+            .parse_record()
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to parse record: {}", e)))?;
+
+        let operation = record.operation();
+        debug!("Executing operation {:?} on {}", operation, table_name);
+
+        match operation {
+            Operation::Insert | Operation::Snapshot | Operation::Update => {
+                let mut final_data = data.clone();
+
+                // If it's an UPDATE, merge changes
+                if matches!(operation, Operation::Update) {
+                    if let Ok(Some(changes)) = record.parse_changes() {
+                        debug!("Merging {} changed fields for UPDATE", changes.len());
+                        for (key, value) in changes {
+                            final_data.insert(key, value);
+                        }
+                    }
+                }
+
+                // Extract column names and values
+                let mut columns = Vec::new();
+                let mut placeholders = Vec::new();
+                let mut values: Vec<&serde_json::Value> = Vec::new();
+                let mut update_sets = Vec::new();
+                let mut pk_column = None;
+
+                for (key, value) in final_data.iter() {
+                    let quoted = Self::quote_identifier(key);
+                    columns.push(quoted.clone());
+                    placeholders.push("?");
+                    values.push(value);
+
+                    if key.to_lowercase() == "id" {
+                        pk_column = Some(quoted);
+                    } else {
+                        update_sets.push(format!("{} = excluded.{}", quoted, quoted));
+                    }
+                }
+
+                let pk_column = pk_column.unwrap_or_else(|| Self::quote_identifier("id"));
+                let columns_str = columns.join(", ");
+                let placeholders_str = placeholders.join(", ");
+
+                let query = match self.config.conflict_resolution {
+                    // Nothing besides the key to update
+                    ConflictResolution::Upsert if update_sets.is_empty() => {
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO NOTHING",
+                            table_name, columns_str, placeholders_str, pk_column
+                        )
+                    }
+                    ConflictResolution::Upsert => {
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO UPDATE SET {}",
+                            table_name,
+                            columns_str,
+                            placeholders_str,
+                            pk_column,
+                            update_sets.join(", ")
+                        )
+                    }
+                    ConflictResolution::Ignore => {
+                        format!(
+                            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO NOTHING",
+                            table_name, columns_str, placeholders_str, pk_column
+                        )
+                    }
+                    ConflictResolution::Replace => {
+                        format!(
+                            "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+                            table_name, columns_str, placeholders_str
+                        )
+                    }
+                };
+
+                debug!("Executing upsert query: {}", query);
+                self.execute_query(executor, &query, &values).await?;
+            }
+            Operation::Delete => {
+                // For DELETE, extract ID and delete
+                let pk_value = data.get("id").or_else(|| data.get("Id"));
+
+                if let Some(val) = pk_value {
+                    let pk_column = Self::quote_identifier("id");
+                    let query = format!("DELETE FROM {} WHERE {} = ?", table_name, pk_column);
+                    debug!("Executing delete query: {}", query);
+                    self.execute_query(executor, &query, &[val]).await?;
+                } else {
+                    warn!(
+                        "Cannot delete record without ID column in table {}",
+                        table_name
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn execute_query<'e, E>(
+        &self,
+        executor: E,
+        query: &str,
+        values: &[&serde_json::Value],
+    ) -> Result<()>
+    where
+        E: sqlx::SqliteExecutor<'e>,
+    {
+        let mut query_builder = sqlx::query(query);
+
+        for value in values {
+            match *value {
+                serde_json::Value::Number(n) => {
+                    if let Some(i) = n.as_i64() {
+                        query_builder = query_builder.bind(i);
+                    } else {
+                        // u64 beyond i64::MAX has no exact SQLite representation
+                        query_builder = query_builder.bind(n.as_f64());
+                    }
+                }
+                serde_json::Value::String(s) => {
+                    query_builder = query_builder.bind(s.clone());
+                }
+                serde_json::Value::Bool(b) => {
+                    query_builder = query_builder.bind(*b);
+                }
+                serde_json::Value::Null => {
+                    query_builder = query_builder.bind(None::<String>);
+                }
+                // Arrays and objects are stored as JSON text, readable with SQLite's json functions
+                _ => {
+                    query_builder = query_builder.bind(value.to_string());
+                }
+            }
+        }
+
+        query_builder
+            .execute(executor)
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Database error: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Destination for SqliteDestination {
+    async fn connect(&mut self) -> Result<()> {
+        info!("Opening SQLite database: {}", self.config.path);
+
+        // WAL lets readers query the file while batches are being written
+        let options = SqliteConnectOptions::new()
+            .filename(&self.config.path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::from_millis(self.config.busy_timeout_ms));
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(self.config.max_connections)
+            .connect_with(options)
+            .await
+            .map_err(|e| Error::Connection(format!("Failed to open SQLite database: {}", e)))?;
+
+        info!("Opened SQLite database successfully");
+
+        // Ensure schema metadata table exists
+        self.ensure_schema_metadata_table(&pool).await?;
+
+        self.pool = Some(pool);
+        self.status.connected = true;
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        info!("Closing SQLite database");
+
+        if let Some(pool) = self.pool.take() {
+            pool.close().await;
+        }
+
+        self.status.connected = false;
+        info!("Closed SQLite database");
+
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status.connected
+    }
+
+    async fn write(&mut self, record: DataRecord) -> Result<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        // Ensure table exists and has all required columns
+        self.ensure_table_exists(pool, &record).await?;
+
+        match self.insert_record(pool, &record).await {
+            Ok(_) => {
+                self.status.records_written += 1;
+                Ok(())
+            }
+            Err(e) => {
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+                error!("Failed to write record: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_batch(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| Error::Connection("Not connected".to_string()))?;
+
+        // Ensure all unique tables exist before processing batch
+        let mut processed_tables = std::collections::HashSet::new();
+        for record in &records {
+            if let Some(table_name) = record.table_name() {
+                if !processed_tables.contains(&table_name) {
+                    self.ensure_table_exists(pool, record).await?;
+                    processed_tables.insert(table_name);
+                }
+            }
+        }
+
+        let mut transaction = pool
+            .begin()
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to begin transaction: {}", e)))?;
+
+        for record in &records {
+            if let Err(e) = self.insert_record(&mut *transaction, record).await {
+                let table_name = record.table_name().unwrap_or_else(|| "unknown".to_string());
+                let operation = record.operation();
+                error!(
+                    "Failed to write record to table '{}' (operation: {:?}): {}",
+                    table_name, operation, e
+                );
+                self.status.errors += 1;
+                self.status.last_error = Some(e.to_string());
+
+                transaction
+                    .rollback()
+                    .await
+                    .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to rollback: {}", e)))?;
+
+                return Err(e);
+            }
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| Error::Generic(anyhow::anyhow!("Failed to commit transaction: {}", e)))?;
+
+        self.status.records_written += records.len() as u64;
+        info!("Successfully wrote batch of {} records", records.len());
+
+        Ok(())
+    }
+
+    fn status(&self) -> DestinationStatus {
+        self.status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdc_core::testing::sample_record;
+    use serde_json::json;
+
+    async fn connected(dir: &tempfile::TempDir) -> SqliteDestination {
+        let mut destination = SqliteDestination::new(SqliteConfig {
+            path: dir.path().join("cdc.db").to_string_lossy().into_owned(),
+            ..Default::default()
+        });
+        destination.connect().await.unwrap();
+        destination
+    }
+
+    fn record(action: &str, row: serde_json::Value) -> DataRecord {
+        DataRecord::new(
+            row.to_string(),
+            json!({ "table_name": "users" }).to_string(),
+            action.to_string(),
+            None,
+        )
+    }
+
+    async fn rows(destination: &SqliteDestination) -> Vec<(i64, Option<String>)> {
+        sqlx::query_as("SELECT id, name FROM users ORDER BY id")
+            .fetch_all(destination.pool.as_ref().unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(SqliteDestination::quote_identifier("users"), "\"users\"");
+        assert_eq!(
+            SqliteDestination::quote_identifier("odd\"name"),
+            "\"odd\"\"name\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_uses_wal_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = connected(&dir).await;
+
+        let (mode,): (String,) = sqlx::query_as("PRAGMA journal_mode")
+            .fetch_one(destination.pool.as_ref().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir).await;
+
+        destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 1, "name": "a" })),
+                record("insert", json!({ "id": 2, "name": "b" })),
+                DataRecord::new(
+                    json!({ "id": 1, "name": "a" }).to_string(),
+                    json!({ "table_name": "users" }).to_string(),
+                    "update".to_string(),
+                    Some(json!({ "name": "c" }).to_string()),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            rows(&destination).await,
+            vec![(1, Some("c".to_string())), (2, Some("b".to_string()))]
+        );
+
+        destination
+            .write(record("delete", json!({ "id": 2 })))
+            .await
+            .unwrap();
+        assert_eq!(rows(&destination).await, vec![(1, Some("c".to_string()))]);
+        assert_eq!(destination.status().records_written, 4);
+    }
+
+    #[tokio::test]
+    async fn test_adds_missing_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir).await;
+
+        destination.write(sample_record("users", 1)).await.unwrap();
+        destination
+            .write(record(
+                "insert",
+                json!({ "id": 2, "name": "b", "tags": ["x"] }),
+            ))
+            .await
+            .unwrap();
+
+        let pool = destination.pool.as_ref().unwrap();
+        let schema = destination.get_table_schema(pool, "users").await.unwrap();
+        assert_eq!(schema["name"], "TEXT");
+        assert_eq!(schema["tags"], "TEXT");
+
+        let (tags,): (String,) = sqlx::query_as("SELECT tags FROM users WHERE id = 2")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(tags, "[\"x\"]");
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut destination = connected(&dir).await;
+        destination
+            .write(record("insert", json!({ "id": 1, "name": "a" })))
+            .await
+            .unwrap();
+
+        // A string in the INTEGER PRIMARY KEY column is rejected by SQLite
+        let result = destination
+            .write_batch(vec![
+                record("insert", json!({ "id": 2, "name": "b" })),
+                record("insert", json!({ "id": "x", "name": "c" })),
+            ])
+            .await;
+        assert!(result.is_err());
+        assert_eq!(rows(&destination).await, vec![(1, Some("a".to_string()))]);
+        assert_eq!(destination.status().errors, 1);
+    }
+}
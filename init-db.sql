@@ -70,6 +70,28 @@ CREATE INDEX IF NOT EXISTS idx_flows_name ON flows(name);
 CREATE INDEX IF NOT EXISTS idx_flows_connector ON flows(connector_name);
 CREATE INDEX IF NOT EXISTS idx_flows_auto_start ON flows(auto_start);
 
+-- Append-only revision history of connector, destination and flow configs
+CREATE TABLE IF NOT EXISTS config_history (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(32) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    revision BIGINT NOT NULL,
+    operation VARCHAR(32) NOT NULL,
+    snapshot JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, name, revision)
+);
+
+-- Flow ownership when several instances share this database
+CREATE TABLE IF NOT EXISTS flow_leases (
+    flow_name VARCHAR(255) PRIMARY KEY,
+    owner VARCHAR(255) NOT NULL,
+    status JSONB,
+    acquired_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    renewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
 -- Trigger to update updated_at timestamp
 CREATE OR REPLACE FUNCTION update_updated_at_column()
 RETURNS TRIGGER AS $$
@@ -79,6 +79,12 @@ export interface FlowConfigEntry {
   messages_received?: number;
 }
 
+export interface TypeInfo {
+  name: string;
+  source: { kind: "builtin" } | { kind: "dynamic"; path: string };
+  config_schema: any;
+}
+
 export const api = {
   getHealth: async (): Promise<HealthResponse> => {
     const response = await axios.get<ApiResponse<HealthResponse>>(
@@ -101,6 +107,21 @@ export const api = {
     checkSuccess(response.data);
   },
 
+  // Types
+  listConnectorTypes: async (): Promise<TypeInfo[]> => {
+    const response = await axios.get<ApiResponse<TypeInfo[]>>(
+      `${API_BASE_URL}/api/types/connectors`
+    );
+    return extractData(response.data);
+  },
+
+  listDestinationTypes: async (): Promise<TypeInfo[]> => {
+    const response = await axios.get<ApiResponse<TypeInfo[]>>(
+      `${API_BASE_URL}/api/types/destinations`
+    );
+    return extractData(response.data);
+  },
+
   // Connectors
   listConnectors: async (): Promise<ConnectorConfigEntry[]> => {
     const response = await axios.get<ApiResponse<ConnectorConfigEntry[]>>(